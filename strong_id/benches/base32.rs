@@ -0,0 +1,34 @@
+//! Benchmarks for the base32 encoding of suffixes, run with `cargo bench -p strong_id --bench base32`
+//!
+//! Inputs of up to 16 bytes are encoded and decoded from a single integer, longer inputs 5 bytes at
+//! a time, so lengths on both sides of 16 bytes are measured.
+
+// benchmarks are run with a recent toolchain, rather than the MSRV
+#![allow(clippy::incompatible_msrv)]
+
+mod timing;
+
+use std::hint::black_box;
+use strong_id::{base32, Id};
+use timing::bench;
+
+fn main() {
+	for len in [2, 8, 16, 17, 32] {
+		let src: Vec<u8> = (0..len as u8).map(|byte| byte.wrapping_mul(0x9D)).collect();
+		let encoded = base32::encode(&src);
+
+		bench(&format!("encode/{len} bytes"), || {
+			let mut dst = [0u8; 52];
+			base32::encode_to_slice(black_box(&src), &mut dst[..encoded.len()]).map(|_| dst)
+		});
+		bench(&format!("decode/{len} bytes"), || {
+			let mut dst = [0u8; 32];
+			base32::decode(black_box(encoded.as_str()), &mut dst[..len]).map(|_| dst)
+		});
+	}
+
+	let value = u128::MAX / 3;
+	let encoded = value.encode();
+	bench("u128/encode", || black_box(value).encode());
+	bench("u128/decode", || u128::decode(black_box(encoded.as_str())));
+}
@@ -0,0 +1,44 @@
+//! Benchmarks for parsing IDs, run with `cargo bench -p strong_id --bench parse`
+//!
+//! A plain timing loop is used rather than a benchmark framework, so that benchmarking doesn't add
+//! dependencies.
+
+// benchmarks are run with a recent toolchain, rather than the MSRV
+#![allow(clippy::incompatible_msrv)]
+
+mod timing;
+
+use std::hint::black_box;
+use strong_id::{strong_id, DynamicStrongId};
+use timing::bench;
+
+strong_id!(pub struct UserId(u128 => "user"));
+
+fn main() {
+	let value = u128::MAX / 3;
+	let generated = UserId::from(value).to_string();
+	let long = format!(
+		"organizationmemberinvitation_{}",
+		UserId::from(value).suffix_encoded()
+	);
+	let invalid = format!(
+		"organizationMemberinvitation_{}",
+		UserId::from(value).suffix_encoded()
+	);
+
+	bench("generated", || {
+		black_box(generated.as_str()).parse::<UserId>()
+	});
+	bench("generated/wrong prefix", || {
+		black_box(long.as_str()).parse::<UserId>()
+	});
+	bench("dynamic", || {
+		black_box(generated.as_str()).parse::<DynamicStrongId<u128>>()
+	});
+	bench("dynamic/long prefix", || {
+		black_box(long.as_str()).parse::<DynamicStrongId<u128>>()
+	});
+	bench("dynamic/invalid prefix", || {
+		black_box(invalid.as_str()).parse::<DynamicStrongId<u128>>()
+	});
+}
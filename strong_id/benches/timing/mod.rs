@@ -0,0 +1,24 @@
+//! The timing loop shared by the benchmarks
+
+use std::{hint::black_box, time::Instant};
+
+const ITERATIONS: u32 = 1_000_000;
+const SAMPLES: u32 = 10;
+
+/// Time `f`, reporting the fastest of a number of samples to reduce noise
+pub fn bench<T>(name: &str, f: impl Fn() -> T) {
+	for _ in 0..ITERATIONS / 10 {
+		black_box(f());
+	}
+
+	let mut fastest = f64::MAX;
+	for _ in 0..SAMPLES {
+		let start = Instant::now();
+		for _ in 0..ITERATIONS {
+			black_box(f());
+		}
+		fastest = fastest.min(start.elapsed().as_nanos() as f64 / ITERATIONS as f64);
+	}
+
+	println!("{name:<40} {fastest:>8.1} ns/iter");
+}
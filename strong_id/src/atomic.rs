@@ -0,0 +1,234 @@
+use crate::{Error, Id, StaticPrefix};
+use core::{
+	marker::PhantomData,
+	num::Wrapping,
+	sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+/// Unsigned integers which can be assigned by an [`AtomicIdCell`]
+pub trait AtomicValue: Id + Sized {
+	/// The largest value of the type
+	const MAX: u64;
+
+	/// The value of a counter, which is at most [`MAX`](Self::MAX)
+	fn from_counter(counter: u64) -> Self;
+}
+
+macro_rules! impl_atomic_value {
+	($($t:ty),*) => {
+		$(
+			impl AtomicValue for $t {
+				const MAX: u64 = <$t>::MAX as u64;
+
+				fn from_counter(counter: u64) -> Self {
+					counter as $t
+				}
+			}
+		)*
+	};
+}
+
+impl_atomic_value!(u8, u16, u32, u64, usize);
+
+impl<T: AtomicValue> AtomicValue for Wrapping<T> {
+	const MAX: u64 = T::MAX;
+
+	fn from_counter(counter: u64) -> Self {
+		Wrapping(T::from_counter(counter))
+	}
+}
+
+/// What an [`AtomicIdCell`] does once it has assigned the largest value of the inner type
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Overflow {
+	/// Fail with [`Error::OutOfRange`], so that IDs are never reused
+	Checked,
+	/// Wrap around to zero
+	Wrapping,
+}
+
+/// Assigns sequential IDs backed by unsigned integers from any number of threads, without locking
+///
+/// IDs are assigned with an atomic increment, so a cell can be shared as a `static`. IDs are unique
+/// and increase for each thread, but threads may observe them in a different order than they were
+/// assigned.
+///
+/// ```rust
+/// use strong_id::{strong_id, AtomicIdCell};
+///
+/// strong_id!(pub struct JobId(u32 => "job"));
+///
+/// static JOB_IDS: AtomicIdCell<JobId> = AtomicIdCell::new();
+///
+/// assert_eq!(JOB_IDS.next(), Ok(JobId::from(0)));
+/// assert_eq!(JOB_IDS.next(), Ok(JobId::from(1)));
+/// ```
+///
+/// By default, a cell fails once it has assigned every value of the inner type. Cells for IDs which
+/// may be reused, e.g. `core::num::Wrapping` IDs of short-lived sessions, wrap around instead.
+///
+/// ```rust
+/// use strong_id::{strong_id, AtomicIdCell, Error, Overflow};
+///
+/// strong_id!(pub struct SlotId(u8 => "slot"));
+///
+/// let checked = AtomicIdCell::<SlotId>::starting_at(255);
+/// assert_eq!(checked.next(), Ok(SlotId::from(255)));
+/// assert_eq!(checked.next(), Err(Error::OutOfRange));
+///
+/// let wrapping = AtomicIdCell::<SlotId>::starting_at(255).overflow(Overflow::Wrapping);
+/// assert_eq!(wrapping.next(), Ok(SlotId::from(255)));
+/// assert_eq!(wrapping.next(), Ok(SlotId::from(0)));
+/// ```
+pub struct AtomicIdCell<T> {
+	next: AtomicU64,
+	/// Set once the largest value has been assigned by a checked cell, as the counter can't move
+	/// past `u64::MAX`
+	exhausted: AtomicBool,
+	overflow: Overflow,
+	_marker: PhantomData<fn() -> T>,
+}
+
+impl<T> AtomicIdCell<T>
+where
+	T: StaticPrefix + From<<T as StaticPrefix>::Inner>,
+	T::Inner: AtomicValue,
+{
+	/// Create a cell whose first ID is zero
+	pub const fn new() -> Self {
+		Self::starting_at(0)
+	}
+
+	/// Create a cell whose first ID is `next`, e.g. to continue after the largest persisted ID
+	pub const fn starting_at(next: u64) -> Self {
+		Self {
+			next: AtomicU64::new(next),
+			exhausted: AtomicBool::new(false),
+			overflow: Overflow::Checked,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Set what the cell does once it has assigned the largest value of the inner type
+	pub const fn overflow(mut self, overflow: Overflow) -> Self {
+		self.overflow = overflow;
+		self
+	}
+
+	/// Assign the next ID
+	///
+	/// Fails with [`Error::OutOfRange`] once every value of the inner type has been assigned, or
+	/// if the cell started past the largest value, unless the cell wraps around.
+	pub fn next(&self) -> Result<T, Error> {
+		let max = <T::Inner as AtomicValue>::MAX;
+
+		let counter = match self.overflow {
+			// the largest value of every type is all ones, so the lower bits of the counter wrap
+			// around along with it
+			Overflow::Wrapping => self.next.fetch_add(1, Ordering::Relaxed) & max,
+			Overflow::Checked => {
+				match self
+					.next
+					.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |next| {
+						if next < max {
+							Some(next + 1)
+						} else {
+							None
+						}
+					}) {
+					Ok(counter) => counter,
+					// the counter stays at the largest value, which is assigned exactly once
+					Err(counter)
+						if counter == max && !self.exhausted.swap(true, Ordering::Relaxed) =>
+					{
+						counter
+					}
+					Err(_) => return Err(Error::OutOfRange),
+				}
+			}
+		};
+
+		Ok(T::from(T::Inner::from_counter(counter)))
+	}
+}
+
+impl<T> Default for AtomicIdCell<T>
+where
+	T: StaticPrefix + From<<T as StaticPrefix>::Inner>,
+	T::Inner: AtomicValue,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T> core::fmt::Debug for AtomicIdCell<T> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("AtomicIdCell")
+			.field("next", &self.next)
+			.field("overflow", &self.overflow)
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{AtomicIdCell, Overflow};
+	use crate::{strong_id, Error};
+	use core::num::Wrapping;
+	use std::{collections::HashSet, sync::Arc, thread};
+
+	strong_id!(pub struct TicketId(u64 => "ticket"));
+	strong_id!(pub struct SessionId(Wrapping<u16> => "session"));
+
+	#[test]
+	fn sequential() {
+		let cell = AtomicIdCell::<TicketId>::starting_at(3203);
+		assert_eq!(cell.next(), Ok(TicketId::from(3203)));
+		assert_eq!(cell.next().unwrap().to_string(), "ticket_0000000000344");
+
+		let cell = AtomicIdCell::<TicketId>::starting_at(u64::MAX);
+		assert_eq!(cell.next(), Ok(TicketId::from(u64::MAX)));
+		assert_eq!(cell.next(), Err(Error::OutOfRange));
+		assert_eq!(cell.next(), Err(Error::OutOfRange));
+	}
+
+	#[test]
+	fn wrapping() {
+		let cell =
+			AtomicIdCell::<SessionId>::starting_at(u16::MAX as u64).overflow(Overflow::Wrapping);
+		assert_eq!(cell.next(), Ok(SessionId::from(Wrapping(u16::MAX))));
+		assert_eq!(cell.next(), Ok(SessionId::from(Wrapping(0))));
+		assert_eq!(SessionId::from(Wrapping(3203)).to_string(), "session_0343");
+		assert_eq!(
+			"session_0343".parse::<SessionId>(),
+			Ok(SessionId::from(Wrapping(3203)))
+		);
+
+		let cell = AtomicIdCell::<SessionId>::starting_at(u16::MAX as u64 + 1);
+		assert_eq!(cell.next(), Err(Error::OutOfRange));
+
+		let cell = AtomicIdCell::<SessionId>::starting_at(u16::MAX as u64 + 1)
+			.overflow(Overflow::Wrapping);
+		assert_eq!(cell.next(), Ok(SessionId::from(Wrapping(0))));
+	}
+
+	#[test]
+	fn concurrent() {
+		let cell = Arc::new(AtomicIdCell::<TicketId>::new());
+		let handles = (0..4)
+			.map(|_| {
+				let cell = Arc::clone(&cell);
+				thread::spawn(move || (0..1000).map(|_| cell.next().unwrap()).collect::<Vec<_>>())
+			})
+			.collect::<Vec<_>>();
+
+		let mut ids = HashSet::new();
+		for handle in handles {
+			let assigned = handle.join().unwrap();
+			assert!(assigned.windows(2).all(|pair| pair[0] < pair[1]));
+			ids.extend(assigned);
+		}
+		assert_eq!(ids.len(), 4000);
+	}
+}
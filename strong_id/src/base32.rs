@@ -1,14 +1,44 @@
-use bitvec::prelude::*;
-use std::iter::repeat;
-use thiserror::Error;
+//! The base32 encoding used for the suffixes of IDs
+//!
+//! Bytes are encoded big-endian using the lowercase [Crockford](https://www.crockford.com/base32.html)
+//! alphabet, `0123456789abcdefghjkmnpqrstvwxyz`, without check symbols. The input is left-padded
+//! with zero bits to a multiple of 5 bits, so the encoding of a fixed number of bytes always has the
+//! same length and sorts in the same order as the bytes.
+//!
+//! This is the same encoding used by [TypeID](https://github.com/jetpack-io/typeid) suffixes, and
+//! can be used to encode auxiliary values consistently with IDs.
+//!
+//! ```rust
+//! use strong_id::base32;
+//!
+//! assert_eq!(base32::encoded_len(2), 4);
+//! assert_eq!(base32::encode(&[0x0c, 0x83]), "0343");
+//!
+//! let mut out = [0u8; 2];
+//! base32::decode("0343", &mut out).unwrap();
+//! assert_eq!(out, [0x0c, 0x83]);
+//! ```
+//!
+//! With the `no_panic` feature, the link step of optimized builds fails if the slice based
+//! encoder and decoder, which back [`encode_to_slice`], [`decode`] and [`decode_array`] as well as
+//! the base32 [`Encoding`](crate::Encoding)s of IDs, could panic. [`encode`] allocates and is not
+//! covered, nor is anything outside of this module, such as prefix validation, the other
+//! encodings, or generating and (de)serializing IDs. The feature needs Rust 1.71, above the 1.60
+//! this crate otherwise supports.
 
-const ALPHABET: [u8; 32] = [
+use core::fmt::{Display, Formatter};
+
+pub(crate) const ALPHABET: [u8; 32] = [
 	b'0', b'1', b'2', b'3', b'4', b'5', b'6', b'7', b'8', b'9', b'a', b'b', b'c', b'd', b'e', b'f',
 	b'g', b'h', b'j', b'k', b'm', b'n', b'p', b'q', b'r', b's', b't', b'v', b'w', b'x', b'y', b'z',
 ];
 
+/// Aligns a table to a cache line, so that lookups don't straddle two lines
+#[repr(align(64))]
+struct CacheAligned<T>(T);
+
 #[rustfmt::skip]
-const ALPHABET_DECODE_MAP: [u8; 256] = [
+const ALPHABET_DECODE_MAP: CacheAligned<[u8; 256]> = CacheAligned([
     0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
     0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
     0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
@@ -35,71 +65,302 @@ const ALPHABET_DECODE_MAP: [u8; 256] = [
     0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
     0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
     0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
-];
+]);
+
+/// Whether the byte is part of the alphabet
+pub const fn is_valid_byte(byte: u8) -> bool {
+	ALPHABET_DECODE_MAP.0[byte as usize] != 0xFF
+}
+
+/// The length of the encoding of `byte_len` bytes
+///
+/// ```rust
+/// use strong_id::base32::encoded_len;
+///
+/// assert_eq!(encoded_len(2), 4);
+/// assert_eq!(encoded_len(16), 26);
+/// ```
+pub const fn encoded_len(byte_len: usize) -> usize {
+	let bits = byte_len * 8;
+	((bits + 5) - (bits + 5) % 5) / 5
+}
+
+const fn pad_bits_len(byte_len: usize) -> usize {
+	encoded_len(byte_len) * 5 - byte_len * 8
+}
 
-pub const fn encodable_bits<T>() -> usize {
-	let byte_len = core::mem::size_of::<T>();
-	let len = byte_len * 8;
-	(len + 5) - ((len + 5) % 5)
+/// Encode bytes into a string
+///
+/// ```rust
+/// use strong_id::base32::encode;
+///
+/// assert_eq!(encode(&[0xff; 2]), "1zzz");
+/// ```
+pub fn encode(src: &[u8]) -> String {
+	let mut out = vec![0u8; encoded_len(src.len())];
+	// `out` is of the encoded length, which is the only length encoding succeeds for
+	let _ = encode_alphabet(src, &mut out, &ALPHABET);
+	// the alphabet is ascii
+	unsafe { String::from_utf8_unchecked(out) }
 }
 
-pub const fn encoded_len<T>() -> usize {
-	encodable_bits::<T>() / 5
+/// Encode bytes into a slice of exactly [`encoded_len`] bytes, without allocating
+///
+/// ```rust
+/// use strong_id::base32::{encode_to_slice, Base32Error};
+///
+/// let mut out = [0u8; 4];
+/// encode_to_slice(&[0x0c, 0x83], &mut out).unwrap();
+/// assert_eq!(&out, b"0343");
+///
+/// assert_eq!(
+///     encode_to_slice(&[0x0c, 0x83], &mut [0u8; 3]),
+///     Err(Base32Error::InvalidOutBytesSize(4, 3))
+/// );
+/// ```
+#[cfg_attr(all(feature = "no_panic", not(debug_assertions)), no_panic::no_panic)]
+pub fn encode_to_slice(src: &[u8], dst: &mut [u8]) -> Result<(), Base32Error> {
+	encode_alphabet(src, dst, &ALPHABET)
 }
 
-const fn pad_bits_len(bytes: &[u8]) -> usize {
-	let bits = bytes.len() * 8;
-	((bits + 5) - (bits + 5) % 5) - bits
+/// Encode into a slice of exactly [`encoded_len`] bytes, using the given symbols
+#[inline]
+#[cfg_attr(all(feature = "no_panic", not(debug_assertions)), no_panic::no_panic)]
+pub(crate) fn encode_alphabet(
+	src: &[u8],
+	dst: &mut [u8],
+	symbols: &[u8; 32],
+) -> Result<(), Base32Error> {
+	let expected = encoded_len(src.len());
+	if dst.len() != expected {
+		return Err(Base32Error::InvalidOutBytesSize(expected, dst.len()));
+	}
+
+	if src.len() <= 16 {
+		encode_u128(src, dst, symbols);
+	} else {
+		encode_symbols(src, dst, symbols);
+	}
+	Ok(())
 }
 
-pub fn encode(src: &[u8], dst: &mut [u8]) {
-	let mut bv = bitvec![u8, Msb0;];
+/// Encode values of up to 128 bits, e.g. integers and UUIDs, from a single integer
+///
+/// The symbols are taken from the end of the value, so the padding bits are the zero bits above
+/// it and need no handling of their own.
+#[cfg_attr(all(feature = "no_panic", not(debug_assertions)), no_panic::no_panic)]
+fn encode_u128(src: &[u8], dst: &mut [u8], symbols: &[u8; 32]) {
+	let mut bytes = [0u8; 16];
+	let skip = bytes.len().saturating_sub(src.len());
+	for (byte, src) in bytes.iter_mut().skip(skip).zip(src) {
+		*byte = *src;
+	}
+
+	let mut value = u128::from_be_bytes(bytes);
+	for out in dst.iter_mut().rev() {
+		*out = symbols[(value & 0x1F) as usize];
+		value >>= 5;
+	}
+}
 
-	bv.extend(repeat(false).take(pad_bits_len(src)));
-	bv.extend_from_bitslice(src.view_bits::<Msb0>());
+/// Encode symbol by symbol, for values of any length
+#[cfg_attr(all(feature = "no_panic", not(debug_assertions)), no_panic::no_panic)]
+fn encode_symbols(src: &[u8], dst: &mut [u8], symbols: &[u8; 32]) {
+	// the padding bits are leading zeroes, so they're accounted for by starting with an empty
+	// buffer which already holds `pad_bits_len` bits
+	let mut buffer: u16 = 0;
+	let mut bits = pad_bits_len(src.len());
+	let mut dst = dst.iter_mut();
 
-	assert_eq!(
-		dst.len(),
-		bv.chunks(5).count(),
-		"out slice is incorrect size"
-	);
+	for byte in src {
+		buffer = (buffer << 8) | *byte as u16;
+		bits += 8;
 
-	for (i, byte) in bv.chunks(5).enumerate() {
-		let o = ALPHABET[byte.load_be::<u8>() as usize];
-		dst[i] = o;
+		while bits >= 5 {
+			bits -= 5;
+			if let Some(out) = dst.next() {
+				*out = symbols[((buffer >> bits) & 0x1F) as usize];
+			}
+		}
 	}
 }
 
 /// Errors which might occur when encoding or decoding bytes into base32
-#[derive(Error, Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq)]
 pub enum Base32Error {
 	/// No bytes were supplied
-	#[error("empty source bytes")]
 	Empty,
-	/// An invalid byte was present in the slice
-	#[error("invalid source byte")]
-	InvalidByte,
+	/// An invalid byte was present in the slice, at the given index
+	///
+	/// The character is the invalid byte, or `U+FFFD` if it isn't ascii. When decoding IDs from a
+	/// `str`, it is the invalid character. IDs which are parsed with their prefix report the index in
+	/// the complete ID, otherwise the index is in the suffix.
+	InvalidByte(usize, char),
 	/// The first byte can not be decoded
-	#[error("invalid first source byte")]
 	InvalidFirstByte,
 	/// The output slice is the wrong size and the data may not fit into it
-	#[error("out bytes slice is the wrong size. expected {0}, found {1}")]
 	InvalidOutBytesSize(usize, usize),
+	/// The check symbol does not match the decoded value, see [`Base32Check`](crate::Base32Check)
+	InvalidCheckSymbol,
+}
+
+#[cfg(not(feature = "wasm-slim"))]
+impl Display for Base32Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		match self {
+			Self::Empty => write!(f, "empty source bytes"),
+			Self::InvalidByte(index, c) => {
+				write!(f, "invalid source character `{c}` at position {index}")
+			}
+			Self::InvalidFirstByte => write!(f, "invalid first source byte"),
+			Self::InvalidOutBytesSize(expected, found) => write!(
+				f,
+				"out bytes slice is the wrong size. expected {expected}, found {found}"
+			),
+			Self::InvalidCheckSymbol => write!(f, "check symbol does not match"),
+		}
+	}
+}
+
+#[cfg(feature = "wasm-slim")]
+impl Display for Base32Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		f.write_str(match self {
+			Self::Empty => "empty source bytes",
+			Self::InvalidByte(_, _) => "invalid source byte",
+			Self::InvalidFirstByte => "invalid first source byte",
+			Self::InvalidOutBytesSize(_, _) => "out bytes slice is the wrong size",
+			Self::InvalidCheckSymbol => "check symbol does not match",
+		})
+	}
+}
+
+impl Base32Error {
+	pub(crate) const fn invalid_byte(index: usize, byte: u8) -> Self {
+		let c = if byte.is_ascii() {
+			byte as char
+		} else {
+			char::REPLACEMENT_CHARACTER
+		};
+
+		Self::InvalidByte(index, c)
+	}
+}
+
+impl std::error::Error for Base32Error {}
+
+/// Decode a string into a slice of bytes
+///
+/// The string must be exactly [`encoded_len`] of the slice's length, so the slice must be the size
+/// of the encoded value.
+///
+/// ```rust
+/// use strong_id::base32::{decode, Base32Error};
+///
+/// let mut out = [0u8; 2];
+/// decode("0343", &mut out).unwrap();
+/// assert_eq!(u16::from_be_bytes(out), 3203);
+///
+/// assert_eq!(decode("0l43", &mut out), Err(Base32Error::InvalidByte(1, 'l')));
+/// assert_eq!(decode("0343", &mut [0u8; 4]), Err(Base32Error::InvalidOutBytesSize(2, 4)));
+/// ```
+#[inline]
+pub fn decode<S: AsRef<[u8]> + ?Sized>(src: &S, dst: &mut [u8]) -> Result<(), Base32Error> {
+	decode_alphabet(src.as_ref(), dst, &ALPHABET_DECODE_MAP.0)
+}
+
+/// Decode a string into an array of bytes, in `const` contexts
+///
+/// The string must be exactly [`encoded_len`] of `N`. Unlike [`decode`], invalid characters which
+/// aren't ascii are reported as `U+FFFD`.
+///
+/// ```rust
+/// use strong_id::base32::{decode_array, Base32Error};
+///
+/// const SYSTEM_USER: u16 = match decode_array::<2>("0343") {
+///     Ok(bytes) => u16::from_be_bytes(bytes),
+///     Err(_) => panic!("invalid ID"),
+/// };
+/// assert_eq!(SYSTEM_USER, 3203);
+///
+/// assert_eq!(decode_array::<2>("0l43"), Err(Base32Error::InvalidByte(1, 'l')));
+/// assert_eq!(decode_array::<4>("0343"), Err(Base32Error::InvalidOutBytesSize(2, 4)));
+/// ```
+pub const fn decode_array<const N: usize>(src: &str) -> Result<[u8; N], Base32Error> {
+	let src = src.as_bytes();
+	if src.is_empty() {
+		return Err(Base32Error::Empty);
+	}
+
+	let pad_bits_len = pad_bits_len(N);
+	if src.len() != encoded_len(N) {
+		let chunks_len = (src.len() * 5).saturating_sub(pad_bits_len) / 8;
+		return Err(Base32Error::InvalidOutBytesSize(chunks_len, N));
+	}
+
+	let mut out = [0u8; N];
+	let mut out_index = 0;
+	let mut buffer: u16 = 0;
+	let mut bits = 0;
+
+	let mut index = 0;
+	while index < src.len() {
+		let decoded = ALPHABET_DECODE_MAP.0[src[index] as usize];
+
+		if index == 0 && decoded > 0x1F >> pad_bits_len {
+			return Err(Base32Error::InvalidFirstByte);
+		}
+		if decoded == 0xFF {
+			return Err(Base32Error::invalid_byte(index, src[index]));
+		}
+
+		// the padding bits of the first byte are known to be zero, so they can be dropped
+		let len = if index == 0 { 5 - pad_bits_len } else { 5 };
+		buffer = (buffer << len) | decoded as u16;
+		bits += len;
+
+		if bits >= 8 {
+			bits -= 8;
+			out[out_index] = (buffer >> bits) as u8;
+			out_index += 1;
+		}
+
+		index += 1;
+	}
+
+	Ok(out)
 }
 
-pub fn decode(src: &[u8], dst: &mut [u8]) -> Result<(), Base32Error> {
+/// Decode using the given map of bytes to symbol values, where `0xFF` marks invalid bytes
+#[cfg_attr(all(feature = "no_panic", not(debug_assertions)), no_panic::no_panic)]
+pub(crate) fn decode_alphabet(
+	src: &[u8],
+	dst: &mut [u8],
+	decode_map: &[u8; 256],
+) -> Result<(), Base32Error> {
 	if src.is_empty() {
 		return Err(Base32Error::Empty);
 	}
 
-	let mut bv = bitvec![u8, Msb0;];
+	let pad_bits_len = pad_bits_len(dst.len());
+
+	let decoded_bits_len = (src.len() * 5).saturating_sub(pad_bits_len);
+	let chunks_len = decoded_bits_len / 8;
+	let dst_len = dst.len();
+	let fits = src.len() == encoded_len(dst_len);
 
-	let pad_bits_len = pad_bits_len(dst);
+	if fits && dst_len <= 16 && decode_u128(src, dst, decode_map, pad_bits_len) {
+		return Ok(());
+	}
+
+	let mut buffer: u16 = 0;
+	let mut bits = 0;
+	let mut dst = dst.iter_mut();
 
-	for (i, byte) in src.iter().enumerate() {
-		let decoded = ALPHABET_DECODE_MAP[*byte as usize];
+	for (index, byte) in src.iter().enumerate() {
+		let decoded = decode_map[*byte as usize];
 
-		if i == 0 {
+		if index == 0 {
 			let max_first_byte: u8 = 0x1F >> pad_bits_len;
 			if decoded > max_first_byte {
 				return Err(Base32Error::InvalidFirstByte);
@@ -107,23 +368,175 @@ pub fn decode(src: &[u8], dst: &mut [u8]) -> Result<(), Base32Error> {
 		}
 
 		if decoded == 0xFF {
-			return Err(Base32Error::InvalidByte);
+			return Err(Base32Error::invalid_byte(index, *byte));
 		}
 
-		let bits = decoded.view_bits::<Msb0>();
-		bv.extend(&bits[3..]);
-	}
+		if !fits {
+			continue;
+		}
+
+		// the padding bits of the first byte are known to be zero, so they can be dropped
+		let len = if index == 0 { 5 - pad_bits_len } else { 5 };
+		buffer = (buffer << len) | decoded as u16;
+		bits += len;
 
-	let bv = &bv[pad_bits_len..];
+		if bits >= 8 {
+			bits -= 8;
+			if let Some(out) = dst.next() {
+				*out = (buffer >> bits) as u8;
+			}
+		}
+	}
 
-	let chunks_len = bv.chunks(8).count();
-	if dst.len() != chunks_len {
-		return Err(Base32Error::InvalidOutBytesSize(chunks_len, dst.len()));
+	if !fits {
+		return Err(Base32Error::InvalidOutBytesSize(chunks_len, dst_len));
 	}
 
-	for (i, byte) in bv.chunks(8).enumerate() {
-		dst[i] = byte.load_be::<u8>();
+	if bits > 0 {
+		if let Some(out) = dst.next() {
+			*out = (buffer & ((1 << bits) - 1)) as u8;
+		}
 	}
 
 	Ok(())
 }
+
+/// Decode values of up to 128 bits, e.g. integers and UUIDs, without branching on each symbol
+///
+/// Returns `false` without writing to `dst` if `src` is invalid, so that the symbol-by-symbol
+/// decoder can report the error.
+#[cfg_attr(all(feature = "no_panic", not(debug_assertions)), no_panic::no_panic)]
+fn decode_u128(src: &[u8], dst: &mut [u8], decode_map: &[u8; 256], pad_bits_len: usize) -> bool {
+	let mut value: u128 = 0;
+	// invalid bytes are mapped to `0xFF`, which is the only value with any of the upper bits set
+	let mut invalid = 0;
+
+	for byte in src {
+		let decoded = decode_map[*byte as usize];
+		invalid |= decoded;
+		value = (value << 5) | (decoded & 0x1F) as u128;
+	}
+
+	let max_first_byte = 0x1F >> pad_bits_len;
+	let first_byte = src.first().map_or(0, |byte| decode_map[*byte as usize]);
+	if invalid & 0xE0 != 0 || first_byte > max_first_byte {
+		return false;
+	}
+
+	// the padding bits were shifted out, so the value is in the trailing bytes
+	let bytes = value.to_be_bytes();
+	let skip = bytes.len().saturating_sub(dst.len());
+	for (out, byte) in dst.iter_mut().zip(bytes.iter().skip(skip)) {
+		*out = *byte;
+	}
+
+	true
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{
+		decode, decode_array, decode_u128, encode, encode_symbols, encode_u128, encoded_len,
+		pad_bits_len, Base32Error, ALPHABET, ALPHABET_DECODE_MAP,
+	};
+	use crate::rng::Rng;
+
+	#[test]
+	fn roundtrip() {
+		for len in 1..=20 {
+			let bytes = (0..len).map(|i| 0xff - i as u8).collect::<Vec<_>>();
+			let encoded = encode(&bytes);
+			assert_eq!(encoded.len(), encoded_len(len));
+
+			let mut decoded = vec![0u8; len];
+			decode(&encoded, &mut decoded).unwrap();
+			assert_eq!(decoded, bytes);
+		}
+	}
+
+	#[test]
+	fn decode_array_matches_decode() {
+		let mut rng = Rng::new(536);
+
+		for _ in 0..100 {
+			let mut bytes = [0u8; 16];
+			rng.fill(&mut bytes);
+			let encoded = encode(&bytes);
+			assert_eq!(decode_array::<16>(&encoded), Ok(bytes));
+			assert_eq!(
+				decode_array::<16>(&encoded[..25]).err(),
+				decode(&encoded[..25], &mut [0u8; 16]).err()
+			);
+		}
+
+		assert_eq!(decode_array::<1>(""), Err(Base32Error::Empty));
+		assert_eq!(decode_array::<1>("80"), Err(Base32Error::InvalidFirstByte));
+		assert_eq!(decode_array::<1>("7z"), Ok([0xff]));
+		assert_eq!(
+			decode_array::<2>("0\u{e9}4"),
+			Err(Base32Error::InvalidByte(1, char::REPLACEMENT_CHARACTER))
+		);
+	}
+
+	#[test]
+	fn encode_u128_matches_symbols() {
+		let mut rng = Rng::new(544);
+
+		for _ in 0..1000 {
+			let len = 1 + rng.below(16);
+			let mut bytes = vec![0u8; len];
+			rng.fill(&mut bytes);
+
+			let mut fast = vec![0u8; encoded_len(len)];
+			let mut symbols = vec![0u8; encoded_len(len)];
+			encode_u128(&bytes, &mut fast, &ALPHABET);
+			encode_symbols(&bytes, &mut symbols, &ALPHABET);
+			assert_eq!(fast, symbols);
+		}
+
+		assert_eq!(encode(&[0xff; 16]), "7zzzzzzzzzzzzzzzzzzzzzzzzz");
+		assert_eq!(encode(&[0xff; 17]), "1zzzzzzzzzzzzzzzzzzzzzzzzzzz");
+	}
+
+	#[test]
+	fn decode_u128_matches_symbols() {
+		let mut rng = Rng::new(532);
+
+		for _ in 0..1000 {
+			let len = 1 + rng.below(16);
+			let mut bytes = vec![0u8; len];
+			rng.fill(&mut bytes);
+			let encoded = encode(&bytes);
+
+			let mut decoded = vec![0u8; len];
+			assert!(decode_u128(
+				encoded.as_bytes(),
+				&mut decoded,
+				&ALPHABET_DECODE_MAP.0,
+				pad_bits_len(len)
+			));
+			assert_eq!(decoded, bytes);
+
+			// invalid input is left to the symbol-by-symbol decoder, which reports the position
+			let index = 1 + rng.below(encoded.len() - 1);
+			let mut invalid = encoded.into_bytes();
+			invalid[index] = b'u';
+			assert!(!decode_u128(
+				&invalid,
+				&mut decoded,
+				&ALPHABET_DECODE_MAP.0,
+				pad_bits_len(len)
+			));
+			assert_eq!(
+				decode(&invalid, &mut decoded),
+				Err(Base32Error::InvalidByte(index, 'u'))
+			);
+		}
+
+		let mut out = [0u8; 16];
+		assert_eq!(
+			decode("80000000000000000000000000", &mut out),
+			Err(Base32Error::InvalidFirstByte)
+		);
+	}
+}
@@ -0,0 +1,285 @@
+use crate::{Id, StrongId};
+use core::marker::PhantomData;
+
+/// A bloom filter of IDs, for answering "has this ID been seen" without storing the IDs
+///
+/// IDs are hashed by the bytes of their value, so the prefix is not part of the filter and an ID
+/// is found regardless of how it was encoded. Lookups may report false positives at around the
+/// rate the filter was created with, but never false negatives.
+///
+/// With the "serde" feature, filters can be serialized to share them between services. Filters
+/// are only compatible with filters of the same size and number of hashes.
+///
+/// ```rust
+/// use strong_id::{strong_id, IdBloom};
+///
+/// strong_id!(pub struct UserId(u64 => "user"));
+///
+/// let mut seen = IdBloom::new(1000, 0.01);
+/// seen.insert(UserId::from(3203));
+///
+/// assert!(seen.maybe_contains(UserId::from(3203)));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IdBloom<T> {
+	bits: Vec<u64>,
+	hashes: u32,
+	id: PhantomData<fn(&T)>,
+}
+
+impl<T: Id> IdBloom<T> {
+	/// Create a filter sized for `expected_items` IDs, with a false positive rate of around
+	/// `false_positive_rate` once they are inserted. Rates above 0.5 are treated as 0.5.
+	///
+	/// # Panics
+	///
+	/// Panics if `false_positive_rate` is not positive, e.g. zero or NaN, or if the filter is too
+	/// large to allocate.
+	pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+		assert!(
+			false_positive_rate > 0.0,
+			"the false positive rate of a bloom filter must be positive, not {false_positive_rate}"
+		);
+
+		let items = expected_items.max(1) as f64;
+		let rate = false_positive_rate.min(0.5);
+		let ln2 = core::f64::consts::LN_2;
+
+		// float to integer casts saturate, so a filter which is too large fails to allocate rather
+		// than wrapping around to a small one
+		let bits = (-items * rate.ln() / (ln2 * ln2)).ceil();
+		let words = ((bits / 64.0).ceil() as usize).max(1);
+		let hashes = (words as f64 * 64.0 / items * ln2).round();
+
+		Self::with_words(words, hashes as u32)
+	}
+
+	/// Create a filter of `bits` bits, rounded up to a multiple of 64, which sets `hashes` bits
+	/// for each ID
+	pub fn with_params(bits: usize, hashes: u32) -> Self {
+		Self::with_words(bits / 64 + usize::from(bits % 64 != 0), hashes)
+	}
+
+	fn with_words(words: usize, hashes: u32) -> Self {
+		Self {
+			bits: vec![0; words.max(1)],
+			hashes: hashes.max(1),
+			id: PhantomData,
+		}
+	}
+
+	/// The number of bits in the filter
+	pub fn bits(&self) -> usize {
+		self.bits.len() * 64
+	}
+
+	/// The number of bits which are set for each ID
+	pub fn hashes(&self) -> u32 {
+		self.hashes
+	}
+
+	/// Add an ID to the filter
+	pub fn insert<S: StrongId<T>>(&mut self, id: S) {
+		let (h1, h2) = hash_pair(id.id().to_bytes().as_ref());
+		let bits = self.bits() as u64;
+		for i in 0..u64::from(self.hashes) {
+			let bit = h1.wrapping_add(i.wrapping_mul(h2)) % bits;
+			self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+		}
+	}
+
+	/// Whether the ID may have been added to the filter. `false` if it definitely wasn't.
+	pub fn maybe_contains<S: StrongId<T>>(&self, id: S) -> bool {
+		let (h1, h2) = hash_pair(id.id().to_bytes().as_ref());
+		let bits = self.bits() as u64;
+		(0..u64::from(self.hashes)).all(|i| {
+			let bit = h1.wrapping_add(i.wrapping_mul(h2)) % bits;
+			self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0
+		})
+	}
+
+	/// Remove all IDs from the filter
+	pub fn clear(&mut self) {
+		self.bits.iter_mut().for_each(|word| *word = 0);
+	}
+}
+
+/// Two independent hashes of the bytes, combined to derive every bit index of an ID
+///
+/// IDs are short and fixed-size, so the bytes are folded a word at a time and mixed with the
+/// splitmix64 finalizer rather than hashed with a general-purpose hasher.
+fn hash_pair(bytes: &[u8]) -> (u64, u64) {
+	let mut h1 = 0x9e37_79b9_7f4a_7c15 ^ bytes.len() as u64;
+	let mut h2 = 0xc2b2_ae3d_27d4_eb4f ^ bytes.len() as u64;
+
+	for chunk in bytes.chunks(8) {
+		let mut word = [0u8; 8];
+		word[..chunk.len()].copy_from_slice(chunk);
+		let word = u64::from_le_bytes(word);
+
+		h1 = mix(h1 ^ word);
+		h2 = mix(h2.rotate_left(29) ^ word);
+	}
+
+	// an odd step visits distinct bits for every hash when the size is a power of two
+	(mix(h1), mix(h2) | 1)
+}
+
+fn mix(mut x: u64) -> u64 {
+	x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+	x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+	x ^ (x >> 31)
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<T> serde::Serialize for IdBloom<T> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		use serde::ser::SerializeStruct;
+
+		let mut filter = serializer.serialize_struct("IdBloom", 2)?;
+		filter.serialize_field("hashes", &self.hashes)?;
+		filter.serialize_field("bits", &self.bits)?;
+		filter.end()
+	}
+}
+
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'de, T> serde::Deserialize<'de> for IdBloom<T> {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		use serde::de::{Error, MapAccess, SeqAccess, Visitor};
+
+		struct BloomVisitor<T>(PhantomData<fn(&T)>);
+
+		impl<T> BloomVisitor<T> {
+			fn build<E: Error>(hashes: u32, bits: Vec<u64>) -> Result<IdBloom<T>, E> {
+				if hashes == 0 {
+					return Err(E::custom("a bloom filter needs at least one hash"));
+				}
+				if bits.is_empty() {
+					return Err(E::custom("a bloom filter needs at least one bit"));
+				}
+
+				Ok(IdBloom {
+					bits,
+					hashes,
+					id: PhantomData,
+				})
+			}
+		}
+
+		impl<'de, T> Visitor<'de> for BloomVisitor<T> {
+			type Value = IdBloom<T>;
+
+			fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+				f.write_str("a bloom filter with `hashes` and `bits` fields")
+			}
+
+			fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+				let hashes = seq
+					.next_element()?
+					.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+				let bits = seq
+					.next_element()?
+					.ok_or_else(|| A::Error::invalid_length(1, &self))?;
+				Self::build(hashes, bits)
+			}
+
+			fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+				let mut hashes = None;
+				let mut bits = None;
+
+				while let Some(key) = map.next_key::<String>()? {
+					match key.as_str() {
+						"hashes" => hashes = Some(map.next_value()?),
+						"bits" => bits = Some(map.next_value()?),
+						_ => return Err(A::Error::unknown_field(&key, &["hashes", "bits"])),
+					}
+				}
+
+				let hashes = hashes.ok_or_else(|| A::Error::missing_field("hashes"))?;
+				let bits = bits.ok_or_else(|| A::Error::missing_field("bits"))?;
+				Self::build(hashes, bits)
+			}
+		}
+
+		deserializer.deserialize_struct("IdBloom", &["hashes", "bits"], BloomVisitor(PhantomData))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::IdBloom;
+	use crate::strong_id;
+
+	strong_id!(pub struct UserId(u64 => "user"));
+
+	#[test]
+	fn sizing() {
+		let filter = IdBloom::<u64>::new(1000, 0.01);
+		assert_eq!(filter.bits(), 9600);
+		assert_eq!(filter.hashes(), 7);
+
+		let filter = IdBloom::<u64>::with_params(65, 0);
+		assert_eq!(filter.bits(), 128);
+		assert_eq!(filter.hashes(), 1);
+
+		let filter = IdBloom::<u64>::new(1000, 2.0);
+		assert_eq!(filter.hashes(), 1);
+	}
+
+	#[test]
+	#[should_panic(expected = "must be positive, not 0")]
+	fn zero_rate() {
+		IdBloom::<u64>::new(1000, 0.0);
+	}
+
+	#[test]
+	#[should_panic(expected = "must be positive, not -0.01")]
+	fn negative_rate() {
+		IdBloom::<u64>::new(1000, -0.01);
+	}
+
+	#[test]
+	#[should_panic(expected = "must be positive, not NaN")]
+	fn nan_rate() {
+		IdBloom::<u64>::new(1000, f64::NAN);
+	}
+
+	#[test]
+	fn membership() {
+		let mut filter = IdBloom::new(10_000, 0.01);
+		for id in (0..10_000).map(UserId::from) {
+			filter.insert(id);
+		}
+
+		assert!((0..10_000)
+			.map(UserId::from)
+			.all(|id| filter.maybe_contains(id)));
+
+		let false_positives = (10_000..110_000)
+			.map(UserId::from)
+			.filter(|id| filter.maybe_contains(id))
+			.count();
+		assert!(false_positives < 1_500, "{false_positives} false positives");
+
+		filter.clear();
+		assert!(!filter.maybe_contains(UserId::from(0)));
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn serde() {
+		let mut filter = IdBloom::with_params(128, 3);
+		filter.insert(UserId::from(3203));
+
+		let json = serde_json::to_string(&filter).unwrap();
+		let shared = serde_json::from_str::<IdBloom<u64>>(&json).unwrap();
+		assert_eq!(shared, filter);
+		assert!(shared.maybe_contains(UserId::from(3203)));
+
+		assert!(serde_json::from_str::<IdBloom<u64>>(r#"{"hashes":0,"bits":[1]}"#).is_err());
+		assert!(serde_json::from_str::<IdBloom<u64>>(r#"{"hashes":1,"bits":[]}"#).is_err());
+	}
+}
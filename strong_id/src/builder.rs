@@ -0,0 +1,253 @@
+//! Step-by-step construction of [`DynamicStrongId`]s
+//!
+//! The steps are enforced at compile time: first the prefix is chosen, then the value, and then
+//! the ID is built. Prefixes are validated when the ID is built.
+//!
+//! ```rust
+//! use strong_id::DynamicStrongId;
+//!
+//! let id = DynamicStrongId::builder().prefix("user").value(3203u16).build().unwrap();
+//! assert_eq!(id.to_string(), "user_0343");
+//!
+//! let id = DynamicStrongId::builder().plain().value(3203u16).build().unwrap();
+//! assert_eq!(id.to_string(), "0343");
+//! ```
+//!
+//! UUID-backed IDs can generate their value:
+//!
+//! ```rust
+//! # #[cfg(feature = "uuid-v4")]
+//! # {
+//! use strong_id::DynamicStrongId;
+//!
+//! let id = DynamicStrongId::builder().prefix("user").v4().build().unwrap();
+//! assert!(id.to_string().starts_with("user_"));
+//! # }
+//! ```
+
+use crate::{DynamicStrongId, Error, Id, Prefix};
+use core::marker::PhantomData;
+
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
+
+/// The prefix has not been chosen
+pub struct NeedsPrefix;
+
+/// The prefix has been chosen, but the value has not
+pub struct NeedsValue;
+
+/// The prefix and value have been chosen
+pub struct Ready<T>(T);
+
+/// Builds a [`DynamicStrongId`], see the [module documentation](self)
+#[must_use]
+pub struct DynamicStrongIdBuilder<'p, T: Id, S> {
+	prefix: Option<Prefix<'p>>,
+	state: S,
+	marker: PhantomData<T>,
+}
+
+impl<'p, T: Id> DynamicStrongId<'p, T> {
+	/// Start building an ID
+	pub fn builder() -> DynamicStrongIdBuilder<'p, T, NeedsPrefix> {
+		DynamicStrongIdBuilder {
+			prefix: None,
+			state: NeedsPrefix,
+			marker: PhantomData,
+		}
+	}
+}
+
+impl<'p, T: Id> DynamicStrongIdBuilder<'p, T, NeedsPrefix> {
+	/// Build an ID with a prefix
+	pub fn prefix<I: Into<Prefix<'p>>>(
+		self,
+		prefix: I,
+	) -> DynamicStrongIdBuilder<'p, T, NeedsValue> {
+		DynamicStrongIdBuilder {
+			prefix: Some(prefix.into()),
+			state: NeedsValue,
+			marker: PhantomData,
+		}
+	}
+
+	/// Build an ID without a prefix
+	pub fn plain(self) -> DynamicStrongIdBuilder<'p, T, NeedsValue> {
+		DynamicStrongIdBuilder {
+			prefix: None,
+			state: NeedsValue,
+			marker: PhantomData,
+		}
+	}
+}
+
+impl<'p, T: Id> DynamicStrongIdBuilder<'p, T, NeedsValue> {
+	/// Use the given value
+	pub fn value(self, value: T) -> DynamicStrongIdBuilder<'p, T, Ready<T>> {
+		DynamicStrongIdBuilder {
+			prefix: self.prefix,
+			state: Ready(value),
+			marker: PhantomData,
+		}
+	}
+}
+
+// Generators for UUID-backed IDs. Generators without a suffix use the current time where the UUID
+// version is time-based, and `_at` variants take an explicit timestamp.
+#[cfg(feature = "uuid")]
+impl<'p> DynamicStrongIdBuilder<'p, Uuid, NeedsValue> {
+	/// Use a UUID created from a u128
+	pub fn from_u128(self, v: u128) -> DynamicStrongIdBuilder<'p, Uuid, Ready<Uuid>> {
+		self.value(Uuid::from_u128(v))
+	}
+
+	#[cfg(feature = "uuid-v1")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v1")))]
+	/// Generate a v1 UUID, see [`Uuid::now_v1`]
+	pub fn v1(self, node_id: &[u8; 6]) -> DynamicStrongIdBuilder<'p, Uuid, Ready<Uuid>> {
+		self.value(Uuid::now_v1(node_id))
+	}
+
+	#[cfg(feature = "uuid-v1")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v1")))]
+	/// Generate a v1 UUID, see [`Uuid::new_v1`]
+	pub fn v1_at(
+		self,
+		ts: uuid::Timestamp,
+		node_id: &[u8; 6],
+	) -> DynamicStrongIdBuilder<'p, Uuid, Ready<Uuid>> {
+		self.value(Uuid::new_v1(ts, node_id))
+	}
+
+	#[cfg(feature = "uuid-v3")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v3")))]
+	/// Generate a v3 UUID, see [`Uuid::new_v3`]
+	pub fn v3(
+		self,
+		namespace: &Uuid,
+		name: &[u8],
+	) -> DynamicStrongIdBuilder<'p, Uuid, Ready<Uuid>> {
+		self.value(Uuid::new_v3(namespace, name))
+	}
+
+	#[cfg(feature = "uuid-v4")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v4")))]
+	/// Generate a v4 UUID, see [`Uuid::new_v4`]
+	pub fn v4(self) -> DynamicStrongIdBuilder<'p, Uuid, Ready<Uuid>> {
+		self.value(Uuid::new_v4())
+	}
+
+	#[cfg(feature = "uuid-v5")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v5")))]
+	/// Generate a v5 UUID, see [`Uuid::new_v5`]
+	pub fn v5(
+		self,
+		namespace: &Uuid,
+		name: &[u8],
+	) -> DynamicStrongIdBuilder<'p, Uuid, Ready<Uuid>> {
+		self.value(Uuid::new_v5(namespace, name))
+	}
+
+	#[cfg(feature = "uuid-v6")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v6")))]
+	/// Generate a v6 UUID, see [`Uuid::now_v6`]
+	pub fn v6(self, node_id: &[u8; 6]) -> DynamicStrongIdBuilder<'p, Uuid, Ready<Uuid>> {
+		self.value(Uuid::now_v6(node_id))
+	}
+
+	#[cfg(feature = "uuid-v6")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v6")))]
+	/// Generate a v6 UUID, see [`Uuid::new_v6`]
+	pub fn v6_at(
+		self,
+		ts: uuid::Timestamp,
+		node_id: &[u8; 6],
+	) -> DynamicStrongIdBuilder<'p, Uuid, Ready<Uuid>> {
+		self.value(Uuid::new_v6(ts, node_id))
+	}
+
+	#[cfg(feature = "uuid-v7")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v7")))]
+	/// Generate a v7 UUID, see [`Uuid::now_v7`]
+	pub fn v7(self) -> DynamicStrongIdBuilder<'p, Uuid, Ready<Uuid>> {
+		self.value(Uuid::now_v7())
+	}
+
+	#[cfg(feature = "uuid-v7")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v7")))]
+	/// Generate a v7 UUID, see [`Uuid::new_v7`]
+	pub fn v7_at(self, ts: uuid::Timestamp) -> DynamicStrongIdBuilder<'p, Uuid, Ready<Uuid>> {
+		self.value(Uuid::new_v7(ts))
+	}
+
+	#[cfg(feature = "uuid-v8")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v8")))]
+	/// Generate a v8 UUID, see [`Uuid::new_v8`]
+	pub fn v8(self, buf: [u8; 16]) -> DynamicStrongIdBuilder<'p, Uuid, Ready<Uuid>> {
+		self.value(Uuid::new_v8(buf))
+	}
+}
+
+impl<'p, T: Id> DynamicStrongIdBuilder<'p, T, Ready<T>> {
+	/// Build the ID, validating its prefix
+	pub fn build(self) -> Result<DynamicStrongId<'p, T>, Error> {
+		let Ready(value) = self.state;
+
+		match self.prefix {
+			Some(prefix) => DynamicStrongId::new(prefix, value),
+			None => Ok(DynamicStrongId::new_plain(value)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{DynamicStrongId, Error};
+
+	#[test]
+	fn build() {
+		let id = DynamicStrongId::builder()
+			.prefix("user")
+			.value(301u32)
+			.build()
+			.unwrap();
+		assert_eq!(id, DynamicStrongId::new("user", 301u32).unwrap());
+
+		let id = DynamicStrongId::builder()
+			.plain()
+			.value(301u32)
+			.build()
+			.unwrap();
+		assert_eq!(id, DynamicStrongId::new_plain(301u32));
+
+		assert_eq!(
+			DynamicStrongId::builder()
+				.prefix("User")
+				.value(301u32)
+				.build(),
+			Err(Error::IncorrectPrefixCharacter('U'))
+		);
+	}
+
+	#[cfg(feature = "uuid-v7")]
+	#[test]
+	fn build_uuid() {
+		use crate::StrongId;
+
+		let id = DynamicStrongId::builder()
+			.prefix("user")
+			.v7()
+			.build()
+			.unwrap();
+		assert_eq!(id.prefix(), Some("user"));
+		assert_eq!(id.id().get_version_num(), 7);
+
+		let id = DynamicStrongId::builder()
+			.plain()
+			.from_u128(301)
+			.build()
+			.unwrap();
+		assert_eq!(id.to_string(), "0000000000000000000000009d");
+	}
+}
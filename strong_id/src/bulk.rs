@@ -0,0 +1,134 @@
+use crate::Error;
+use core::{fmt::Display, str::FromStr};
+
+/// The error of a single item of a batch, see [`parse_many`]
+#[derive(Debug, Eq, PartialEq)]
+pub struct ItemError {
+	/// The position of the item in the batch
+	pub index: usize,
+	/// Why the item failed
+	pub error: Error,
+}
+
+impl Display for ItemError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "item {}: {}", self.index, self.error)
+	}
+}
+
+impl std::error::Error for ItemError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		Some(&self.error)
+	}
+}
+
+/// Parse a batch of IDs, returning the result of each item instead of failing the whole batch
+///
+/// The results are in the order of the input, and errors carry the index of their item, so that
+/// they can be reported after being separated from the parsed IDs.
+///
+/// ```rust
+/// use strong_id::{parse_many, strong_id};
+///
+/// strong_id!(pub struct UserId(u16 => "user"));
+///
+/// let (ids, errors): (Vec<_>, Vec<_>) = parse_many::<UserId, _, _>(["user_0343", "user_0l43"])
+///     .into_iter()
+///     .partition(Result::is_ok);
+///
+/// assert_eq!(ids, vec![Ok(UserId::from(3203))]);
+/// assert_eq!(errors[0].as_ref().unwrap_err().index, 1);
+/// ```
+pub fn parse_many<T, I, S>(values: I) -> Vec<Result<T, ItemError>>
+where
+	T: FromStr<Err = Error>,
+	I: IntoIterator<Item = S>,
+	S: AsRef<str>,
+{
+	values
+		.into_iter()
+		.enumerate()
+		.map(|(index, value)| {
+			value
+				.as_ref()
+				.parse::<T>()
+				.map_err(|error| ItemError { index, error })
+		})
+		.collect()
+}
+
+/// Encode a batch of IDs, in the order of the input
+///
+/// Encoding an ID can not fail, so unlike [`parse_many`] there are no per-item errors.
+///
+/// ```rust
+/// use strong_id::{encode_many, strong_id};
+///
+/// strong_id!(pub struct UserId(u16 => "user"));
+///
+/// let encoded = encode_many([UserId::from(3203), UserId::from(3204)]);
+/// assert_eq!(encoded, vec!["user_0343", "user_0344"]);
+/// ```
+pub fn encode_many<T, I>(ids: I) -> Vec<String>
+where
+	T: Display,
+	I: IntoIterator<Item = T>,
+{
+	ids.into_iter().map(|id| id.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{encode_many, parse_many, ItemError};
+	use crate::{strong_id, Base32Error, Error};
+
+	strong_id!(pub struct UserId(u16 => "user"));
+
+	#[test]
+	fn parse_many_reports_each_item() {
+		let results = parse_many::<UserId, _, _>(vec![
+			"user_0343".to_string(),
+			"team_0343".to_string(),
+			"user_0344".to_string(),
+			"user_034".to_string(),
+		]);
+
+		assert_eq!(
+			results,
+			vec![
+				Ok(UserId::from(3203)),
+				Err(ItemError {
+					index: 1,
+					error: Error::InvalidPrefix("user".into(), "team".into()),
+				}),
+				Ok(UserId::from(3204)),
+				Err(ItemError {
+					index: 3,
+					error: Error::InvalidLength(4, 3),
+				}),
+			]
+		);
+	}
+
+	#[test]
+	fn item_error_position() {
+		let results = parse_many::<UserId, _, _>(["user_0l43"]);
+		let error = results.into_iter().next().unwrap().unwrap_err();
+		assert_eq!(
+			error.error,
+			Error::Base32Error(Base32Error::InvalidByte(6, 'l'))
+		);
+		assert_eq!(error.to_string(), format!("item 0: {}", error.error));
+	}
+
+	#[test]
+	fn encode_many_roundtrip() {
+		let ids = vec![UserId::from(0), UserId::from(u16::MAX)];
+		let encoded = encode_many(&ids);
+		let parsed = parse_many::<UserId, _, _>(&encoded)
+			.into_iter()
+			.collect::<Result<Vec<_>, _>>()
+			.unwrap();
+		assert_eq!(parsed, ids);
+	}
+}
@@ -0,0 +1,152 @@
+use core::fmt::{self, Formatter};
+use std::borrow::Cow;
+
+#[cfg(feature = "canonicalize")]
+use crate::global::Global;
+
+#[cfg(feature = "canonicalize")]
+type Canonicalizer = Box<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// The canonicalizer of the process
+#[cfg(feature = "canonicalize")]
+static CANONICALIZER: Global<Canonicalizer> = Global::new();
+
+/// Set the canonicalizer of the process, which rewrites IDs before they are parsed and when they
+/// are displayed
+///
+/// Every entry point which parses IDs, i.e. `FromStr` and `from_str_relaxed` of generated types and
+/// [`DynamicStrongId`](crate::DynamicStrongId)s and
+/// [`DynamicStrongId::parse_with_prefix`](crate::DynamicStrongId::parse_with_prefix), applies the
+/// canonicalizer, e.g. to map the legacy prefixes of an organization which is mid-migration to their
+/// canonical prefixes. The canonicalizer returns `None` to leave an ID unchanged. It must map
+/// canonical IDs to themselves, and its result is validated like any other input. Errors report
+/// positions in the canonicalized ID.
+///
+/// `Display` of generated types, [`DynamicStrongId`](crate::DynamicStrongId)s and
+/// [`StaticPrefixId`](crate::StaticPrefixId)s also applies the canonicalizer, to the ID as it would
+/// be written, e.g. with an uppercase suffix for the alternate form `{:#}`. IDs which are created in
+/// code with a legacy prefix are therefore also written in their canonical form. The rewritten ID is
+/// written as is, without being validated, and displaying an ID allocates while a canonicalizer is
+/// set.
+///
+/// The canonicalizer is global to the process, and setting one replaces the previous one for every
+/// thread. It is intended to be set once at startup, as replaced canonicalizers are leaked, since
+/// they may still be running on another thread.
+///
+/// ```rust
+/// use strong_id::{set_canonicalizer, strong_id};
+///
+/// strong_id!(pub struct UserId(u16 => "user"));
+///
+/// set_canonicalizer(|value| {
+///     value
+///         .strip_prefix("usr_")
+///         .map(|suffix| format!("user_{suffix}"))
+/// });
+///
+/// assert_eq!("usr_0343".parse::<UserId>().unwrap().to_string(), "user_0343");
+/// ```
+#[cfg(feature = "canonicalize")]
+#[cfg_attr(docsrs, doc(cfg(feature = "canonicalize")))]
+pub fn set_canonicalizer<F>(canonicalizer: F)
+where
+	F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+{
+	CANONICALIZER.set(Box::new(canonicalizer));
+}
+
+/// Apply the canonicalizer set with `set_canonicalizer`, if any
+#[doc(hidden)]
+#[inline]
+pub fn _internal_canonicalize(value: &str) -> Cow<'_, str> {
+	#[cfg(feature = "canonicalize")]
+	{
+		if let Some(canonical) = CANONICALIZER
+			.get()
+			.and_then(|canonicalizer| canonicalizer(value))
+		{
+			return Cow::Owned(canonical);
+		}
+	}
+
+	Cow::Borrowed(value)
+}
+
+/// Write an ID with `write`, rewritten by the canonicalizer set with `set_canonicalizer`, if any
+#[doc(hidden)]
+#[inline]
+pub fn _internal_fmt_canonical<W>(f: &mut Formatter<'_>, write: W) -> fmt::Result
+where
+	W: Fn(&mut Formatter<'_>) -> fmt::Result,
+{
+	#[cfg(feature = "canonicalize")]
+	{
+		if let Some(canonicalizer) = CANONICALIZER.get() {
+			struct Written<W>(W);
+
+			impl<W: Fn(&mut Formatter<'_>) -> fmt::Result> fmt::Display for Written<W> {
+				fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+					(self.0)(f)
+				}
+			}
+
+			let written = if f.alternate() {
+				format!("{:#}", Written(&write))
+			} else {
+				Written(&write).to_string()
+			};
+			return f.write_str(&canonicalizer(&written).unwrap_or(written));
+		}
+	}
+
+	write(f)
+}
+
+#[cfg(all(test, feature = "canonicalize"))]
+mod tests {
+	use super::set_canonicalizer;
+	use crate::{strong_id, DynamicStrongId, Error};
+
+	strong_id!(pub struct AccountId(u16 => "canonaccount"));
+
+	// the canonicalizer is global, so it only rewrites a prefix which no other test uses
+	#[test]
+	fn canonicalize() {
+		set_canonicalizer(|value| {
+			value
+				.strip_prefix("canonlegacy_")
+				.map(|suffix| format!("canonaccount_{suffix}"))
+		});
+
+		let id = "canonlegacy_0343".parse::<AccountId>().unwrap();
+		assert_eq!(id, AccountId::from(3203));
+		assert_eq!(id.to_string(), "canonaccount_0343");
+		assert_eq!(
+			AccountId::from_str_relaxed("CANONLEGACY_0343"),
+			Ok(AccountId::from(3203))
+		);
+
+		let id = "canonlegacy_0343".parse::<DynamicStrongId<u16>>().unwrap();
+		assert_eq!(id.to_string(), "canonaccount_0343");
+		assert_eq!(
+			DynamicStrongId::<u16>::parse_with_prefix("canonaccount", "canonlegacy_0343"),
+			Ok(id)
+		);
+
+		// canonicalized IDs are validated, and errors refer to the canonical ID
+		assert_eq!(
+			"canonlegacy_034".parse::<AccountId>(),
+			Err(Error::InvalidLength(4, 3))
+		);
+		assert_eq!(
+			"canonaccount_0343".parse::<AccountId>(),
+			Ok(AccountId::from(3203))
+		);
+
+		// IDs created with a legacy prefix are displayed in their canonical form
+		let id = DynamicStrongId::new("canonlegacy", 3203u16).unwrap();
+		assert_eq!(id.to_string(), "canonaccount_0343");
+		assert_eq!(format!("{id:#}"), "canonaccount_0343");
+		assert_eq!(format!("{:#}", AccountId::from(3203)), "canonaccount_0343");
+	}
+}
@@ -0,0 +1,197 @@
+use crate::base32::is_valid_byte;
+use core::fmt::{Display, Formatter};
+
+/// A rule of the [TypeID specification](https://github.com/jetpack-io/typeid/blob/main/spec/README.md)
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Rule {
+	/// The prefix is at most 63 characters
+	PrefixLength,
+	/// The prefix only contains lowercase ascii characters, and when the "delimited" feature is
+	/// enabled, underscores which do not start or end the prefix
+	PrefixCharacters,
+	/// The separator is only present when there is a prefix
+	Separator,
+	/// The suffix is 26 characters
+	SuffixLength,
+	/// The suffix only contains characters from the base32 alphabet
+	SuffixAlphabet,
+	/// The first character of the suffix is at most `7`, so that the suffix fits into 128 bits
+	SuffixFirstCharacter,
+}
+
+impl Display for Rule {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		f.write_str(match self {
+			Self::PrefixLength => "prefix must be at most 63 characters",
+			Self::PrefixCharacters => "prefix must only contain lowercase ascii characters",
+			Self::Separator => "separator must only be present with a prefix",
+			Self::SuffixLength => "suffix must be 26 characters",
+			Self::SuffixAlphabet => "suffix must only contain characters from the base32 alphabet",
+			Self::SuffixFirstCharacter => "suffix must start with a character from 0 to 7",
+		})
+	}
+}
+
+/// The outcome of checking a single [`Rule`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RuleResult {
+	/// The rule which was checked
+	pub rule: Rule,
+	/// Whether the ID satisfies the rule
+	pub passed: bool,
+	/// The position of the first offending character, if the rule failed because of one
+	pub position: Option<usize>,
+}
+
+/// A report of which TypeID specification rules an ID satisfies, created with [`check`]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ComplianceReport {
+	results: Vec<RuleResult>,
+}
+
+impl ComplianceReport {
+	/// Whether the ID satisfies every rule
+	pub fn is_compliant(&self) -> bool {
+		self.results.iter().all(|result| result.passed)
+	}
+
+	/// The result of every rule which was checked
+	pub fn results(&self) -> &[RuleResult] {
+		&self.results
+	}
+
+	/// The results of the rules which failed
+	pub fn failures(&self) -> impl Iterator<Item = &RuleResult> {
+		self.results.iter().filter(|result| !result.passed)
+	}
+}
+
+impl Display for ComplianceReport {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		for result in &self.results {
+			let status = if result.passed { "pass" } else { "fail" };
+			write!(f, "[{status}] {}", result.rule)?;
+			if let Some(position) = result.position {
+				write!(f, " (position {position})")?;
+			}
+			writeln!(f)?;
+		}
+		Ok(())
+	}
+}
+
+fn result(rule: Rule, position: Option<usize>, passed: bool) -> RuleResult {
+	RuleResult {
+		rule,
+		passed,
+		position,
+	}
+}
+
+/// Check an ID against each rule of the TypeID specification
+///
+/// Unlike parsing, which stops at the first error, every rule is checked so that the report can
+/// explain all of the reasons an ID is rejected.
+///
+/// ```rust
+/// use strong_id::{check, Rule};
+///
+/// let report = check("User_01h455vb4pex5vsknk084sn02q");
+/// assert!(!report.is_compliant());
+///
+/// let failures = report.failures().map(|result| result.rule).collect::<Vec<_>>();
+/// assert_eq!(failures, vec![Rule::PrefixCharacters]);
+/// ```
+pub fn check(value: &str) -> ComplianceReport {
+	let (prefix, suffix, suffix_offset) = match value.rsplit_once('_') {
+		Some((prefix, suffix)) => (Some(prefix), suffix, prefix.len() + 1),
+		None => (None, value, 0),
+	};
+
+	let mut results = Vec::with_capacity(6);
+
+	let prefix_bytes = prefix.unwrap_or_default().as_bytes();
+	results.push(result(Rule::PrefixLength, None, prefix_bytes.len() < 64));
+
+	let last = prefix_bytes.len().saturating_sub(1);
+	let invalid_prefix_character = prefix_bytes.iter().enumerate().position(|(index, b)| {
+		let delimiter = cfg!(feature = "delimited") && *b == b'_' && index != 0 && index != last;
+		!(b.is_ascii_lowercase() || delimiter)
+	});
+	results.push(result(
+		Rule::PrefixCharacters,
+		invalid_prefix_character,
+		invalid_prefix_character.is_none(),
+	));
+
+	results.push(result(
+		Rule::Separator,
+		prefix.filter(|prefix| prefix.is_empty()).map(|_| 0),
+		prefix.map_or(true, |prefix| !prefix.is_empty()),
+	));
+
+	results.push(result(Rule::SuffixLength, None, suffix.len() == 26));
+
+	let invalid_suffix_character = suffix.bytes().position(|b| !is_valid_byte(b));
+	results.push(result(
+		Rule::SuffixAlphabet,
+		invalid_suffix_character.map(|position| suffix_offset + position),
+		invalid_suffix_character.is_none(),
+	));
+
+	let first_character_valid = suffix.bytes().next().map_or(true, |b| b <= b'7');
+	results.push(result(
+		Rule::SuffixFirstCharacter,
+		Some(suffix_offset).filter(|_| !first_character_valid),
+		first_character_valid,
+	));
+
+	ComplianceReport { results }
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{check, Rule};
+
+	#[test]
+	fn compliance() {
+		struct Case(&'static str, Vec<(Rule, Option<usize>)>);
+		let cases = vec![
+			Case("01h455vb4pex5vsknk084sn02q", vec![]),
+			Case("prefix_01h455vb4pex5vsknk084sn02q", vec![]),
+			Case(
+				"PREFIX_01h455vb4pex5vsknk084sn02q",
+				vec![(Rule::PrefixCharacters, Some(0))],
+			),
+			Case(
+				"_01h455vb4pex5vsknk084sn02q",
+				vec![(Rule::Separator, Some(0))],
+			),
+			Case(
+				"prefix_81h455vb4pex5vsknk084sn02q",
+				vec![(Rule::SuffixFirstCharacter, Some(7))],
+			),
+			Case(
+				"prefix_01h455vb4pex5vsknk084sn0l",
+				vec![(Rule::SuffixLength, None), (Rule::SuffixAlphabet, Some(31))],
+			),
+		];
+
+		for case in cases {
+			let report = check(case.0);
+			let failures = report
+				.failures()
+				.map(|result| (result.rule, result.position))
+				.collect::<Vec<_>>();
+			assert_eq!(failures, case.1, "{}", case.0);
+			assert_eq!(report.is_compliant(), case.1.is_empty());
+		}
+
+		let report = check(&format!("{}_01h455vb4pex5vsknk084sn02q", "a".repeat(64)));
+		let failures = report
+			.failures()
+			.map(|result| result.rule)
+			.collect::<Vec<_>>();
+		assert_eq!(failures, vec![Rule::PrefixLength]);
+	}
+}
@@ -0,0 +1,140 @@
+use crate::{Error, Id};
+use core::fmt;
+
+/// The length of the encoded suffix of a compound ID, the encoded segments without a separator
+#[doc(hidden)]
+pub const fn _internal_compound_suffix_len<A: Id, B: Id>() -> usize {
+	A::ENCODED_LEN + B::ENCODED_LEN
+}
+
+/// Format a compound ID, see `strong_id!`
+#[doc(hidden)]
+pub fn _internal_fmt_compound<A: Id, B: Id>(
+	prefix: Option<&str>,
+	first: &A,
+	second: &B,
+	f: &mut fmt::Formatter<'_>,
+) -> fmt::Result {
+	crate::_internal_fmt_canonical(f, |f| {
+		if let Some(prefix) = prefix {
+			f.write_str(prefix)?;
+			f.write_str("_")?;
+		}
+
+		let mut suffix = first.encode();
+		suffix.push_str(&second.encode());
+		if f.alternate() {
+			suffix.make_ascii_uppercase();
+		}
+		f.write_str(&suffix)
+	})
+}
+
+/// Parse a compound ID, see `strong_id!`
+#[doc(hidden)]
+pub fn _internal_parse_compound<A: Id, B: Id>(
+	prefix: Option<&str>,
+	value: &str,
+) -> Result<(A, B), Error> {
+	let canonical = crate::_internal_canonicalize(value);
+	let value: &str = &canonical;
+
+	let (suffix, offset) = match (prefix, value.rsplit_once('_')) {
+		(Some(prefix), None) => return Err(Error::MissingPrefix(prefix.into())),
+		(Some(prefix), Some(("", _))) => return Err(Error::MissingPrefix(prefix.into())),
+		(Some(prefix), Some((parsed_prefix, suffix))) => {
+			crate::_internal_check_delimiters(parsed_prefix)?;
+			if parsed_prefix != prefix {
+				return Err(Error::InvalidPrefix(
+					prefix.to_string(),
+					parsed_prefix.to_string(),
+				));
+			}
+			(suffix, parsed_prefix.len() + 1)
+		}
+		(None, Some((parsed_prefix, _))) => {
+			return Err(Error::NoPrefixExpected(parsed_prefix.to_string()))
+		}
+		(None, None) => (value, 0),
+	};
+
+	let expected = _internal_compound_suffix_len::<A, B>();
+	if suffix.len() != expected || !suffix.is_char_boundary(A::ENCODED_LEN) {
+		return Err(Error::InvalidLength(expected, suffix.len()));
+	}
+
+	let (first, second) = suffix.split_at(A::ENCODED_LEN);
+	Ok((
+		A::decode(first).map_err(|error| error._internal_offset(offset))?,
+		B::decode(second).map_err(|error| error._internal_offset(offset + A::ENCODED_LEN))?,
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{base32::Base32Error, strong_id, Error};
+
+	strong_id!(pub struct TenantItemId(u16, u32 => "tenantitem"));
+	strong_id!(pub struct PairId(pub u8, pub u16));
+
+	#[test]
+	fn compound() {
+		let id = TenantItemId::new(3203, 4095);
+		assert_eq!(id.to_string(), "tenantitem_034300003zz");
+		assert_eq!(id.to_string().len(), TenantItemId::ENCODED_LEN);
+		assert_eq!(format!("{id:#}"), "tenantitem_034300003ZZ");
+		assert_eq!(id.first(), &3203);
+		assert_eq!(id.second(), &4095);
+		assert_eq!(id.into_parts(), (3203, 4095));
+		assert_eq!(TenantItemId::from((3203, 4095)), id);
+
+		assert_eq!("tenantitem_034300003zz".parse::<TenantItemId>(), Ok(id));
+		assert_eq!(TenantItemId::try_from("tenantitem_034300003zz"), Ok(id));
+
+		let pair = PairId(1, 2);
+		assert_eq!(pair.to_string(), "010002");
+		assert_eq!("010002".parse::<PairId>(), Ok(pair));
+	}
+
+	#[test]
+	fn ordering() {
+		let mut ids = [
+			TenantItemId::new(2, 1),
+			TenantItemId::new(1, 2),
+			TenantItemId::new(1, 1),
+		];
+		ids.sort();
+
+		let mut encoded = ids.iter().map(ToString::to_string).collect::<Vec<_>>();
+		encoded.sort();
+		assert_eq!(
+			encoded,
+			ids.iter().map(ToString::to_string).collect::<Vec<_>>()
+		);
+		assert_eq!(ids[0], TenantItemId::new(1, 1));
+	}
+
+	#[test]
+	fn invalid() {
+		assert_eq!(
+			"034300003zz".parse::<TenantItemId>(),
+			Err(Error::MissingPrefix("tenantitem".into()))
+		);
+		assert_eq!(
+			"user_034300003zz".parse::<TenantItemId>(),
+			Err(Error::InvalidPrefix("tenantitem".into(), "user".into()))
+		);
+		assert_eq!(
+			"tenantitem_03430003zz".parse::<TenantItemId>(),
+			Err(Error::InvalidLength(11, 10))
+		);
+		assert_eq!(
+			"tenantitem_0343000u3zz".parse::<TenantItemId>(),
+			Err(Error::Base32Error(Base32Error::InvalidByte(18, 'u')))
+		);
+		assert_eq!(
+			"pair_010002".parse::<PairId>(),
+			Err(Error::NoPrefixExpected("pair".into()))
+		);
+	}
+}
@@ -0,0 +1,127 @@
+use crate::{Base32, Encoding};
+
+/// Characters which are easily mistaken for each other, in order of preference
+const LOOKALIKES: &[(char, &[char])] = &[
+	('o', &['0']),
+	('O', &['0']),
+	('0', &['o', 'O']),
+	('i', &['1', 'l']),
+	('I', &['1', 'l']),
+	('l', &['1', 'i']),
+	('L', &['1', 'l']),
+	('1', &['l', 'i', 'I']),
+	('u', &['v']),
+	('U', &['v', 'V']),
+	('|', &['1', 'l']),
+];
+
+/// An invalid character of an ID which looks like a valid one, see [`confusables`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Confusable {
+	/// The byte offset of the character in the ID
+	pub position: usize,
+	/// The invalid character
+	pub found: char,
+	/// The valid character it was likely meant to be
+	pub suggestion: char,
+}
+
+/// Find the invalid characters in the suffix of an ID which look like valid [`Base32`] characters,
+/// e.g. `O` instead of `0`, or `I` instead of `1`
+///
+/// This is meant for form UIs which highlight the characters to fix. Invalid characters without a
+/// lookalike are not reported, see [`Error::position`](crate::Error::position) for the first invalid
+/// character of an ID.
+///
+/// ```rust
+/// use strong_id::{confusables, Confusable};
+///
+/// let found = confusables("user_O3I3");
+/// assert_eq!(
+///     found,
+///     vec![
+///         Confusable { position: 5, found: 'O', suggestion: '0' },
+///         Confusable { position: 7, found: 'I', suggestion: '1' },
+///     ]
+/// );
+/// ```
+pub fn confusables(value: &str) -> Vec<Confusable> {
+	confusables_with::<Base32>(value)
+}
+
+/// Find the invalid characters in the suffix of an ID which look like valid characters of the given
+/// [`Encoding`], see [`confusables`]
+///
+/// The suffix is the part of the ID after its last `_`, unless `_` is a symbol of the encoding, in
+/// which case the whole ID is checked.
+pub fn confusables_with<E: Encoding>(value: &str) -> Vec<Confusable> {
+	let offset = match value.rsplit_once('_') {
+		Some((prefix, _)) if !E::SYMBOLS.contains(&b'_') => prefix.len() + 1,
+		_ => 0,
+	};
+
+	let is_valid = |c: char| c.is_ascii() && E::SYMBOLS.contains(&(c as u8));
+
+	value[offset..]
+		.char_indices()
+		.filter(|&(_, c)| !is_valid(c))
+		.filter_map(|(index, c)| {
+			let lookalikes = LOOKALIKES
+				.iter()
+				.find(|(lookalike, _)| *lookalike == c)
+				.map_or(&[][..], |(_, lookalikes)| *lookalikes);
+
+			// the same character in another case looks the most alike
+			let other_case = if c.is_ascii_uppercase() {
+				c.to_ascii_lowercase()
+			} else {
+				c.to_ascii_uppercase()
+			};
+
+			core::iter::once(other_case)
+				.chain(lookalikes.iter().copied())
+				.find(|suggestion| is_valid(*suggestion))
+				.map(|suggestion| Confusable {
+					position: offset + index,
+					found: c,
+					suggestion,
+				})
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{confusables, confusables_with, Confusable, Hex};
+
+	fn suggestions(found: Vec<Confusable>) -> Vec<(usize, char)> {
+		found
+			.into_iter()
+			.map(|confusable| (confusable.position, confusable.suggestion))
+			.collect()
+	}
+
+	#[test]
+	fn confusable() {
+		assert!(confusables("user_0343").is_empty());
+		assert_eq!(suggestions(confusables("0o1I")), vec![(1, '0'), (3, '1')]);
+		assert_eq!(
+			suggestions(confusables("user_acc_0L4U")),
+			vec![(10, '1'), (12, 'v')]
+		);
+		// uppercase characters are suggested in lowercase
+		assert_eq!(
+			suggestions(confusables("user_03ZZ")),
+			vec![(7, 'z'), (8, 'z')]
+		);
+		// invalid characters without a lookalike are not reported
+		assert!(confusables("user_03!-").is_empty());
+		// the prefix is not checked
+		assert!(confusables("User_0343").is_empty());
+
+		assert_eq!(
+			suggestions(confusables_with::<Hex>("trace_0O1g")),
+			vec![(7, '0')]
+		);
+	}
+}
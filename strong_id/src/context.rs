@@ -0,0 +1,249 @@
+//! Task-local IDs, e.g. correlation IDs which flow implicitly through async call trees
+//!
+//! [`with_current`] makes an ID current while a future is polled, and [`current`] returns the
+//! current ID of a type from anywhere within it. The ID is set for each poll of the future, rather
+//! than for a thread, so it works on any executor, including multi-threaded runtimes like `tokio`
+//! and `actix`. Futures which are spawned as separate tasks don't inherit the IDs of the task which
+//! spawned them, and need to be wrapped themselves.
+//!
+//! ```rust
+//! use strong_id::{context, strong_id};
+//!
+//! strong_id!(pub struct RequestId(u64 => "req"));
+//!
+//! async fn log(message: &str) -> String {
+//!     match context::current::<RequestId>() {
+//!         Some(id) => format!("[{id}] {message}"),
+//!         None => message.to_string(),
+//!     }
+//! }
+//!
+//! async fn handle() -> String {
+//!     log("handling request").await
+//! }
+//!
+//! # async fn run() {
+//! let logged = context::with_current(RequestId::from(3203), handle()).await;
+//! assert_eq!(logged, "[req_0000000000343] handling request");
+//! # }
+//! ```
+
+use core::{
+	any::{Any, TypeId},
+	cell::RefCell,
+	future::Future,
+	pin::Pin,
+	task::{Context, Poll},
+};
+use std::collections::HashMap;
+
+type Value = Box<dyn Any + Send>;
+
+thread_local! {
+	/// The IDs of the future which is being polled on this thread, by their type
+	static CURRENT: RefCell<HashMap<TypeId, Value>> = RefCell::new(HashMap::new());
+}
+
+/// Make `id` current while `future` is polled, see [`current`]
+///
+/// IDs of other types which are current stay current, and an ID of the same type is shadowed until
+/// the future completes.
+pub fn with_current<T, F>(id: T, future: F) -> WithCurrent<F>
+where
+	T: Send + 'static,
+	F: Future,
+{
+	WithCurrent {
+		type_id: TypeId::of::<T>(),
+		value: Some(Box::new(id)),
+		future: Box::pin(future),
+	}
+}
+
+/// Run `f` with `id` current, for synchronous code, see [`current`]
+pub fn sync_scope<T, F, R>(id: T, f: F) -> R
+where
+	T: Send + 'static,
+	F: FnOnce() -> R,
+{
+	let mut value = Some(Box::new(id) as Value);
+	let _guard = Scope::enter(TypeId::of::<T>(), &mut value);
+	f()
+}
+
+/// The current ID of the type, set by [`with_current`] or [`sync_scope`]
+pub fn current<T: Clone + 'static>() -> Option<T> {
+	CURRENT.with(|current| {
+		current
+			.borrow()
+			.get(&TypeId::of::<T>())
+			.and_then(|value| value.downcast_ref::<T>())
+			.cloned()
+	})
+}
+
+/// A future with an ID which is current while it is polled, created with [`with_current`]
+pub struct WithCurrent<F> {
+	type_id: TypeId,
+	value: Option<Value>,
+	future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for WithCurrent<F> {
+	type Output = F::Output;
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let this = self.get_mut();
+		let _guard = Scope::enter(this.type_id, &mut this.value);
+		this.future.as_mut().poll(cx)
+	}
+}
+
+impl<F> core::fmt::Debug for WithCurrent<F> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("WithCurrent").finish_non_exhaustive()
+	}
+}
+
+/// Moves a value into the current IDs, and moves it back out along with restoring the value it
+/// shadowed when dropped, including when the future panics
+struct Scope<'a> {
+	type_id: TypeId,
+	value: &'a mut Option<Value>,
+	shadowed: Option<Value>,
+}
+
+impl<'a> Scope<'a> {
+	fn enter(type_id: TypeId, value: &'a mut Option<Value>) -> Self {
+		let shadowed = match value.take() {
+			Some(entered) => CURRENT.with(|current| current.borrow_mut().insert(type_id, entered)),
+			None => None,
+		};
+
+		Self {
+			type_id,
+			value,
+			shadowed,
+		}
+	}
+}
+
+impl<'a> Drop for Scope<'a> {
+	fn drop(&mut self) {
+		CURRENT.with(|current| {
+			let mut current = current.borrow_mut();
+			*self.value = current.remove(&self.type_id);
+			if let Some(shadowed) = self.shadowed.take() {
+				current.insert(self.type_id, shadowed);
+			}
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{current, sync_scope, with_current};
+	use crate::strong_id;
+	use core::{
+		future::Future,
+		pin::Pin,
+		task::{Context, Poll},
+	};
+	use std::{sync::Arc, task::Wake};
+
+	strong_id!(pub struct RequestId(u64 => "req"));
+	strong_id!(pub struct TenantId(u16 => "tenant"));
+
+	struct NoopWaker;
+
+	impl Wake for NoopWaker {
+		fn wake(self: Arc<Self>) {}
+	}
+
+	/// Yields once before completing, so that it is polled twice
+	#[derive(Default)]
+	struct YieldOnce(bool);
+
+	impl Future for YieldOnce {
+		type Output = ();
+
+		fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+			if self.0 {
+				Poll::Ready(())
+			} else {
+				self.0 = true;
+				cx.waker().wake_by_ref();
+				Poll::Pending
+			}
+		}
+	}
+
+	/// Poll a future to completion, interleaved with polls of another future
+	fn interleaved<A: Future, B: Future>(a: A, b: B) -> (A::Output, B::Output) {
+		let waker = Arc::new(NoopWaker).into();
+		let mut context = Context::from_waker(&waker);
+		let (mut a, mut b) = (Box::pin(a), Box::pin(b));
+		let (mut a_out, mut b_out) = (None, None);
+
+		while a_out.is_none() || b_out.is_none() {
+			if a_out.is_none() {
+				if let Poll::Ready(out) = a.as_mut().poll(&mut context) {
+					a_out = Some(out);
+				}
+			}
+			if b_out.is_none() {
+				if let Poll::Ready(out) = b.as_mut().poll(&mut context) {
+					b_out = Some(out);
+				}
+			}
+		}
+
+		(a_out.unwrap(), b_out.unwrap())
+	}
+
+	async fn observe() -> (Option<RequestId>, Option<RequestId>) {
+		let before = current::<RequestId>();
+		YieldOnce::default().await;
+		(before, current::<RequestId>())
+	}
+
+	#[test]
+	fn with_current_per_future() {
+		let first = with_current(RequestId::from(1), observe());
+		let second = with_current(RequestId::from(2), observe());
+
+		let (first, second) = interleaved(first, second);
+		assert_eq!(first, (Some(RequestId::from(1)), Some(RequestId::from(1))));
+		assert_eq!(second, (Some(RequestId::from(2)), Some(RequestId::from(2))));
+		assert_eq!(current::<RequestId>(), None);
+	}
+
+	#[test]
+	fn nested() {
+		let nested = with_current(TenantId::from(7), async {
+			let outer = current::<RequestId>();
+			let inner = with_current(RequestId::from(2), observe()).await;
+			(outer, inner, current::<RequestId>(), current::<TenantId>())
+		});
+		let (nested, _) = interleaved(with_current(RequestId::from(1), nested), async {});
+
+		assert_eq!(
+			nested,
+			(
+				Some(RequestId::from(1)),
+				(Some(RequestId::from(2)), Some(RequestId::from(2))),
+				Some(RequestId::from(1)),
+				Some(TenantId::from(7)),
+			)
+		);
+	}
+
+	#[test]
+	fn sync() {
+		let id = sync_scope(RequestId::from(3203), || {
+			sync_scope(TenantId::from(7), current::<RequestId>)
+		});
+		assert_eq!(id, Some(RequestId::from(3203)));
+		assert_eq!(current::<RequestId>(), None);
+	}
+}
@@ -0,0 +1,110 @@
+use core::fmt::{Debug, Display, Formatter};
+
+/// Formats a collection of IDs compactly, eliding the middle of large collections
+///
+/// Useful in log statements which would otherwise print every ID of a large collection.
+///
+/// ```rust
+/// use strong_id::{debug_ids, strong_id};
+///
+/// strong_id!(pub struct UserId(u16 => "user"));
+///
+/// let ids = (0..1000).map(UserId::from).collect::<Vec<_>>();
+/// assert_eq!(
+///     format!("{:?}", debug_ids(&ids)),
+///     "1000 IDs [user_0000, user_0001, user_0002, ..994 more.., user_00z5, user_00z6, user_00z7]"
+/// );
+/// ```
+#[derive(Clone, Copy)]
+pub struct IdsDebug<'a, T> {
+	ids: &'a [T],
+	edges: usize,
+}
+
+/// Format a collection of IDs compactly, see [`IdsDebug`]
+pub fn debug_ids<T: Display>(ids: &[T]) -> IdsDebug<'_, T> {
+	IdsDebug::new(ids)
+}
+
+impl<'a, T: Display> IdsDebug<'a, T> {
+	/// Show the first and last 3 IDs of large collections
+	pub fn new(ids: &'a [T]) -> Self {
+		Self { ids, edges: 3 }
+	}
+
+	/// Show the first and last `edges` IDs of large collections
+	pub fn with_edges(mut self, edges: usize) -> Self {
+		self.edges = edges;
+		self
+	}
+}
+
+impl<'a, T: Display> Display for IdsDebug<'a, T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		let len = self.ids.len();
+		write!(f, "{len} {} [", if len == 1 { "ID" } else { "IDs" })?;
+
+		let (head, tail, elided) = if len > self.edges * 2 {
+			(
+				&self.ids[..self.edges],
+				&self.ids[len - self.edges..],
+				len - self.edges * 2,
+			)
+		} else {
+			(self.ids, &self.ids[..0], 0)
+		};
+
+		let mut separator = "";
+		for id in head {
+			write!(f, "{separator}{id}")?;
+			separator = ", ";
+		}
+
+		if elided > 0 {
+			write!(f, "{separator}..{elided} more..")?;
+		}
+
+		for id in tail {
+			write!(f, "{separator}{id}")?;
+		}
+
+		f.write_str("]")
+	}
+}
+
+impl<'a, T: Display> Debug for IdsDebug<'a, T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		Display::fmt(self, f)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{debug_ids, strong_id};
+
+	strong_id!(pub struct UserId(u8 => "user"));
+
+	#[test]
+	fn format() {
+		let ids = (0..10).map(UserId::from).collect::<Vec<_>>();
+
+		assert_eq!(debug_ids(&ids[..0]).to_string(), "0 IDs []");
+		assert_eq!(debug_ids(&ids[..1]).to_string(), "1 ID [user_00]");
+		assert_eq!(
+			debug_ids(&ids[..6]).to_string(),
+			"6 IDs [user_00, user_01, user_02, user_03, user_04, user_05]"
+		);
+		assert_eq!(
+			debug_ids(&ids[..7]).to_string(),
+			"7 IDs [user_00, user_01, user_02, ..1 more.., user_04, user_05, user_06]"
+		);
+		assert_eq!(
+			format!("{:?}", debug_ids(&ids).with_edges(1)),
+			"10 IDs [user_00, ..8 more.., user_09]"
+		);
+		assert_eq!(
+			debug_ids(&ids).with_edges(0).to_string(),
+			"10 IDs [..10 more..]"
+		);
+	}
+}
@@ -1,35 +1,108 @@
-use crate::{Error, Id, StrongId};
-use core::fmt::{Display, Formatter};
-use std::borrow::Cow;
+use crate::{Base32, ErasedStrongId, Error, ExpectedFormat, Id, StrongId};
+use core::{
+	cmp::Ordering,
+	fmt::{Debug, Display, Formatter},
+	hash::{Hash, Hasher},
+};
+use std::{borrow::Cow, collections::BTreeMap, sync::Arc};
 
+#[cfg(feature = "uuid")]
+use crate::Granularity;
+#[cfg(feature = "uuid-v7")]
+use crate::IntoTimestamp;
 #[cfg(feature = "uuid")]
 use uuid::Uuid;
 
-fn map_prefix<'p, I: Into<Prefix<'p>>>(prefix: I) -> Result<Prefix<'p>, Error> {
-	let prefix = prefix.into();
-	if prefix.inner.len() >= 64 {
-		return Err(Error::PrefixTooLong(prefix.inner.len()));
+/// Reasons a prefix may be invalid. Kept separate from [`Error`] so that validation can be
+/// evaluated in `const` contexts.
+#[derive(Clone, Copy)]
+enum InvalidPrefix {
+	TooLong(usize),
+	Empty,
+	Character(u8),
+}
+
+impl From<InvalidPrefix> for Error {
+	fn from(value: InvalidPrefix) -> Self {
+		match value {
+			InvalidPrefix::TooLong(len) => Error::PrefixTooLong(len),
+			InvalidPrefix::Empty => Error::PrefixExpected,
+			InvalidPrefix::Character(b) => Error::IncorrectPrefixCharacter(b as char),
+		}
+	}
+}
+
+const fn validate_prefix(bytes: &[u8]) -> Result<(), InvalidPrefix> {
+	if bytes.len() >= 64 {
+		return Err(InvalidPrefix::TooLong(bytes.len()));
 	}
 
-	if prefix.inner.is_empty() {
-		return Err(Error::PrefixExpected);
+	if bytes.is_empty() {
+		return Err(InvalidPrefix::Empty);
 	}
 
 	let underscore = b'_';
-	let bytes = prefix.inner.as_bytes();
 
-	if *bytes.first().unwrap() == underscore || *bytes.last().unwrap() == underscore {
-		return Err(Error::IncorrectPrefixCharacter(underscore as char));
+	if let [b'_', ..] | [.., b'_'] = bytes {
+		return Err(InvalidPrefix::Character(underscore));
 	}
 
-	for b in bytes {
-		if cfg!(feature = "delimited") && *b == b'_' {
+	// iterate with slice patterns rather than indexing, so that there are no bounds checks which
+	// could panic
+	let mut rest = bytes;
+	while let [b, tail @ ..] = rest {
+		rest = tail;
+
+		if cfg!(feature = "delimited") && *b == underscore {
 			continue;
 		} else if !b.is_ascii_lowercase() {
-			return Err(Error::IncorrectPrefixCharacter(*b as char));
+			return Err(InvalidPrefix::Character(*b));
 		}
 	}
 
+	Ok(())
+}
+
+pub(crate) fn map_prefix<'p, I: Into<Prefix<'p>>>(prefix: I) -> Result<Prefix<'p>, Error> {
+	let prefix = prefix.into();
+	validate_prefix(prefix.as_str().as_bytes())?;
+	if !prefix.allow_reserved && prefix.is_reserved() {
+		return Err(Error::ReservedPrefix(prefix.to_string()));
+	}
+	Ok(prefix)
+}
+
+/// Validate a prefix which was split from an ID at its last `_`, in a single pass over its bytes
+///
+/// Reports the same errors, in the same order, as rejecting blank prefixes, then
+/// [`_internal_check_delimiters`](crate::_internal_check_delimiters) and then [`map_prefix`].
+fn parse_prefix(prefix: &str) -> Result<Prefix<'static>, Error> {
+	let mut delimited = false;
+	let mut invalid = None;
+	for b in prefix.bytes() {
+		if b == b'_' {
+			delimited = true;
+		} else if !b.is_ascii_lowercase() && invalid.is_none() {
+			invalid = Some(b);
+		}
+	}
+
+	// a blank prefix has no valid bytes, so it is only checked for when there are invalid bytes
+	if (prefix.is_empty() || invalid.is_some()) && prefix.trim().is_empty() {
+		return Err(Error::MissingPrefix(prefix.into()));
+	}
+	crate::check_delimiters_with(prefix, delimited)?;
+	if prefix.len() >= 64 {
+		return Err(Error::PrefixTooLong(prefix.len()));
+	}
+	if let Some(b) = invalid {
+		return Err(Error::IncorrectPrefixCharacter(b as char));
+	}
+
+	let prefix = Prefix::new_unchecked(prefix.to_string());
+	if prefix.is_reserved() {
+		return Err(Error::ReservedPrefix(prefix.to_string()));
+	}
 	Ok(prefix)
 }
 
@@ -126,22 +199,240 @@ pub struct DynamicStrongId<'p, T: Id> {
 	suffix: T,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
-#[doc(hidden)]
+/// A [`DynamicStrongId`] which owns its prefix
+///
+/// Parsed IDs always own their prefix, and other IDs can be converted with
+/// [`DynamicStrongId::into_owned`]. This is useful for storing IDs in long-lived state without
+/// carrying the prefix lifetime around.
+pub type DynamicStrongIdBuf<T> = DynamicStrongId<'static, T>;
+
+/// IDs grouped by their prefix, see [`group_by_prefix`]
+pub type PrefixGroups<T> = BTreeMap<Option<Prefix<'static>>, Vec<DynamicStrongIdBuf<T>>>;
+
+/// Parse a stream of IDs with different prefixes and group them by prefix, e.g. before handing
+/// each group to typed processing
+///
+/// IDs are grouped in a single pass. Grouped IDs own their prefix, as they outlive the input, so
+/// the prefix of every ID is allocated, and is copied once more for the key of each group. IDs
+/// without a prefix are grouped under `None`. The groups are ordered by prefix, and each group
+/// keeps the order of the input. Fails on the first ID which can't be parsed.
+///
+/// ```rust
+/// use strong_id::{group_by_prefix, Prefix, StrongId};
+///
+/// let groups = group_by_prefix::<u16, _, _>(["user_0343", "team_0001", "user_0344"]).unwrap();
+///
+/// let users = &groups[&Some(Prefix::from_static("user"))];
+/// assert_eq!(users.iter().map(|id| *id.id()).collect::<Vec<_>>(), vec![3203, 3204]);
+///
+/// let prefixes = groups.keys().flatten().map(Prefix::as_str).collect::<Vec<_>>();
+/// assert_eq!(prefixes, vec!["team", "user"]);
+/// ```
+pub fn group_by_prefix<T, I, S>(ids: I) -> Result<PrefixGroups<T>, Error>
+where
+	T: Id,
+	I: IntoIterator<Item = S>,
+	S: AsRef<str>,
+{
+	let mut groups = PrefixGroups::new();
+
+	for id in ids {
+		let id = id.as_ref().parse::<DynamicStrongIdBuf<T>>()?;
+		match groups.get_mut(&id.prefix) {
+			Some(group) => group.push(id),
+			None => {
+				groups.insert(id.prefix.clone(), vec![id]);
+			}
+		}
+	}
+
+	Ok(groups)
+}
+
+/// The prefix of a [`DynamicStrongId`]
+///
+/// Prefixes are validated when they are used to create an ID, but can also be validated up-front
+/// with [`Prefix::new`] and reused across many IDs.
+///
+/// ```rust
+/// use strong_id::{DynamicStrongId, Prefix};
+///
+/// static USER: Prefix<'static> = Prefix::from_static("user");
+///
+/// let user_id = DynamicStrongId::new(USER.clone(), 3203u16).unwrap();
+/// assert_eq!(user_id.to_string(), "user_0343");
+/// ```
+///
+/// Prefixes created from an `Arc<str>`, or converted with [`Prefix::into_shared`], are shared
+/// rather than copied when cloned. This makes cloning IDs pointer-cheap, e.g. when passing them
+/// between threads or tasks.
+#[derive(Clone)]
 pub struct Prefix<'p> {
-	inner: Cow<'p, str>,
+	inner: PrefixInner<'p>,
+	allow_reserved: bool,
+}
+
+#[derive(Clone)]
+enum PrefixInner<'p> {
+	Borrowed(&'p str),
+	Owned(String),
+	Shared(Arc<str>),
+}
+
+impl<'p> Prefix<'p> {
+	/// Create a validated prefix
+	pub fn new<I: Into<Cow<'p, str>>>(value: I) -> Result<Self, Error> {
+		let prefix = Self::new_unchecked(value);
+		validate_prefix(prefix.as_str().as_bytes())?;
+		Ok(prefix)
+	}
+
+	/// Create a prefix without validating it. It will still be validated when used to create a
+	/// [`DynamicStrongId`].
+	pub fn new_unchecked<I: Into<Cow<'p, str>>>(value: I) -> Self {
+		let inner = match value.into() {
+			Cow::Borrowed(value) => PrefixInner::Borrowed(value),
+			Cow::Owned(value) => PrefixInner::Owned(value),
+		};
+		Self {
+			inner,
+			allow_reserved: false,
+		}
+	}
+
+	/// Create a prefix from a `&'static str`, usable in `const` and `static` items
+	///
+	/// # Panics
+	///
+	/// Panics if the prefix is invalid. In a `const` context, this fails compilation instead.
+	pub const fn from_static(value: &'static str) -> Self {
+		match validate_prefix(value.as_bytes()) {
+			Ok(()) => Self {
+				inner: PrefixInner::Borrowed(value),
+				allow_reserved: false,
+			},
+			Err(InvalidPrefix::TooLong(_)) => panic!("prefix is longer than 63 characters"),
+			Err(InvalidPrefix::Empty) => panic!("prefix must be non-empty"),
+			Err(InvalidPrefix::Character(_)) => {
+				panic!("prefix contains non ascii lowercase characters")
+			}
+		}
+	}
+
+	/// The prefix as a `&str`
+	pub fn as_str(&self) -> &str {
+		match &self.inner {
+			PrefixInner::Borrowed(value) => value,
+			PrefixInner::Owned(value) => value,
+			PrefixInner::Shared(value) => value,
+		}
+	}
+
+	/// Convert into a prefix which owns its value
+	pub fn into_owned(self) -> Prefix<'static> {
+		let inner = match self.inner {
+			PrefixInner::Borrowed(value) => PrefixInner::Owned(value.to_string()),
+			PrefixInner::Owned(value) => PrefixInner::Owned(value),
+			PrefixInner::Shared(value) => PrefixInner::Shared(value),
+		};
+		Prefix {
+			inner,
+			allow_reserved: self.allow_reserved,
+		}
+	}
+
+	/// Convert into a prefix which is shared when cloned
+	pub fn into_shared(self) -> Prefix<'static> {
+		let inner = match self.inner {
+			PrefixInner::Shared(value) => value,
+			_ => Arc::from(self.as_str()),
+		};
+		Prefix {
+			inner: PrefixInner::Shared(inner),
+			allow_reserved: self.allow_reserved,
+		}
+	}
+
+	/// Whether the prefix was reserved with [`reserve_prefixes`](crate::reserve_prefixes)
+	pub fn is_reserved(&self) -> bool {
+		crate::reserved::is_reserved(self.as_str())
+	}
+
+	/// Allow creating and parsing [`DynamicStrongId`]s with this prefix, even if it is reserved
+	pub fn allow_reserved(self) -> Self {
+		Self {
+			allow_reserved: true,
+			..self
+		}
+	}
+}
+
+impl<'p> Debug for Prefix<'p> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Prefix")
+			.field("inner", &self.as_str())
+			.finish()
+	}
 }
 
 impl<'p> Display for Prefix<'p> {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-		write!(f, "{}", self.inner)
+		f.write_str(self.as_str())
+	}
+}
+
+impl<'p> PartialEq for Prefix<'p> {
+	fn eq(&self, other: &Self) -> bool {
+		self.as_str() == other.as_str()
+	}
+}
+
+impl<'p> Eq for Prefix<'p> {}
+
+impl<'p> PartialOrd for Prefix<'p> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<'p> Ord for Prefix<'p> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.as_str().cmp(other.as_str())
+	}
+}
+
+impl<'p> Hash for Prefix<'p> {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.as_str().hash(state)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'p> serde::Serialize for Prefix<'p> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		serializer.serialize_str(self.as_str())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'p, 'de> serde::Deserialize<'de> for Prefix<'p> {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		Prefix::new(String::deserialize(deserializer)?)
+			.map_err(|error| serde::de::Error::custom(error.to_string()))
 	}
 }
 
 impl<'p> From<&'p str> for Prefix<'p> {
 	fn from(value: &'p str) -> Self {
 		Self {
-			inner: Cow::Borrowed(value),
+			inner: PrefixInner::Borrowed(value),
+			allow_reserved: false,
 		}
 	}
 }
@@ -149,7 +440,17 @@ impl<'p> From<&'p str> for Prefix<'p> {
 impl<'p> From<String> for Prefix<'p> {
 	fn from(value: String) -> Self {
 		Self {
-			inner: Cow::Owned(value),
+			inner: PrefixInner::Owned(value),
+			allow_reserved: false,
+		}
+	}
+}
+
+impl<'p> From<Arc<str>> for Prefix<'p> {
+	fn from(value: Arc<str>) -> Self {
+		Self {
+			inner: PrefixInner::Shared(value),
+			allow_reserved: false,
 		}
 	}
 }
@@ -170,6 +471,202 @@ impl<'p, T: Id> DynamicStrongId<'p, T> {
 			suffix: value,
 		}
 	}
+
+	/// A description of the format of IDs with the given prefix, for showing to users
+	pub fn expected_format(prefix: Option<&str>) -> ExpectedFormat<'_> {
+		ExpectedFormat::new(prefix, T::ENCODED_LEN)
+	}
+
+	/// Encode the complete ID into the start of `dst` without allocating, returning the number of
+	/// bytes written
+	///
+	/// Fails with [`Error::BufferTooSmall`] if `dst` is shorter than the encoded ID.
+	///
+	/// ```rust
+	/// use strong_id::DynamicStrongId;
+	///
+	/// let id = DynamicStrongId::new("user", 3203u16).unwrap();
+	///
+	/// let mut buf = [0u8; 16];
+	/// let len = id.encode_into(&mut buf).unwrap();
+	/// assert_eq!(&buf[..len], b"user_0343");
+	/// ```
+	pub fn encode_into(&self, dst: &mut [u8]) -> Result<usize, Error> {
+		StrongId::encode_into(self, dst)
+	}
+
+	/// Create a new ID with a prefix from a value generated by `f`
+	///
+	/// The prefix is validated before `f` is called, so a value is not generated, e.g. fetched
+	/// from a database sequence, for an ID which can't be created.
+	///
+	/// ```rust
+	/// use strong_id::DynamicStrongId;
+	///
+	/// let mut sequence = 3202u16..;
+	/// let id = DynamicStrongId::new_with("user", || sequence.next().unwrap()).unwrap();
+	/// assert_eq!(id.to_string(), "user_0342");
+	///
+	/// assert!(DynamicStrongId::new_with("User", || sequence.next().unwrap()).is_err());
+	/// assert_eq!(sequence.next(), Some(3203));
+	/// ```
+	pub fn new_with<I, F>(prefix: I, f: F) -> Result<Self, Error>
+	where
+		I: Into<Prefix<'p>>,
+		F: FnOnce() -> T,
+	{
+		Ok(Self {
+			prefix: Some(map_prefix(prefix)?),
+			suffix: f(),
+		})
+	}
+
+	/// Create a new ID without a prefix from a value generated by `f`
+	pub fn new_plain_with<F: FnOnce() -> T>(f: F) -> Self {
+		Self::new_plain(f())
+	}
+
+	/// Parse an ID which must have the given prefix
+	///
+	/// Fails with [`Error::MissingPrefix`] or [`Error::InvalidPrefix`] when the parsed prefix does
+	/// not match, in the same way that generated StrongIds do.
+	///
+	/// ```rust
+	/// use strong_id::{DynamicStrongId, Error};
+	///
+	/// let id = DynamicStrongId::<u16>::parse_with_prefix("user", "user_0343").unwrap();
+	/// assert_eq!(id.to_string(), "user_0343");
+	///
+	/// assert_eq!(
+	///     DynamicStrongId::<u16>::parse_with_prefix("user", "order_0343"),
+	///     Err(Error::InvalidPrefix("user".into(), "order".into()))
+	/// );
+	/// ```
+	pub fn parse_with_prefix<I: Into<Prefix<'p>>>(prefix: I, value: &str) -> Result<Self, Error> {
+		let prefix = map_prefix(prefix)?;
+		let canonical = crate::_internal_canonicalize(value);
+
+		let suffix = match canonical.rsplit_once('_') {
+			None => return Err(Error::MissingPrefix(prefix.to_string())),
+			Some(("", _suffix)) => return Err(Error::MissingPrefix(prefix.to_string())),
+			Some((parsed_prefix, suffix)) => {
+				crate::_internal_check_delimiters(parsed_prefix)?;
+
+				if parsed_prefix != prefix.as_str() {
+					return Err(Error::InvalidPrefix(
+						prefix.to_string(),
+						parsed_prefix.to_string(),
+					));
+				}
+
+				T::decode(suffix)?
+			}
+		};
+
+		Ok(Self {
+			prefix: Some(prefix),
+			suffix,
+		})
+	}
+
+	/// Replace the prefix of the ID, keeping its suffix
+	///
+	/// ```rust
+	/// use strong_id::DynamicStrongId;
+	///
+	/// let id = DynamicStrongId::new("usr", 3203u16).unwrap();
+	/// assert_eq!(id.with_prefix("user").unwrap().to_string(), "user_0343");
+	/// ```
+	pub fn with_prefix<'q, I: Into<Prefix<'q>>>(
+		self,
+		prefix: I,
+	) -> Result<DynamicStrongId<'q, T>, Error> {
+		DynamicStrongId::new(prefix, self.suffix)
+	}
+
+	/// Remove the prefix of the ID, keeping its suffix
+	pub fn strip_prefix(self) -> Self {
+		Self {
+			prefix: None,
+			suffix: self.suffix,
+		}
+	}
+
+	/// Whether the ID has the given prefix
+	///
+	/// IDs without a prefix never match.
+	pub fn matches_prefix(&self, prefix: &str) -> bool {
+		match &self.prefix {
+			Some(own) => own.as_str() == prefix,
+			None => false,
+		}
+	}
+
+	/// The encoded suffix of the ID, without the prefix and separator
+	pub fn suffix_encoded(&self) -> String {
+		self.suffix.encode()
+	}
+
+	/// Parse an ID, also accepting uppercase prefixes and suffixes, e.g. for IDs typed by people or
+	/// passed through systems which fold case
+	///
+	/// ```rust
+	/// use strong_id::DynamicStrongId;
+	///
+	/// let voucher_id = DynamicStrongId::<u16>::from_str_relaxed("VOUCHER_03ZZ").unwrap();
+	/// assert_eq!(voucher_id.to_string(), "voucher_03zz");
+	/// ```
+	pub fn from_str_relaxed(value: &str) -> Result<Self, Error> {
+		match value.rsplit_once('_') {
+			Some((prefix, suffix)) => format!(
+				"{}_{}",
+				prefix.to_ascii_lowercase(),
+				crate::_internal_normalize_case::<Base32>(suffix)
+			)
+			.parse(),
+			None => crate::_internal_normalize_case::<Base32>(value).parse(),
+		}
+	}
+
+	/// The ID with an uppercase suffix, e.g. for printing on labels. The same as formatting the ID
+	/// with `{:#}`.
+	///
+	/// ```rust
+	/// use strong_id::DynamicStrongId;
+	///
+	/// let voucher_id = DynamicStrongId::new("voucher", 4095u16).unwrap();
+	/// assert_eq!(voucher_id.to_uppercase_string(), "voucher_03ZZ");
+	/// assert_eq!(format!("{voucher_id:#}"), "voucher_03ZZ");
+	/// ```
+	pub fn to_uppercase_string(&self) -> String {
+		format!("{self:#}")
+	}
+
+	/// Consume the ID and return the inner value
+	pub fn into_inner(self) -> T {
+		self.suffix
+	}
+
+	/// Consume the ID and return its prefix and inner value
+	pub fn into_parts(self) -> (Option<Prefix<'p>>, T) {
+		(self.prefix, self.suffix)
+	}
+
+	/// Convert into an ID which owns its prefix
+	pub fn into_owned(self) -> DynamicStrongIdBuf<T> {
+		DynamicStrongId {
+			prefix: self.prefix.map(Prefix::into_owned),
+			suffix: self.suffix,
+		}
+	}
+
+	/// Convert into an ID whose prefix is shared when cloned
+	pub fn into_shared(self) -> DynamicStrongIdBuf<T> {
+		DynamicStrongId {
+			prefix: self.prefix.map(Prefix::into_shared),
+			suffix: self.suffix,
+		}
+	}
 }
 
 #[cfg(feature = "uuid")]
@@ -181,6 +678,21 @@ impl<'p> From<DynamicStrongId<'p, Uuid>> for Uuid {
 
 // Utility functions for calling Uuid `new_` and `now_` functions when a [`DynamicStrongId`] is
 // backed by a [`Uuid`].
+#[cfg(feature = "uuid")]
+impl DynamicStrongId<'static, Uuid> {
+	/// Create an ID in `const` contexts, see `typeid!`
+	#[doc(hidden)]
+	pub const fn _internal_from_static(prefix: Option<&'static str>, v: u128) -> Self {
+		Self {
+			prefix: match prefix {
+				Some(prefix) => Some(Prefix::from_static(prefix)),
+				None => None,
+			},
+			suffix: Uuid::from_u128(v),
+		}
+	}
+}
+
 #[cfg(feature = "uuid")]
 impl<'p> DynamicStrongId<'p, Uuid> {
 	/// Create a new UUID-backed ID from a u128 with a prefix
@@ -199,6 +711,36 @@ impl<'p> DynamicStrongId<'p, Uuid> {
 		}
 	}
 
+	/// Borrow the underlying UUID bytes
+	pub fn as_bytes(&self) -> &[u8; 16] {
+		self.suffix.as_bytes()
+	}
+
+	/// Consume the ID and return the underlying UUID bytes
+	pub fn to_bytes(self) -> [u8; 16] {
+		self.suffix.into_bytes()
+	}
+
+	/// Format the underlying UUID in its hyphenated form, e.g.
+	/// `0189466f-a14b-77c2-9348-55ffc06f2188`
+	pub fn to_hyphenated_string(&self) -> String {
+		self.suffix.hyphenated().to_string()
+	}
+
+	/// A key for time-partitioned storage, derived from the timestamp of a time-based (e.g. v7)
+	/// UUID, such as `2024-06-01` for [`Granularity::Day`]
+	///
+	/// Returns `None` if the UUID is not time-based.
+	pub fn partition_key(&self, granularity: Granularity) -> Option<String> {
+		granularity.key(&self.suffix)
+	}
+
+	/// The [`partition_key`](DynamicStrongId::partition_key) as a number, such as `20240601` for
+	/// [`Granularity::Day`]
+	pub fn partition_number(&self, granularity: Granularity) -> Option<u32> {
+		granularity.number(&self.suffix)
+	}
+
 	#[cfg(feature = "uuid-v1")]
 	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v1")))]
 	/// Create a new UUID-backed ID by generating a v1 UUID with a prefix
@@ -431,6 +973,27 @@ impl<'p> DynamicStrongId<'p, Uuid> {
 		}
 	}
 
+	#[cfg(feature = "uuid-v7")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v7")))]
+	/// Create a new UUID-backed ID by generating a v7 UUID for a point in time with a prefix
+	///
+	/// See [`IntoTimestamp`]
+	pub fn new_v7_at<I: Into<Prefix<'p>>, D: IntoTimestamp>(
+		prefix: I,
+		at: D,
+	) -> Result<Self, Error> {
+		Self::new_v7(prefix, at.into_timestamp())
+	}
+
+	#[cfg(feature = "uuid-v7")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v7")))]
+	/// Create a new UUID-backed ID by generating a v7 UUID for a point in time without a prefix
+	///
+	/// See [`IntoTimestamp`]
+	pub fn new_v7_at_plain<D: IntoTimestamp>(at: D) -> Self {
+		Self::new_v7_plain(at.into_timestamp())
+	}
+
 	#[cfg(feature = "uuid-v8")]
 	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v8")))]
 	/// Create a new UUID-backed ID by generating a v7 UUID with a prefix
@@ -456,12 +1019,56 @@ impl<'p> DynamicStrongId<'p, Uuid> {
 	}
 }
 
+impl<'p, T: Id + PartialEq> PartialEq<T> for DynamicStrongId<'p, T> {
+	fn eq(&self, other: &T) -> bool {
+		self.suffix == *other
+	}
+}
+
+impl<'p, T: Id + PartialOrd> PartialOrd<T> for DynamicStrongId<'p, T> {
+	fn partial_cmp(&self, other: &T) -> Option<Ordering> {
+		self.suffix.partial_cmp(other)
+	}
+}
+
+// The reverse comparisons can't be implemented generically, because `T` would be uncovered
+macro_rules! impl_inner_cmp {
+	($($t:ty),*) => {
+		$(
+			impl<'p> PartialEq<DynamicStrongId<'p, $t>> for $t {
+				fn eq(&self, other: &DynamicStrongId<'p, $t>) -> bool {
+					*self == other.suffix
+				}
+			}
+
+			impl<'p> PartialOrd<DynamicStrongId<'p, $t>> for $t {
+				fn partial_cmp(&self, other: &DynamicStrongId<'p, $t>) -> Option<Ordering> {
+					self.partial_cmp(&other.suffix)
+				}
+			}
+		)*
+	};
+}
+
+impl_inner_cmp!(u8, u16, u32, u64, u128, usize);
+
+#[cfg(feature = "uuid")]
+impl_inner_cmp!(Uuid);
+
 impl<'p, T: Id> Display for DynamicStrongId<'p, T> {
 	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-		match &self.prefix {
-			Some(prefix) => write!(f, "{}_{}", prefix, self.suffix.encode()),
-			None => write!(f, "{}", self.suffix.encode()),
-		}
+		crate::_internal_fmt_canonical(f, |f| {
+			if let Some(prefix) = &self.prefix {
+				f.write_str(prefix.as_str())?;
+				f.write_str("_")?;
+			}
+
+			let mut suffix = self.suffix.encode();
+			if f.alternate() {
+				suffix.make_ascii_uppercase();
+			}
+			f.write_str(&suffix)
+		})
 	}
 }
 
@@ -470,15 +1077,15 @@ impl<'p, T: Id> core::str::FromStr for DynamicStrongId<'p, T> {
 
 	#[inline]
 	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		let canonical = crate::_internal_canonicalize(value);
+		let value: &str = &canonical;
 		let split = value.rsplit_once('_');
 
 		Ok(match split {
-			Some((prefix, _suffix)) if prefix.trim().is_empty() => {
-				return Err(Error::MissingPrefix(prefix.into()))
-			}
 			Some((prefix, suffix)) => Self {
-				prefix: Some(map_prefix(prefix.to_string())?),
-				suffix: T::decode(suffix)?,
+				prefix: Some(parse_prefix(prefix)?),
+				suffix: T::decode(suffix)
+					.map_err(|error| error._internal_offset(prefix.len() + 1))?,
 			},
 			None => Self {
 				prefix: None,
@@ -488,10 +1095,36 @@ impl<'p, T: Id> core::str::FromStr for DynamicStrongId<'p, T> {
 	}
 }
 
+impl<'p, T: Id> TryFrom<&str> for DynamicStrongId<'p, T> {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		value.parse()
+	}
+}
+
+impl<'p, T: Id> TryFrom<String> for DynamicStrongId<'p, T> {
+	type Error = Error;
+
+	fn try_from(value: String) -> Result<Self, Self::Error> {
+		value.parse()
+	}
+}
+
+impl<'p, T: Id> TryFrom<&[u8]> for DynamicStrongId<'p, T> {
+	type Error = Error;
+
+	fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+		core::str::from_utf8(value)
+			.map_err(|_| Error::InvalidUtf8)?
+			.parse()
+	}
+}
+
 impl<'p, T: Id> StrongId<T> for DynamicStrongId<'p, T> {
 	fn prefix(&self) -> Option<&str> {
 		match &self.prefix {
-			Some(prefix) => Some(prefix.inner.as_ref()),
+			Some(prefix) => Some(prefix.as_str()),
 			None => None,
 		}
 	}
@@ -501,6 +1134,16 @@ impl<'p, T: Id> StrongId<T> for DynamicStrongId<'p, T> {
 	}
 }
 
+impl<'p, T: Id> ErasedStrongId for DynamicStrongId<'p, T> {
+	fn erased_prefix(&self) -> Option<&str> {
+		self.prefix()
+	}
+
+	fn encoded(&self) -> String {
+		self.suffix.encode()
+	}
+}
+
 #[cfg(feature = "serde")]
 impl<'p, T: Id> serde::Serialize for DynamicStrongId<'p, T> {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -525,7 +1168,58 @@ impl<'p, 'de, T: Id> serde::Deserialize<'de> for DynamicStrongId<'p, T> {
 
 #[cfg(test)]
 mod tests {
-	use crate::{Base32Error, DynamicStrongId, Error, Prefix, StrongId};
+	use crate::{
+		group_by_prefix, Base32Error, DynamicStrongId, DynamicStrongIdBuf, Error, Prefix, StrongId,
+	};
+
+	#[cfg(feature = "delimited")]
+	#[test]
+	fn concat_prefix() {
+		use crate::concat_prefix;
+
+		const INVOICE: Prefix<'static> = Prefix::from_static(concat_prefix!("billing", "invoice"));
+		let id = DynamicStrongId::<u16>::new(INVOICE, 3203).unwrap();
+		assert_eq!(id.to_string(), "billing_invoice_0343");
+		assert_eq!(concat_prefix!("user"), "user");
+	}
+
+	#[test]
+	fn prefix_literal() {
+		use crate::{optional_prefix, prefix};
+
+		const USER: Prefix<'static> = prefix!("user");
+		assert_eq!(USER, Prefix::new("user").unwrap());
+		assert_eq!(
+			DynamicStrongId::<u16>::new(prefix!("user"), 3203)
+				.unwrap()
+				.to_string(),
+			"user_0343"
+		);
+
+		const PLAIN: Option<Prefix<'static>> = optional_prefix!("");
+		assert_eq!(PLAIN, None);
+		assert_eq!(optional_prefix!("user"), Some(USER));
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn typeid_literal() {
+		use crate::typeid;
+		use uuid::Uuid;
+
+		const SYSTEM_USER: DynamicStrongId<'static, Uuid> =
+			typeid!("user_01h455vb4pex5vsknk084sn02q");
+		assert_eq!(
+			SYSTEM_USER,
+			"user_01h455vb4pex5vsknk084sn02q"
+				.parse::<DynamicStrongId<Uuid>>()
+				.unwrap()
+		);
+
+		let plain = typeid!("7zzzzzzzzzzzzzzzzzzzzzzzzz");
+		assert_eq!(plain.prefix(), None);
+		assert_eq!(*plain.id(), Uuid::max());
+	}
 
 	#[test]
 	fn valid_u32() {
@@ -645,7 +1339,7 @@ mod tests {
 			Case("09d", Error::InvalidLength(2, 3)),
 			Case("8f", Error::Base32Error(Base32Error::InvalidFirstByte)),
 			Case("000", Error::InvalidLength(2, 3)),
-			Case("0l", Error::Base32Error(Base32Error::InvalidByte)),
+			Case("0l", Error::Base32Error(Base32Error::InvalidByte(1, 'l'))),
 			Case("Case_00", Error::IncorrectPrefixCharacter('C')),
 			Case("00numeric_00", Error::IncorrectPrefixCharacter('0')),
 			Case("case0_00", Error::IncorrectPrefixCharacter('0')),
@@ -657,6 +1351,69 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn multiple_underscores() {
+		let id = "foo_bar_0343".parse::<DynamicStrongId<u16>>();
+		if cfg!(feature = "delimited") {
+			assert_eq!(id.unwrap().prefix(), Some("foo_bar"));
+		} else {
+			assert_eq!(id, Err(Error::DelimitedPrefix("foo_bar".into())));
+		}
+
+		struct Case(&'static str, Error);
+		let cases = vec![
+			Case("foo__0343", Error::MisplacedDelimiter("foo_".into())),
+			Case("_foo_0343", Error::MisplacedDelimiter("_foo".into())),
+			Case("__0343", Error::MisplacedDelimiter("_".into())),
+			Case("_0343", Error::MissingPrefix("".into())),
+		];
+
+		for case in cases {
+			let error = case.0.parse::<DynamicStrongId<u16>>().unwrap_err();
+			assert_eq!(error, case.1, "{}", case.0);
+		}
+
+		let error = DynamicStrongId::<u16>::parse_with_prefix("foo", "foo_bar_0343").unwrap_err();
+		if cfg!(feature = "delimited") {
+			assert_eq!(error, Error::InvalidPrefix("foo".into(), "foo_bar".into()));
+		} else {
+			assert_eq!(error, Error::DelimitedPrefix("foo_bar".into()));
+		}
+		assert_eq!(
+			DynamicStrongId::<u16>::parse_with_prefix("foo", "foo__0343"),
+			Err(Error::MisplacedDelimiter("foo_".into()))
+		);
+	}
+
+	#[test]
+	fn invalid_prefix() {
+		struct Case(&'static str, Error);
+		let cases = vec![
+			Case(" _0343", Error::MissingPrefix(" ".into())),
+			Case("\u{3000}_0343", Error::MissingPrefix("\u{3000}".into())),
+			Case("Foo__0343", Error::MisplacedDelimiter("Foo_".into())),
+			Case("fOo_0343", Error::IncorrectPrefixCharacter('O')),
+			Case("f0o_0343", Error::IncorrectPrefixCharacter('0')),
+			Case("fé_0343", Error::IncorrectPrefixCharacter('\u{c3}')),
+			Case(
+				"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaA_0343",
+				Error::PrefixTooLong(64),
+			),
+		];
+
+		for case in cases {
+			let error = case.0.parse::<DynamicStrongId<u16>>().unwrap_err();
+			assert_eq!(error, case.1, "{}", case.0);
+		}
+
+		if !cfg!(feature = "delimited") {
+			assert_eq!(
+				"f_O_0343".parse::<DynamicStrongId<u16>>(),
+				Err(Error::DelimitedPrefix("f_O".into()))
+			);
+		}
+	}
+
 	#[cfg(feature = "serde")]
 	#[test]
 	fn u32_prefix_serde() {
@@ -667,4 +1424,221 @@ mod tests {
 		let value = serde_json::to_string(&value).unwrap();
 		assert_eq!("\"prefix_000009d\"", value);
 	}
+
+	#[test]
+	fn into_parts() {
+		let id = DynamicStrongId::new("dyn", 301u32).unwrap();
+		assert_eq!(id.clone().into_inner(), 301);
+		assert_eq!(id.into_parts(), (Some("dyn".into()), 301));
+
+		let id = DynamicStrongId::new_plain(301u32);
+		assert_eq!(id.into_parts(), (None, 301));
+	}
+
+	#[test]
+	fn into_owned() {
+		struct State {
+			id: DynamicStrongIdBuf<u32>,
+		}
+
+		let prefix = String::from("dyn");
+		let id = DynamicStrongId::new(prefix.as_str(), 301u32).unwrap();
+		let state = State {
+			id: id.into_owned(),
+		};
+		drop(prefix);
+
+		assert_eq!(state.id.to_string(), "dyn_000009d");
+		assert_eq!(
+			"dyn_000009d".parse::<DynamicStrongIdBuf<u32>>().unwrap(),
+			state.id
+		);
+	}
+
+	#[test]
+	fn prefix() {
+		const PREFIX: Prefix<'static> = Prefix::from_static("dyn");
+
+		assert_eq!(PREFIX.as_str(), "dyn");
+		assert_eq!(Prefix::new("dyn").unwrap(), PREFIX);
+		assert_eq!(Prefix::new(String::from("dyn")).unwrap(), PREFIX);
+		assert_eq!(
+			Prefix::new("Dyn"),
+			Err(Error::IncorrectPrefixCharacter('D'))
+		);
+		assert_eq!(
+			Prefix::new("_dyn"),
+			Err(Error::IncorrectPrefixCharacter('_'))
+		);
+		assert_eq!(Prefix::new(""), Err(Error::PrefixExpected));
+		assert_eq!(Prefix::new("a".repeat(64)), Err(Error::PrefixTooLong(64)));
+
+		let unchecked = Prefix::new_unchecked("Dyn");
+		assert_eq!(
+			DynamicStrongId::new(unchecked, 301u32),
+			Err(Error::IncorrectPrefixCharacter('D'))
+		);
+
+		let id = DynamicStrongId::new(PREFIX, 301u32).unwrap();
+		assert_eq!(id.to_string(), "dyn_000009d");
+	}
+
+	#[test]
+	fn into_shared() {
+		use std::sync::Arc;
+
+		let prefix: Arc<str> = Arc::from("dyn");
+		let id = DynamicStrongId::new(prefix.clone(), 301u32).unwrap();
+		let cloned = id.clone();
+		assert_eq!(Arc::strong_count(&prefix), 3);
+		assert_eq!(cloned.to_string(), "dyn_000009d");
+
+		let parsed = "dyn_000009d"
+			.parse::<DynamicStrongId<u32>>()
+			.unwrap()
+			.into_shared();
+		assert_eq!(parsed, id);
+		assert_eq!(parsed.clone().prefix(), Some("dyn"));
+	}
+
+	#[test]
+	fn parse_with_prefix() {
+		struct Case(&'static str, Result<u32, Error>);
+		let cases = vec![
+			Case("dyn_000009d", Ok(301)),
+			Case("000009d", Err(Error::MissingPrefix("dyn".into()))),
+			Case("_000009d", Err(Error::MissingPrefix("dyn".into()))),
+			Case(
+				"other_000009d",
+				Err(Error::InvalidPrefix("dyn".into(), "other".into())),
+			),
+			Case("dyn_09d", Err(Error::InvalidLength(7, 3))),
+		];
+
+		for case in cases {
+			let parsed = DynamicStrongId::<u32>::parse_with_prefix("dyn", case.0);
+			assert_eq!(parsed.map(|id| *id.id()), case.1);
+		}
+
+		assert_eq!(
+			DynamicStrongId::<u32>::parse_with_prefix("Dyn", "Dyn_000009d"),
+			Err(Error::IncorrectPrefixCharacter('D'))
+		);
+	}
+
+	#[test]
+	fn prefix_mutation() {
+		let id = DynamicStrongId::new("usr", 301u32).unwrap();
+		assert!(id.matches_prefix("usr"));
+		assert!(!id.matches_prefix("user"));
+
+		let id = id.with_prefix("user").unwrap();
+		assert!(id.matches_prefix("user"));
+		assert_eq!(id.to_string(), "user_000009d");
+
+		assert_eq!(
+			id.clone().with_prefix("User"),
+			Err(Error::IncorrectPrefixCharacter('U'))
+		);
+
+		let id = id.strip_prefix();
+		assert!(!id.matches_prefix("user"));
+		assert!(!id.matches_prefix(""));
+		assert_eq!(id.to_string(), "000009d");
+
+		let id = id.with_prefix(String::from("order")).unwrap();
+		assert_eq!(id.to_string(), "order_000009d");
+	}
+
+	#[test]
+	fn compare_inner() {
+		let id = DynamicStrongId::new("user", 301u32).unwrap();
+		assert!(id == 301);
+		assert!(301 == id);
+		assert!(id < 302);
+		assert!(300 < id);
+		assert!(DynamicStrongId::new_plain(301u32) == 301);
+	}
+
+	#[test]
+	fn suffix_encoded() {
+		let id = DynamicStrongId::new("dyn", 301u32).unwrap();
+		assert_eq!(id.suffix_encoded(), "000009d");
+		assert_eq!(
+			DynamicStrongId::new_plain(301u32).suffix_encoded(),
+			"000009d"
+		);
+	}
+
+	#[test]
+	fn try_from() {
+		let id = DynamicStrongId::new("dyn", 301u32).unwrap();
+		assert_eq!(DynamicStrongId::try_from("dyn_000009d"), Ok(id.clone()));
+		assert_eq!(
+			DynamicStrongId::try_from(String::from("dyn_000009d")),
+			Ok(id.clone())
+		);
+		assert_eq!(DynamicStrongId::try_from(&b"dyn_000009d"[..]), Ok(id));
+		assert_eq!(
+			DynamicStrongId::<u32>::try_from(&b"dyn_\xff00009d"[..]),
+			Err(Error::InvalidUtf8)
+		);
+	}
+
+	#[test]
+	fn new_with() {
+		let id = DynamicStrongId::new_with("dyn", || 301u32).unwrap();
+		assert_eq!(id.to_string(), "dyn_000009d");
+
+		let id = DynamicStrongId::new_plain_with(|| 301u32);
+		assert_eq!(id.to_string(), "000009d");
+
+		let mut called = false;
+		let result = DynamicStrongId::new_with("Dyn", || {
+			called = true;
+			301u32
+		});
+		assert_eq!(result, Err(Error::IncorrectPrefixCharacter('D')));
+		assert!(!called);
+	}
+
+	#[test]
+	fn expected_format() {
+		assert_eq!(
+			DynamicStrongId::<u32>::expected_format(Some("dyn")).to_string(),
+			"\"dyn_\" followed by 7 characters of [0-9a-hjkmnp-tv-z]"
+		);
+		assert_eq!(
+			DynamicStrongId::<u32>::expected_format(None).to_string(),
+			"7 characters of [0-9a-hjkmnp-tv-z]"
+		);
+	}
+
+	#[test]
+	fn group_by_prefix_order() {
+		let groups =
+			group_by_prefix::<u16, _, _>(["user_0343", "0002", "team_0001", "user_0342", "0001"])
+				.unwrap();
+
+		let grouped = groups
+			.iter()
+			.map(|(prefix, ids)| {
+				let prefix = prefix.as_ref().map(Prefix::as_str);
+				(prefix, ids.iter().map(|id| *id.id()).collect::<Vec<_>>())
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(
+			grouped,
+			vec![
+				(None, vec![2, 1]),
+				(Some("team"), vec![1]),
+				(Some("user"), vec![3203, 3202]),
+			]
+		);
+
+		assert_eq!(
+			group_by_prefix::<u16, _, _>(["user_0343", "user_"]),
+			Err(Error::InvalidLength(4, 0))
+		);
+	}
 }
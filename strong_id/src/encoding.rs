@@ -0,0 +1,674 @@
+use crate::{base32, Base32Error};
+use core::marker::PhantomData;
+
+/// How the suffixes of IDs are encoded
+///
+/// The suffix of an ID is the encoding of the big-endian bytes of its value, see [`Id::to_bytes`](crate::Id::to_bytes).
+/// Generated IDs use [`Base32`] unless another encoding is selected with
+/// `#[strong_id(encoding = ...)]`.
+///
+/// The encoded length of `n` bytes is the number of symbols needed to hold `n * 8` bits and
+/// `MIN_PAD_BITS` leading zero bits, so that values of a fixed size always encode to the same length,
+/// followed by `CHECK_LEN` check symbols.
+pub trait Encoding {
+	/// The symbols of the encoding, in order of their value
+	///
+	/// Symbols must be ascii. Suffixes which may contain `_`, which separates prefixes from suffixes,
+	/// are split from the prefix by their length, see [`Base64Url`].
+	const SYMBOLS: &'static [u8];
+	/// The number of bits held by each symbol
+	const SYMBOL_BITS: usize;
+	/// The minimum number of leading zero bits the encoding is padded with
+	const MIN_PAD_BITS: usize = 0;
+	/// The number of trailing check symbols
+	const CHECK_LEN: usize = 0;
+	/// Whether suffixes are uppercased when IDs are formatted with `{:#}`. Encodings with symbols
+	/// which differ only in case, e.g. [`Base64Url`], are never uppercased.
+	const UPPERCASE: bool = true;
+
+	/// The length of the encoding of `byte_len` bytes
+	fn encoded_len(byte_len: usize) -> usize {
+		encoded_len(byte_len, Self::MIN_PAD_BITS, Self::SYMBOL_BITS) + Self::CHECK_LEN
+	}
+
+	/// Encode bytes into a slice of exactly [`encoded_len`](Self::encoded_len) bytes
+	///
+	/// Fails with [`Base32Error::InvalidOutBytesSize`] if `dst` has another length.
+	fn encode(src: &[u8], dst: &mut [u8]) -> Result<(), Base32Error>;
+
+	/// Decode an encoded value into a slice of bytes, of the size of the encoded value
+	fn decode(src: &[u8], dst: &mut [u8]) -> Result<(), Base32Error>;
+}
+
+/// Check that `dst` is of the encoded length of `src`, for implementations of [`Encoding::encode`]
+fn check_encoded_len<E: Encoding>(src: &[u8], dst: &[u8]) -> Result<(), Base32Error> {
+	let expected = E::encoded_len(src.len());
+	if dst.len() != expected {
+		return Err(Base32Error::InvalidOutBytesSize(expected, dst.len()));
+	}
+
+	Ok(())
+}
+
+/// [`Encoding::encoded_len`], which can be used in consts
+#[doc(hidden)]
+pub const fn encoded_len(byte_len: usize, min_pad_bits: usize, symbol_bits: usize) -> usize {
+	let bits = byte_len * 8 + min_pad_bits;
+	bits / symbol_bits + (bits % symbol_bits != 0) as usize
+}
+
+/// The symbols of a base32 encoding, see [`Base32`]
+// the decode map is first, so that it starts on a cache line and only the lines holding the
+// symbols of an ID are loaded when decoding it
+#[derive(Clone, Copy, Debug)]
+#[repr(C, align(64))]
+pub struct Alphabet {
+	pub(crate) decode_map: [u8; 256],
+	pub(crate) symbols: [u8; 32],
+}
+
+impl Alphabet {
+	/// Create an alphabet from 32 symbols, in order of their value
+	///
+	/// Panics, at compile-time when used in a const, if a symbol is repeated, is not ascii or is
+	/// `_`.
+	pub const fn new(symbols: &[u8; 32]) -> Self {
+		let mut decode_map = [0xFF; 256];
+
+		let mut index = 0;
+		while index < symbols.len() {
+			let symbol = symbols[index];
+			assert!(
+				symbol.is_ascii() && symbol != b'_',
+				"alphabet symbols must be ascii and may not be `_`"
+			);
+			assert!(
+				decode_map[symbol as usize] == 0xFF,
+				"alphabet symbols must be unique"
+			);
+
+			decode_map[symbol as usize] = index as u8;
+			index += 1;
+		}
+
+		Self {
+			symbols: *symbols,
+			decode_map,
+		}
+	}
+
+	/// Also decode `alias` as `symbol`, e.g. when they're easily confused
+	///
+	/// Encoding always produces the symbols of the alphabet. Panics if `symbol` is not part of the
+	/// alphabet, or `alias` already decodes to another symbol.
+	pub const fn with_alias(mut self, alias: u8, symbol: u8) -> Self {
+		let value = self.decode_map[symbol as usize];
+		assert!(value != 0xFF, "aliased symbol is not part of the alphabet");
+		assert!(
+			alias.is_ascii() && alias != b'_',
+			"aliases must be ascii and may not be `_`"
+		);
+		assert!(
+			self.decode_map[alias as usize] == 0xFF || self.decode_map[alias as usize] == value,
+			"alias already decodes to another symbol"
+		);
+
+		self.decode_map[alias as usize] = value;
+		self
+	}
+
+	/// Also decode the other case of each alphabetic symbol
+	pub const fn case_insensitive(mut self) -> Self {
+		let mut index = 0;
+		while index < self.symbols.len() {
+			let symbol = self.symbols[index];
+			if symbol.is_ascii_lowercase() {
+				self = self.with_alias(symbol.to_ascii_uppercase(), symbol);
+			} else if symbol.is_ascii_uppercase() {
+				self = self.with_alias(symbol.to_ascii_lowercase(), symbol);
+			}
+			index += 1;
+		}
+
+		self
+	}
+}
+
+/// The alphabet of a [`Base32`] encoding
+///
+/// ```rust
+/// use strong_id::{strong_id, Alphabet, Base32, Base32Alphabet};
+///
+/// pub struct Upper;
+///
+/// impl Base32Alphabet for Upper {
+///     const ALPHABET: Alphabet = Alphabet::new(b"0123456789ABCDEFGHJKMNPQRSTVWXYZ");
+/// }
+///
+/// strong_id! {
+///     #[strong_id(encoding = Base32<Upper>)]
+///     pub struct UserId(u16 => "user")
+/// }
+///
+/// let user_id = UserId::from(4095);
+/// assert_eq!(user_id.to_string(), "user_03ZZ");
+/// assert_eq!("user_03ZZ".parse::<UserId>().unwrap(), user_id);
+/// assert_eq!(
+///     UserId::EXPECTED_FORMAT.to_string(),
+///     "\"user_\" followed by 4 characters of [0-9A-HJKMNP-TV-Z]"
+/// );
+/// ```
+pub trait Base32Alphabet {
+	/// The symbols of the alphabet
+	const ALPHABET: Alphabet;
+	/// The additional check symbols for the values 32 to 36, see [`Base32Check`]
+	const CHECK_SYMBOLS: [u8; 5] = *b"*~$=u";
+}
+
+/// The lowercase [Crockford](https://www.crockford.com/base32.html) alphabet used by
+/// [TypeID](https://github.com/jetpack-io/typeid), `0123456789abcdefghjkmnpqrstvwxyz`
+pub struct Crockford;
+
+impl Base32Alphabet for Crockford {
+	const ALPHABET: Alphabet = Alphabet::new(&base32::ALPHABET);
+}
+
+/// The [`Crockford`] alphabet, which also decodes uppercase symbols, `i` and `l` as `1` and `o` as
+/// `0`, following the original Crockford rules
+///
+/// Useful for IDs which are transcribed by people, or mangled by fonts in emails and PDFs. IDs are
+/// still encoded with the canonical lowercase symbols.
+///
+/// ```rust
+/// use strong_id::{strong_id, Base32, CrockfordLenient};
+///
+/// strong_id! {
+///     #[strong_id(encoding = Base32<CrockfordLenient>)]
+///     pub struct UserId(u16 => "user")
+/// }
+///
+/// let user_id = "user_O3IL".parse::<UserId>().unwrap();
+/// assert_eq!(user_id.to_string(), "user_0311");
+/// ```
+pub struct CrockfordLenient;
+
+impl Base32Alphabet for CrockfordLenient {
+	const ALPHABET: Alphabet = Crockford::ALPHABET
+		.case_insensitive()
+		.with_alias(b'i', b'1')
+		.with_alias(b'I', b'1')
+		.with_alias(b'l', b'1')
+		.with_alias(b'L', b'1')
+		.with_alias(b'o', b'0')
+		.with_alias(b'O', b'0');
+}
+
+/// Base32 with the given alphabet, padded with at least one leading zero bit
+///
+/// This is the default encoding of IDs, with the [`Crockford`] alphabet. See also the
+/// [`base32`](crate::base32) module.
+pub struct Base32<A = Crockford>(PhantomData<A>);
+
+impl<A: Base32Alphabet> Encoding for Base32<A> {
+	const SYMBOLS: &'static [u8] = &A::ALPHABET.symbols;
+	const SYMBOL_BITS: usize = 5;
+	const MIN_PAD_BITS: usize = 1;
+
+	fn encode(src: &[u8], dst: &mut [u8]) -> Result<(), Base32Error> {
+		base32::encode_alphabet(src, dst, &A::ALPHABET.symbols)
+	}
+
+	fn decode(src: &[u8], dst: &mut [u8]) -> Result<(), Base32Error> {
+		base32::decode_alphabet(src, dst, &A::ALPHABET.decode_map)
+	}
+}
+
+/// [`Base32`] followed by a [Crockford](https://www.crockford.com/base32.html) check symbol, which
+/// catches single-character typos in IDs entered by people
+///
+/// The check symbol is the value modulo 37, encoded with the symbols of the alphabet and the
+/// additional [`Base32Alphabet::CHECK_SYMBOLS`], `*~$=u`. Note that these IDs don't satisfy the
+/// TypeID specification.
+///
+/// ```rust
+/// use strong_id::{strong_id, Base32Check, Base32Error, Error};
+///
+/// strong_id! {
+///     #[strong_id(encoding = Base32Check)]
+///     pub struct TicketId(u16 => "ticket")
+/// }
+///
+/// let ticket_id = TicketId::from(3203);
+/// assert_eq!(ticket_id.to_string(), "ticket_0343n");
+/// assert_eq!("ticket_0343n".parse::<TicketId>().unwrap(), ticket_id);
+///
+/// assert_eq!(
+///     "ticket_0334n".parse::<TicketId>(),
+///     Err(Error::Base32Error(Base32Error::InvalidCheckSymbol))
+/// );
+/// ```
+pub struct Base32Check<A = Crockford>(PhantomData<A>);
+
+impl<A: Base32Alphabet> Base32Check<A> {
+	/// The value of the bytes modulo 37
+	fn checksum(bytes: &[u8]) -> u8 {
+		bytes
+			.iter()
+			.fold(0u16, |checksum, byte| (checksum * 256 + *byte as u16) % 37) as u8
+	}
+
+	fn check_symbol(checksum: u8) -> u8 {
+		match checksum.checked_sub(32) {
+			Some(index) => A::CHECK_SYMBOLS[index as usize],
+			None => A::ALPHABET.symbols[checksum as usize],
+		}
+	}
+
+	fn check_value(symbol: u8) -> Option<u8> {
+		match A::ALPHABET.decode_map[symbol as usize] {
+			0xFF => A::CHECK_SYMBOLS
+				.iter()
+				.position(|check_symbol| *check_symbol == symbol)
+				.map(|index| index as u8 + 32),
+			value => Some(value),
+		}
+	}
+}
+
+impl<A: Base32Alphabet> Encoding for Base32Check<A> {
+	const SYMBOLS: &'static [u8] = &with_check_symbols(A::ALPHABET.symbols, A::CHECK_SYMBOLS);
+	const SYMBOL_BITS: usize = 5;
+	const MIN_PAD_BITS: usize = 1;
+	const CHECK_LEN: usize = 1;
+
+	fn encode(src: &[u8], dst: &mut [u8]) -> Result<(), Base32Error> {
+		check_encoded_len::<Self>(src, dst)?;
+
+		if let Some((check, dst)) = dst.split_last_mut() {
+			base32::encode_alphabet(src, dst, &A::ALPHABET.symbols)?;
+			*check = Self::check_symbol(Self::checksum(src));
+		}
+		Ok(())
+	}
+
+	fn decode(src: &[u8], dst: &mut [u8]) -> Result<(), Base32Error> {
+		let (check, src) = src.split_last().ok_or(Base32Error::Empty)?;
+		base32::decode_alphabet(src, dst, &A::ALPHABET.decode_map)?;
+
+		match Self::check_value(*check) {
+			Some(value) if value == Self::checksum(dst) => Ok(()),
+			Some(_) => Err(Base32Error::InvalidCheckSymbol),
+			None => Err(Base32Error::invalid_byte(src.len(), *check)),
+		}
+	}
+}
+
+/// Lowercase hexadecimal, for interop with systems which store IDs as hex strings, e.g. trace IDs
+///
+/// Uppercase digits are also decoded, and IDs are always encoded lowercase.
+///
+/// ```rust
+/// use strong_id::{strong_id, Hex};
+///
+/// strong_id! {
+///     #[strong_id(encoding = Hex)]
+///     pub struct TraceId(u64 => "trace")
+/// }
+///
+/// let trace_id = "trace_4BF92F3577B34DA6".parse::<TraceId>().unwrap();
+/// assert_eq!(trace_id, TraceId::from(0x4bf9_2f35_77b3_4da6));
+/// assert_eq!(trace_id.to_string(), "trace_4bf92f3577b34da6");
+/// ```
+pub struct Hex;
+
+impl Hex {
+	const DIGITS: [u8; 16] = *b"0123456789abcdef";
+
+	fn decode_symbol(src: &[u8], index: usize) -> Result<u8, Base32Error> {
+		match src.get(index).copied().unwrap_or_default() {
+			symbol @ b'0'..=b'9' => Ok(symbol - b'0'),
+			symbol @ b'a'..=b'f' => Ok(symbol - b'a' + 10),
+			symbol @ b'A'..=b'F' => Ok(symbol - b'A' + 10),
+			symbol => Err(Base32Error::invalid_byte(index, symbol)),
+		}
+	}
+}
+
+impl Encoding for Hex {
+	const SYMBOLS: &'static [u8] = &Hex::DIGITS;
+	const SYMBOL_BITS: usize = 4;
+
+	fn encode(src: &[u8], dst: &mut [u8]) -> Result<(), Base32Error> {
+		check_encoded_len::<Self>(src, dst)?;
+
+		for (byte, pair) in src.iter().zip(dst.chunks_exact_mut(2)) {
+			pair[0] = Hex::DIGITS[(byte >> 4) as usize];
+			pair[1] = Hex::DIGITS[(byte & 0x0F) as usize];
+		}
+		Ok(())
+	}
+
+	fn decode(src: &[u8], dst: &mut [u8]) -> Result<(), Base32Error> {
+		if src.is_empty() {
+			return Err(Base32Error::Empty);
+		}
+		if src.len() != dst.len() * 2 {
+			return Err(Base32Error::InvalidOutBytesSize(src.len() / 2, dst.len()));
+		}
+
+		for (index, byte) in dst.iter_mut().enumerate() {
+			*byte =
+				Hex::decode_symbol(src, index * 2)? << 4 | Hex::decode_symbol(src, index * 2 + 1)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Unpadded [base64url](https://www.rfc-editor.org/rfc/rfc4648#section-5), for compact IDs in JWT
+/// claims and HTTP headers
+///
+/// The suffix is the base64url encoding of the bytes of the value, so it can be decoded by any
+/// base64url implementation. Base64url suffixes may contain `_`, so IDs are split from their prefix
+/// by the length of the suffix rather than at the last `_`. Unlike [`Base32`], the encoded IDs don't
+/// sort in the order of their values, and they don't satisfy the TypeID specification.
+///
+/// ```rust
+/// use strong_id::{strong_id, Base64Url};
+///
+/// strong_id! {
+///     #[strong_id(encoding = Base64Url)]
+///     pub struct SessionId(u128 => "session")
+/// }
+///
+/// let session_id = SessionId::from(u128::MAX - 0x20);
+/// assert_eq!(session_id.to_string(), "session_____________________3w");
+/// assert_eq!(
+///     "session_____________________3w".parse::<SessionId>().unwrap(),
+///     session_id
+/// );
+/// ```
+pub struct Base64Url;
+
+impl Base64Url {
+	const DIGITS: [u8; 64] = *b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+	fn decode_symbol(symbol: u8) -> Option<u32> {
+		match symbol {
+			b'A'..=b'Z' => Some((symbol - b'A') as u32),
+			b'a'..=b'z' => Some((symbol - b'a') as u32 + 26),
+			b'0'..=b'9' => Some((symbol - b'0') as u32 + 52),
+			b'-' => Some(62),
+			b'_' => Some(63),
+			_ => None,
+		}
+	}
+}
+
+impl Encoding for Base64Url {
+	const SYMBOLS: &'static [u8] = &Base64Url::DIGITS;
+	const SYMBOL_BITS: usize = 6;
+	const UPPERCASE: bool = false;
+
+	fn encode(src: &[u8], dst: &mut [u8]) -> Result<(), Base32Error> {
+		check_encoded_len::<Self>(src, dst)?;
+
+		let mut symbols = dst.iter_mut();
+		let mut buffer = 0u32;
+		let mut bits = 0;
+
+		for byte in src {
+			buffer = buffer << 8 | *byte as u32;
+			bits += 8;
+
+			while bits >= 6 {
+				bits -= 6;
+				if let Some(symbol) = symbols.next() {
+					*symbol = Base64Url::DIGITS[(buffer >> bits & 0x3F) as usize];
+				}
+			}
+			buffer &= (1 << bits) - 1;
+		}
+
+		// the remaining bits are padded with trailing zero bits
+		if let Some(symbol) = symbols.next() {
+			*symbol = Base64Url::DIGITS[(buffer << (6 - bits) & 0x3F) as usize];
+		}
+		Ok(())
+	}
+
+	fn decode(src: &[u8], dst: &mut [u8]) -> Result<(), Base32Error> {
+		if src.is_empty() {
+			return Err(Base32Error::Empty);
+		}
+		let expected = Self::encoded_len(dst.len());
+		if src.len() != expected {
+			return Err(Base32Error::InvalidOutBytesSize(expected, src.len()));
+		}
+
+		let mut bytes = dst.iter_mut();
+		let mut buffer = 0u32;
+		let mut bits = 0;
+
+		for (index, symbol) in src.iter().enumerate() {
+			let value = Base64Url::decode_symbol(*symbol)
+				.ok_or_else(|| Base32Error::invalid_byte(index, *symbol))?;
+			buffer = buffer << 6 | value;
+			bits += 6;
+
+			if bits >= 8 {
+				bits -= 8;
+				if let Some(byte) = bytes.next() {
+					*byte = (buffer >> bits) as u8;
+				}
+				buffer &= (1 << bits) - 1;
+			}
+		}
+
+		// reject non-canonical encodings, so that each value has a single encoding
+		if buffer != 0 {
+			let index = src.len() - 1;
+			return Err(Base32Error::invalid_byte(index, src[index]));
+		}
+
+		Ok(())
+	}
+}
+
+const fn with_check_symbols(symbols: [u8; 32], check_symbols: [u8; 5]) -> [u8; 37] {
+	let mut all = [0; 37];
+
+	let mut index = 0;
+	while index < all.len() {
+		all[index] = if index < 32 {
+			symbols[index]
+		} else {
+			check_symbols[index - 32]
+		};
+		index += 1;
+	}
+
+	all
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{
+		Alphabet, Base32, Base32Alphabet, Base32Check, Base64Url, CrockfordLenient, Encoding, Hex,
+	};
+	use crate::{base32, Id};
+
+	struct Reversed;
+
+	impl Base32Alphabet for Reversed {
+		const ALPHABET: Alphabet = Alphabet::new(b"zyxwvtsrqpnmkjhgfedcba9876543210");
+	}
+
+	#[test]
+	fn encoded_len() {
+		for len in 0..=32 {
+			assert_eq!(<Base32>::encoded_len(len), base32::encoded_len(len));
+		}
+	}
+
+	#[test]
+	fn custom_alphabet() {
+		assert_eq!(3203u16.encode_with::<Base32<Reversed>>(), "zwvw");
+		assert_eq!(u16::decode_with::<Base32<Reversed>, _>("zwvw"), Ok(3203));
+		assert_eq!(
+			u16::decode_with::<Base32<Reversed>, _>("0343"),
+			Err(crate::Error::Base32Error(
+				crate::Base32Error::InvalidFirstByte
+			))
+		);
+	}
+
+	#[test]
+	fn lenient() {
+		type Lenient = Base32<CrockfordLenient>;
+
+		for (encoded, canonical) in [("O343", "0343"), ("o343", "0343"), ("1ZYX", "1zyx")] {
+			assert_eq!(
+				u16::decode_with::<Lenient, _>(encoded),
+				u16::decode(canonical)
+			);
+		}
+		assert_eq!(u16::decode_with::<Lenient, _>("01il"), Ok(0x0421));
+		assert_eq!(u16::decode_with::<Lenient, _>("01IL"), Ok(0x0421));
+		assert_eq!(
+			u16::decode_with::<Lenient, _>("0u00"),
+			Err(crate::Error::Base32Error(crate::Base32Error::InvalidByte(
+				1, 'u'
+			)))
+		);
+		assert_eq!(0x0421u16.encode_with::<Lenient>(), "0111");
+	}
+
+	#[test]
+	fn check_symbol() {
+		type Check = Base32Check;
+
+		assert_eq!(<Check>::encoded_len(2), 5);
+		assert_eq!(0u16.encode_with::<Check>(), "00000");
+		// 36 is the largest check value
+		assert_eq!(36u16.encode_with::<Check>(), "0014u");
+		assert_eq!(u16::decode_with::<Check, _>("0014u"), Ok(36));
+		assert_eq!(32u16.encode_with::<Check>(), "0010*");
+		assert_eq!(u128::MAX.encode_with::<Check>().len(), 27);
+
+		for value in [1u128, 301, u64::MAX as u128, u128::MAX] {
+			let encoded = value.encode_with::<Check>();
+			assert_eq!(u128::decode_with::<Check, _>(&encoded), Ok(value));
+		}
+
+		// single-character substitutions and transpositions are caught
+		assert_eq!(
+			u16::decode_with::<Check, _>("0344n"),
+			Err(crate::Error::Base32Error(
+				crate::Base32Error::InvalidCheckSymbol
+			))
+		);
+		assert_eq!(
+			u16::decode_with::<Check, _>("0433n"),
+			Err(crate::Error::Base32Error(
+				crate::Base32Error::InvalidCheckSymbol
+			))
+		);
+		assert_eq!(
+			u16::decode_with::<Check, _>("0343!"),
+			Err(crate::Error::Base32Error(crate::Base32Error::InvalidByte(
+				4, '!'
+			)))
+		);
+	}
+
+	#[test]
+	fn hex() {
+		assert_eq!(<Hex>::encoded_len(16), 32);
+		assert_eq!(0u8.encode_with::<Hex>(), "00");
+		assert_eq!(0xABCDu16.encode_with::<Hex>(), "abcd");
+		assert_eq!(i32::MAX.encode_with::<Hex>(), "ffffffff");
+
+		for encoded in ["abcd", "ABCD", "aBcD"] {
+			assert_eq!(u16::decode_with::<Hex, _>(encoded), Ok(0xABCD));
+		}
+		assert_eq!(
+			u16::decode_with::<Hex, _>("abcg"),
+			Err(crate::Error::Base32Error(crate::Base32Error::InvalidByte(
+				3, 'g'
+			)))
+		);
+		assert_eq!(
+			u16::decode_with::<Hex, _>("abc"),
+			Err(crate::Error::InvalidLength(4, 3))
+		);
+
+		let uuid_like = 0x0189_4668_3f8f_7f45_8a1a_ca07_6061_8c67u128;
+		assert_eq!(
+			u128::decode_with::<Hex, _>(uuid_like.encode_with::<Hex>()),
+			Ok(uuid_like)
+		);
+	}
+
+	#[test]
+	fn base64url() {
+		type B64 = Base64Url;
+
+		assert_eq!(<B64>::encoded_len(2), 3);
+		assert_eq!(<B64>::encoded_len(16), 22);
+		assert_eq!(0u8.encode_with::<B64>(), "AA");
+		// the same as other base64url implementations, e.g. `[0xfb, 0xff]` is `-_8`
+		assert_eq!(0xFBFFu16.encode_with::<B64>(), "-_8");
+		assert_eq!(0x004D_616Eu32.encode_with::<B64>(), "AE1hbg");
+
+		for value in [0u128, 1, 301, u64::MAX as u128, u128::MAX] {
+			let encoded = value.encode_with::<B64>();
+			assert_eq!(u128::decode_with::<B64, _>(&encoded), Ok(value));
+		}
+
+		// the trailing bits of `-_9` are not zero
+		assert_eq!(
+			u16::decode_with::<B64, _>("-_9"),
+			Err(crate::Error::Base32Error(crate::Base32Error::InvalidByte(
+				2, '9'
+			)))
+		);
+		assert_eq!(
+			u16::decode_with::<B64, _>("-_="),
+			Err(crate::Error::Base32Error(crate::Base32Error::InvalidByte(
+				2, '='
+			)))
+		);
+	}
+
+	#[test]
+	fn encode_wrong_len() {
+		use crate::Base32Error::InvalidOutBytesSize;
+
+		let src = [0x0C, 0x83];
+		assert_eq!(
+			<Base32>::encode(&src, &mut [0; 3]),
+			Err(InvalidOutBytesSize(4, 3))
+		);
+		assert_eq!(
+			<Base32Check>::encode(&src, &mut [0; 4]),
+			Err(InvalidOutBytesSize(5, 4))
+		);
+		assert_eq!(
+			<Base32Check>::encode(&src, &mut []),
+			Err(InvalidOutBytesSize(5, 0))
+		);
+		assert_eq!(
+			Hex::encode(&src, &mut [0; 5]),
+			Err(InvalidOutBytesSize(4, 5))
+		);
+		assert_eq!(
+			Base64Url::encode(&src, &mut [0; 4]),
+			Err(InvalidOutBytesSize(3, 4))
+		);
+
+		let mut dst = [0; 4];
+		assert_eq!(<Base32>::encode(&src, &mut dst), Ok(()));
+		assert_eq!(&dst, b"0343");
+	}
+}
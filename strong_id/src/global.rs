@@ -0,0 +1,58 @@
+use core::{
+	ptr,
+	sync::atomic::{AtomicPtr, Ordering},
+};
+
+/// A process-wide value which is read without locking, for settings which are made once at
+/// startup. Values are shared between threads, so they must be `Send` and `Sync`.
+///
+/// Values which are replaced are leaked rather than freed, as they may still be borrowed by a
+/// concurrent reader. Every replacement therefore leaks the previous value for the rest of the
+/// process.
+pub(crate) struct Global<T>(AtomicPtr<T>);
+
+impl<T> Global<T> {
+	/// An unset value
+	pub(crate) const fn new() -> Self {
+		Self(AtomicPtr::new(ptr::null_mut()))
+	}
+}
+
+impl<T: Send + Sync> Global<T> {
+	/// The current value, if any
+	pub(crate) fn get(&self) -> Option<&'static T> {
+		let ptr = self.0.load(Ordering::Acquire);
+
+		// SAFETY: stored values are created from a `Box`, and are never freed or mutated
+		unsafe { ptr.as_ref() }
+	}
+
+	/// Replace the value, leaking the previous value
+	#[cfg_attr(not(feature = "canonicalize"), allow(dead_code))]
+	pub(crate) fn set(&self, value: T) {
+		self.0
+			.store(Box::into_raw(Box::new(value)), Ordering::Release);
+	}
+
+	/// Replace the value with one derived from the current value, leaking the previous value.
+	/// `update` is called again if another thread replaced the value in the meantime, so that no
+	/// concurrent update is lost.
+	pub(crate) fn update(&self, update: impl Fn(Option<&T>) -> T) {
+		let mut current = self.0.load(Ordering::Acquire);
+		loop {
+			// SAFETY: stored values are created from a `Box`, and are never freed or mutated
+			let new = Box::into_raw(Box::new(update(unsafe { current.as_ref() })));
+			match self
+				.0
+				.compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire)
+			{
+				Ok(_) => return,
+				Err(actual) => {
+					// SAFETY: the value was never shared, as it was not stored
+					drop(unsafe { Box::from_raw(new) });
+					current = actual;
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,82 @@
+use crate::{Error, Id, StrongId};
+
+/// Unsigned integers which can be used as indices, see [`IndexId`]
+pub trait IndexValue: Id + Copy + TryFrom<usize> + TryInto<usize> {}
+
+impl IndexValue for u8 {}
+impl IndexValue for u16 {}
+impl IndexValue for u32 {}
+impl IndexValue for u64 {}
+impl IndexValue for usize {}
+
+/// Use IDs backed by unsigned integers as indices, e.g. of arenas or ECS storages, without casting
+///
+/// ```rust
+/// use strong_id::{strong_id, IndexId};
+///
+/// strong_id!(pub struct NodeId(u32 => "node"));
+///
+/// let nodes = vec!["root", "child"];
+/// let id = NodeId::from_index(1);
+/// assert_eq!(nodes[id.index()], "child");
+/// ```
+pub trait IndexId<T: IndexValue>: StrongId<T> + From<T> {
+	/// The ID as an index
+	///
+	/// Panics if the ID doesn't fit into a `usize`, which can only happen for `u64` IDs on targets
+	/// with narrower pointers.
+	fn index(&self) -> usize {
+		match (*self.id()).try_into() {
+			Ok(index) => index,
+			Err(_) => panic!("ID does not fit into usize"),
+		}
+	}
+
+	/// Create an ID from an index
+	///
+	/// Panics if the index doesn't fit into the inner type of the ID, see
+	/// [`try_from_index`](Self::try_from_index).
+	fn from_index(index: usize) -> Self
+	where
+		Self: Sized,
+	{
+		match Self::try_from_index(index) {
+			Ok(id) => id,
+			Err(_) => panic!("index {index} does not fit into the ID"),
+		}
+	}
+
+	/// Create an ID from an index, failing with [`Error::OutOfRange`] if it doesn't fit into the
+	/// inner type of the ID
+	fn try_from_index(index: usize) -> Result<Self, Error>
+	where
+		Self: Sized,
+	{
+		T::try_from(index)
+			.map(Self::from)
+			.map_err(|_| Error::OutOfRange)
+	}
+}
+
+impl<T: IndexValue, S: StrongId<T> + From<T>> IndexId<T> for S {}
+
+#[cfg(test)]
+mod tests {
+	use super::IndexId;
+	use crate::{strong_id, Error};
+
+	strong_id!(pub struct SlotId(u8 => "slot"));
+
+	#[test]
+	fn index() {
+		assert_eq!(SlotId::from(7).index(), 7);
+		assert_eq!(SlotId::from_index(255), SlotId::from(255));
+		assert_eq!(SlotId::try_from_index(256), Err(Error::OutOfRange));
+	}
+
+	#[test]
+	#[should_panic(expected = "index 256 does not fit into the ID")]
+	fn from_index_out_of_range() {
+		SlotId::from_index(256);
+	}
+}
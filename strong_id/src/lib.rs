@@ -2,8 +2,13 @@
 //!
 //! A StrongId is any type which implements `StrongId<T: Id>`.
 //!
-//! The `Id` trait is implemented for `u8`, `u16`, `u32`, `u64`, `u128`, `usize` and when the `"uuid"` feature is enabled,
-//! `Uuid`.
+//! The `Id` trait is implemented for `u8`, `u16`, `u32`, `u64`, `u128`, `usize`, their `NonZero`
+//! and signed counterparts, `Wrapping` integers and when the `"uuid"` feature is enabled, `Uuid`.
+//! Signed integers are encoded in offset-binary so that encoded IDs sort in the same order as their
+//! values.
+//!
+//! IDs backed by unsigned integers can be assigned sequentially from any number of threads with an
+//! [`AtomicIdCell`].
 //!
 //! ## Examples
 //!
@@ -162,6 +167,27 @@
 //! Note that this would still require the "uuid" feature so that the [`Id`] trait can be implemented
 //! for Uuid.
 //!
+//! Existing newtypes can derive the same impls with `#[strong_id(standalone)]`, keeping their own
+//! methods:
+//!
+//! ```rust
+//! use strong_id::{StrongId, StrongUuid};
+//! use uuid::Uuid;
+//!
+//! #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, StrongId, StrongUuid)]
+//! #[strong_id(prefix = "user", standalone)]
+//! pub struct UserId(Uuid);
+//!
+//! impl UserId {
+//!     pub fn is_system(&self) -> bool {
+//!         self.0.is_nil()
+//!     }
+//! }
+//!
+//! let user_id = "user_00000000000000000000000000".parse::<UserId>().unwrap();
+//! assert!(user_id.is_system());
+//! ```
+//!
 //! #### Generated TypeId without a prefix
 //!
 //! ```rust,ignore
@@ -178,17 +204,98 @@
 //! //     suffix: 01894671-66e2-743a-b35b-23d2ae7b1ab6,
 //! // }
 //! ```
+//!
+//! ## Parsing IDs with several underscores
+//!
+//! IDs are split into their prefix and suffix at the last `_`, so `foo_bar_0343` has the prefix
+//! `foo_bar`. The prefix is then checked before it is compared with the expected prefix:
+//!
+//! - A prefix which starts or ends with `_`, e.g. of `_foo_0343` or `foo__0343`, fails with
+//!   `Error::MisplacedDelimiter`.
+//! - Without the "delimited" feature, a prefix which contains `_` fails with
+//!   `Error::DelimitedPrefix`, as it can never be valid. With the feature, it is a valid prefix,
+//!   which is compared with the expected prefix as usual.
+//! - An empty prefix, e.g. of `_0343`, fails with `Error::MissingPrefix`.
+//!
+//! IDs of generated types without a prefix fail with `Error::NoPrefixExpected` for any prefix.
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 extern crate self as strong_id;
 
-mod base32;
+#[cfg(target_has_atomic = "64")]
+mod atomic;
+pub mod base32;
+#[cfg(feature = "bloom")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bloom")))]
+mod bloom;
+pub mod builder;
+mod bulk;
+mod canonical;
+mod compliance;
+mod compound;
+mod confusables;
+#[cfg(feature = "context")]
+#[cfg_attr(docsrs, doc(cfg(feature = "context")))]
+pub mod context;
+mod debug;
 mod dynamic;
-
-use crate::base32::encoded_len;
+mod encoding;
+mod global;
+mod index;
+#[cfg(feature = "loadtest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "loadtest")))]
+pub mod loadtest;
+#[cfg(feature = "uuid")]
+mod partition;
+#[cfg(feature = "uuid")]
+mod raw;
+mod registry;
+mod reserved;
+mod rng;
+mod roundtrip;
+mod salted;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde_helpers;
+mod static_prefix;
+mod storage;
+#[cfg(feature = "uuid")]
+mod timestamp;
+mod ulid;
+mod unverified;
+
+#[cfg(target_has_atomic = "64")]
+pub use atomic::*;
+#[cfg(feature = "bloom")]
+pub use bloom::*;
+pub use bulk::*;
+pub use canonical::*;
+pub use compliance::*;
+pub use compound::*;
+pub use confusables::*;
+pub use debug::*;
 pub use dynamic::*;
-use thiserror::Error;
+#[doc(hidden)]
+pub use encoding::encoded_len as _internal_encoded_len;
+pub use encoding::{
+	Alphabet, Base32, Base32Alphabet, Base32Check, Base64Url, Crockford, CrockfordLenient,
+	Encoding, Hex,
+};
+pub use index::*;
+#[cfg(feature = "uuid")]
+pub use partition::*;
+#[cfg(feature = "uuid")]
+pub use raw::*;
+pub use registry::*;
+pub use reserved::{reserve_prefixes, reserved_prefixes};
+pub use salted::*;
+pub use static_prefix::*;
+pub use storage::*;
+#[cfg(feature = "uuid")]
+pub use timestamp::*;
+pub use ulid::*;
+pub use unverified::*;
 
 pub use base32::Base32Error;
 pub use strong_id_macros::*;
@@ -201,20 +308,388 @@ use uuid::Uuid;
 #[cfg(feature = "serde")]
 pub use serde;
 
+#[doc(hidden)]
+pub use roundtrip::assert_roundtrip as _internal_assert_roundtrip;
+
 /// Represents a type which can be encoded and decoded
+///
+/// Values are encoded as the [`Encoding`] of their bytes, which is [`Base32`] unless another
+/// encoding is given. Implementations only provide the bytes of their values, and every other item
+/// has a default which is derived from them:
+///
+/// ```rust
+/// use strong_id::{Error, Id};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Rgb(u8, u8, u8);
+///
+/// impl Id for Rgb {
+///     type Bytes = [u8; 3];
+///
+///     fn to_bytes(&self) -> Self::Bytes {
+///         [self.0, self.1, self.2]
+///     }
+///
+///     fn from_bytes(bytes: Self::Bytes) -> Result<Self, Error> {
+///         Ok(Rgb(bytes[0], bytes[1], bytes[2]))
+///     }
+/// }
+///
+/// assert_eq!(Rgb::ENCODED_LEN, 5);
+/// assert_eq!(Rgb::decode(Rgb(255, 128, 0).encode()), Ok(Rgb(255, 128, 0)));
+/// ```
+///
+/// Implementations of 0.4 and earlier, which provided `encode` and `decode`, provide `Bytes`,
+/// `to_bytes` and `from_bytes` instead, see the changelog.
 pub trait Id {
+	/// The big-endian bytes of the value
+	type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default;
+
+	/// The length of the encoded value
+	const ENCODED_LEN: usize = base32::encoded_len(core::mem::size_of::<Self::Bytes>());
+
+	/// Whether the value is a signed integer, whose bytes are offset by flipping the sign bit so that
+	/// they sort in the same order as the value. Used to serialize the value as an integer.
+	const SIGNED: bool = false;
+
+	/// The bytes of the value, which sort in the same order as the value
+	fn to_bytes(&self) -> Self::Bytes;
+	/// The value from its bytes
+	fn from_bytes(bytes: Self::Bytes) -> Result<Self, Error>
+	where
+		Self: Sized;
+
 	/// Encode the value into a `String`
-	fn encode(&self) -> String;
+	fn encode(&self) -> String {
+		self.encode_with::<Base32>()
+	}
+
 	/// Decode the value from a `str`
 	fn decode<T: AsRef<str>>(val: T) -> Result<Self, Error>
 	where
-		Self: Sized;
+		Self: Sized,
+	{
+		Self::decode_with::<Base32, T>(val)
+	}
+
+	/// Encode the value into a `String` with the given encoding
+	fn encode_with<E: Encoding>(&self) -> String {
+		let bytes = self.to_bytes();
+		let mut out = vec![0u8; E::encoded_len(bytes.as_ref().len())];
+		// `out` is of the encoded length, which is the only length encoding succeeds for
+		let _ = E::encode(bytes.as_ref(), &mut out);
+
+		match String::from_utf8(out) {
+			Ok(encoded) => encoded,
+			Err(error) => String::from_utf8_lossy(error.as_bytes()).into_owned(),
+		}
+	}
+
+	/// Decode the value from a `str` with the given encoding
+	fn decode_with<E: Encoding, T: AsRef<str>>(val: T) -> Result<Self, Error>
+	where
+		Self: Sized,
+	{
+		decode_bytes::<Self, E>(val.as_ref())
+	}
+
+	/// Encode the value into the start of `dst` without allocating, returning the number of bytes
+	/// written
+	///
+	/// Fails with [`Error::BufferTooSmall`] if `dst` is shorter than [`ENCODED_LEN`](Self::ENCODED_LEN).
+	///
+	/// ```rust
+	/// use strong_id::Id;
+	///
+	/// let mut buf = [0u8; 8];
+	/// let len = 3203u16.encode_into(&mut buf).unwrap();
+	/// assert_eq!(&buf[..len], b"0343");
+	/// ```
+	fn encode_into(&self, dst: &mut [u8]) -> Result<usize, Error> {
+		self.encode_into_with::<Base32>(dst)
+	}
+
+	/// Encode the value into the start of `dst` with the given encoding, returning the number of
+	/// bytes written
+	fn encode_into_with<E: Encoding>(&self, dst: &mut [u8]) -> Result<usize, Error> {
+		let bytes = self.to_bytes();
+		let len = E::encoded_len(bytes.as_ref().len());
+		match dst.get_mut(..len) {
+			Some(out) => {
+				E::encode(bytes.as_ref(), out)?;
+				Ok(len)
+			}
+			None => Err(Error::BufferTooSmall(len, dst.len())),
+		}
+	}
+}
+
+/// Encode a complete ID, i.e. its prefix, separator and suffix, into the start of `dst`
+#[doc(hidden)]
+pub fn _internal_encode_into<I: Id, E: Encoding>(
+	prefix: Option<&str>,
+	id: &I,
+	dst: &mut [u8],
+) -> Result<usize, Error> {
+	let prefix_len = prefix.map_or(0, |prefix| prefix.len() + 1);
+	let len = prefix_len + E::encoded_len(core::mem::size_of::<I::Bytes>());
+	if dst.len() < len {
+		return Err(Error::BufferTooSmall(len, dst.len()));
+	}
+
+	let (head, suffix) = dst.split_at_mut(prefix_len);
+	if let (Some(prefix), Some((separator, prefix_out))) = (prefix, head.split_last_mut()) {
+		prefix_out.copy_from_slice(prefix.as_bytes());
+		*separator = b'_';
+	}
+
+	Ok(prefix_len + id.encode_into_with::<E>(suffix)?)
+}
+
+fn decode_bytes<I: Id, E: Encoding>(val: &str) -> Result<I, Error> {
+	let mut bytes = I::Bytes::default();
+
+	let expected = E::encoded_len(bytes.as_ref().len());
+	if val.len() != expected {
+		return Err(Error::InvalidLength(expected, val.len()));
+	}
+
+	E::decode(val.as_bytes(), bytes.as_mut()).map_err(|error| match error {
+		// report the invalid character rather than its first byte, which may not be ascii
+		Base32Error::InvalidByte(index, c) => {
+			let c = val
+				.get(index..)
+				.and_then(|rest| rest.chars().next())
+				.unwrap_or(c);
+			Base32Error::InvalidByte(index, c)
+		}
+		error => error,
+	})?;
+	I::from_bytes(bytes)
+}
+
+/// Split an ID into its prefix and a suffix of `suffix_len` characters
+///
+/// IDs are split at the last `_`, unless the symbols of the encoding include `_`, in which case a
+/// suffix of the expected length is split off first.
+#[doc(hidden)]
+pub fn _internal_split_suffix<E: Encoding>(value: &str, suffix_len: usize) -> Option<(&str, &str)> {
+	if E::SYMBOLS.contains(&b'_') {
+		if value.len() == suffix_len {
+			return None;
+		}
+
+		if let Some(at) = value.len().checked_sub(suffix_len + 1) {
+			if value.as_bytes()[at] == b'_' {
+				return Some((&value[..at], &value[at + 1..]));
+			}
+		}
+	}
+
+	value.rsplit_once('_')
+}
+
+/// Check the underscores of a prefix which was split from an ID at its last `_`
+///
+/// A prefix may not start or end with `_`, as the ID would then have consecutive underscores, and
+/// may only contain `_` with the "delimited" feature. The prefix must not be empty.
+#[doc(hidden)]
+pub fn _internal_check_delimiters(prefix: &str) -> Result<(), Error> {
+	if prefix.starts_with('_') || prefix.ends_with('_') {
+		return Err(Error::MisplacedDelimiter(prefix.to_string()));
+	}
+
+	if !cfg!(feature = "delimited") && prefix.contains('_') {
+		return Err(Error::DelimitedPrefix(prefix.to_string()));
+	}
+
+	Ok(())
+}
+
+/// Check the underscores of a prefix, whose bytes were already scanned for `_`
+#[inline]
+pub(crate) fn check_delimiters_with(prefix: &str, delimited: bool) -> Result<(), Error> {
+	if prefix.starts_with('_') || prefix.ends_with('_') {
+		return Err(Error::MisplacedDelimiter(prefix.to_string()));
+	}
+
+	if !cfg!(feature = "delimited") && delimited {
+		return Err(Error::DelimitedPrefix(prefix.to_string()));
+	}
+
+	Ok(())
+}
+
+/// Switch the case of symbols which aren't part of the encoding, but whose other case is
+#[doc(hidden)]
+pub fn _internal_normalize_case<E: Encoding>(suffix: &str) -> std::borrow::Cow<'_, str> {
+	let normalize = |byte: u8| {
+		let other = if byte.is_ascii_uppercase() {
+			byte.to_ascii_lowercase()
+		} else {
+			byte.to_ascii_uppercase()
+		};
+
+		if !E::SYMBOLS.contains(&byte) && E::SYMBOLS.contains(&other) {
+			other
+		} else {
+			byte
+		}
+	};
+
+	if suffix.bytes().all(|byte| normalize(byte) == byte) {
+		return std::borrow::Cow::Borrowed(suffix);
+	}
+
+	// only ascii bytes are switched, so the result is valid UTF-8
+	let normalized = suffix.bytes().map(normalize).collect::<Vec<_>>();
+	match String::from_utf8(normalized) {
+		Ok(normalized) => std::borrow::Cow::Owned(normalized),
+		Err(_) => std::borrow::Cow::Borrowed(suffix),
+	}
+}
+
+/// Whether a byte is unreserved in URLs, see [RFC 3986](https://www.rfc-editor.org/rfc/rfc3986#section-2.3)
+const fn is_url_unreserved(byte: u8) -> bool {
+	byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~')
+}
+
+/// Percent-encode the reserved bytes of an encoded ID
+#[doc(hidden)]
+pub fn _internal_to_url_component(encoded: String) -> String {
+	if encoded.bytes().all(is_url_unreserved) {
+		return encoded;
+	}
+
+	let mut component = String::with_capacity(encoded.len() * 3);
+	for byte in encoded.bytes() {
+		if is_url_unreserved(byte) {
+			component.push(byte as char);
+		} else {
+			const HEX: &[u8; 16] = b"0123456789ABCDEF";
+			component.push('%');
+			component.push(HEX[(byte >> 4) as usize] as char);
+			component.push(HEX[(byte & 0x0F) as usize] as char);
+		}
+	}
+
+	component
+}
+
+/// Percent-decode a URL component. Malformed escapes are kept as they are, and fail to parse as IDs.
+#[doc(hidden)]
+pub fn _internal_from_url_component(component: &str) -> Result<std::borrow::Cow<'_, str>, Error> {
+	if !component.contains('%') {
+		return Ok(std::borrow::Cow::Borrowed(component));
+	}
+
+	let hex_value = |byte: u8| (byte as char).to_digit(16).map(|value| value as u8);
+
+	let mut bytes = Vec::with_capacity(component.len());
+	let mut rest = component.as_bytes();
+	while let [byte, tail @ ..] = rest {
+		rest = tail;
+
+		if let (b'%', [high, low, tail @ ..]) = (byte, tail) {
+			if let (Some(high), Some(low)) = (hex_value(*high), hex_value(*low)) {
+				bytes.push(high << 4 | low);
+				rest = tail;
+				continue;
+			}
+		}
+
+		bytes.push(*byte);
+	}
+
+	String::from_utf8(bytes)
+		.map(std::borrow::Cow::Owned)
+		.map_err(|_| Error::InvalidUtf8)
+}
+
+/// Reject suffixes of pointer-sized integers which were encoded on a target with another pointer
+/// width, which would otherwise fail with a length error, or decode to another value
+#[cfg(not(feature = "portable-usize"))]
+fn check_pointer_width<E: Encoding>(len: usize) -> Result<(), Error> {
+	let native = E::encoded_len(core::mem::size_of::<usize>());
+
+	for bits in [16, 32, 64] {
+		if bits != usize::BITS && len != native && len == E::encoded_len(bits as usize / 8) {
+			return Err(Error::PointerWidth(bits));
+		}
+	}
+
+	Ok(())
 }
 
 /// Represents a type which can be used as a StrongId
-pub trait StrongId<T: Id>: core::fmt::Display + core::str::FromStr {
+///
+/// Parsing is provided by [`FromStr`](core::str::FromStr) on the implementing types. It is not a
+/// supertrait so that `StrongId` can be implemented for references and smart pointers, allowing
+/// generic functions taking an `impl StrongId<T>` to accept them without cloning.
+pub trait StrongId<T: Id>: core::fmt::Display {
 	fn prefix(&self) -> Option<&str>;
 	fn id(&self) -> &T;
+
+	/// Encode the complete ID, i.e. its prefix, separator and suffix, into the start of `dst`
+	/// without allocating, returning the number of bytes written
+	///
+	/// Fails with [`Error::BufferTooSmall`] if `dst` is shorter than the encoded ID. The suffix is
+	/// encoded with [`Base32`] unless the ID type uses another encoding.
+	fn encode_into(&self, dst: &mut [u8]) -> Result<usize, Error> {
+		_internal_encode_into::<T, Base32>(self.prefix(), self.id(), dst)
+	}
+}
+
+macro_rules! impl_strong_id_deref {
+	($($t:ty),*) => {
+		$(
+			impl<T: Id, S: StrongId<T> + ?Sized> StrongId<T> for $t {
+				fn prefix(&self) -> Option<&str> {
+					(**self).prefix()
+				}
+
+				fn id(&self) -> &T {
+					(**self).id()
+				}
+
+				fn encode_into(&self, dst: &mut [u8]) -> Result<usize, Error> {
+					(**self).encode_into(dst)
+				}
+			}
+
+			impl<S: ErasedStrongId + ?Sized> ErasedStrongId for $t {
+				fn erased_prefix(&self) -> Option<&str> {
+					(**self).erased_prefix()
+				}
+
+				fn encoded(&self) -> String {
+					(**self).encoded()
+				}
+			}
+		)*
+	};
+}
+
+impl_strong_id_deref!(&S, &mut S, Box<S>, std::rc::Rc<S>, std::sync::Arc<S>);
+
+/// An object-safe view of a StrongId
+///
+/// [`StrongId`] can not be used as a trait object, so this trait allows IDs with different
+/// backing types to be held together, e.g. in a `Vec<Box<dyn ErasedStrongId>>`.
+pub trait ErasedStrongId: core::fmt::Display {
+	/// The prefix of the ID, if any
+	fn erased_prefix(&self) -> Option<&str>;
+	/// The encoded suffix of the ID, without the prefix
+	fn encoded(&self) -> String;
+}
+
+/// Implemented for generated StrongIds, whose prefix is known at compile-time
+pub trait StaticPrefix {
+	/// The inner value of the ID type
+	type Inner: Id;
+
+	/// The prefix of the ID type, if any
+	const PREFIX: Option<&'static str>;
 }
 
 /// Utility trait to allow StrongId's backed by a Uuid to wrap calls to Uuid `new_` and `now_`
@@ -224,6 +699,32 @@ pub trait StrongId<T: Id>: core::fmt::Display + core::str::FromStr {
 pub trait StrongUuid {
 	fn from_u128(v: u128) -> Self;
 
+	/// Borrow the underlying UUID bytes
+	fn as_bytes(&self) -> &[u8; 16];
+
+	/// Consume the ID and return the underlying UUID bytes
+	fn to_bytes(self) -> [u8; 16];
+
+	/// Format the underlying UUID in its hyphenated form, e.g.
+	/// `0189466f-a14b-77c2-9348-55ffc06f2188`
+	fn to_hyphenated_string(&self) -> String {
+		Uuid::from_bytes(*self.as_bytes()).hyphenated().to_string()
+	}
+
+	/// A key for time-partitioned storage, derived from the timestamp of a time-based (e.g. v7)
+	/// UUID, such as `2024-06-01` for [`Granularity::Day`]
+	///
+	/// Returns `None` if the UUID is not time-based, or is a v1 or v6 UUID from before 1970.
+	fn partition_key(&self, granularity: Granularity) -> Option<String> {
+		granularity.key(&Uuid::from_bytes(*self.as_bytes()))
+	}
+
+	/// The [`partition_key`](StrongUuid::partition_key) as a number, such as `20240601` for
+	/// [`Granularity::Day`], see [`Granularity::number`]
+	fn partition_number(&self, granularity: Granularity) -> Option<u32> {
+		granularity.number(&Uuid::from_bytes(*self.as_bytes()))
+	}
+
 	#[cfg(feature = "uuid-v1")]
 	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v1")))]
 	fn new_v1(ts: uuid::Timestamp, node_id: &[u8; 6]) -> Self;
@@ -260,32 +761,43 @@ pub trait StrongUuid {
 	#[cfg_attr(docsrs, doc(cfg(all(feature = "uuid-v7"))))]
 	fn now_v7() -> Self;
 
+	/// Generate a v7 UUID-backed ID for a point in time, e.g. a `chrono::DateTime<Utc>` when
+	/// backfilling historical records. See [`IntoTimestamp`].
+	#[cfg(feature = "uuid-v7")]
+	#[cfg_attr(docsrs, doc(cfg(all(feature = "uuid-v7"))))]
+	fn new_v7_at<D: IntoTimestamp>(at: D) -> Self
+	where
+		Self: Sized,
+	{
+		Self::new_v7(at.into_timestamp())
+	}
+
 	#[cfg(feature = "uuid-v8")]
 	#[cfg_attr(docsrs, doc(cfg(all(feature = "uuid-v8"))))]
 	fn new_v8(buf: [u8; 16]) -> Self;
 }
 
 macro_rules! impl_strong_uint {
-	($t:ty) => {
+	($t:ty $(, $check_len:ident)?) => {
 		impl Id for $t {
-			fn encode(&self) -> ::std::string::String {
-				let mut out = [0u8; encoded_len::<$t>()];
-				::strong_id::base32::encode(&self.to_be_bytes(), &mut out);
-				let encoded = unsafe { ::core::str::from_utf8_unchecked(&out) };
-				encoded.to_string()
-			}
-
-			fn decode<T: AsRef<str>>(val: T) -> ::core::result::Result<Self, ::strong_id::Error> {
-				let val = val.as_ref();
-				if val.len() != encoded_len::<$t>() {
-					return Err(::strong_id::Error::InvalidLength(encoded_len::<$t>(), val.len()));
-				}
+			type Bytes = [u8; ::core::mem::size_of::<$t>()];
 
-				let mut out = [0; ::core::mem::size_of::<$t>()];
-				::strong_id::base32::decode(val.as_bytes(), &mut out)?;
+			fn to_bytes(&self) -> Self::Bytes {
+				self.to_be_bytes()
+			}
 
-				Ok(Self::from_be_bytes(out))
+			fn from_bytes(bytes: Self::Bytes) -> ::core::result::Result<Self, ::strong_id::Error> {
+				Ok(Self::from_be_bytes(bytes))
 			}
+
+			$(
+				fn decode_with<E: Encoding, T: AsRef<str>>(
+					val: T,
+				) -> ::core::result::Result<Self, ::strong_id::Error> {
+					$check_len::<E>(val.as_ref().len())?;
+					decode_bytes::<Self, E>(val.as_ref())
+				}
+			)?
 		}
 	};
 }
@@ -295,106 +807,853 @@ impl_strong_uint!(u16);
 impl_strong_uint!(u32);
 impl_strong_uint!(u64);
 impl_strong_uint!(u128);
-impl_strong_uint!(usize);
+#[cfg(not(feature = "portable-usize"))]
+impl_strong_uint!(usize, check_pointer_width);
+
+// Encode pointer-sized integers as their 64-bit counterparts, so that encoded IDs have the same
+// length on every target. Values which don't fit the target's pointer width fail to decode.
+#[cfg(feature = "portable-usize")]
+macro_rules! impl_strong_portable {
+	($t:ty, $portable:ty) => {
+		impl Id for $t {
+			type Bytes = <$portable as Id>::Bytes;
+
+			const SIGNED: bool = <$portable as Id>::SIGNED;
+
+			fn to_bytes(&self) -> Self::Bytes {
+				(*self as $portable).to_bytes()
+			}
+
+			fn from_bytes(bytes: Self::Bytes) -> ::core::result::Result<Self, ::strong_id::Error> {
+				<$t as ::core::convert::TryFrom<$portable>>::try_from(<$portable>::from_bytes(
+					bytes,
+				)?)
+				.map_err(|_| ::strong_id::Error::OutOfRange)
+			}
+		}
+	};
+}
+
+#[cfg(feature = "portable-usize")]
+impl_strong_portable!(usize, u64);
+
+// Signed integers are encoded in offset-binary, i.e. with the sign bit flipped, so that encoded IDs
+// sort in the same order as their values
+macro_rules! impl_strong_int {
+	($t:ty, $unsigned:ty $(, $check_len:ident)?) => {
+		impl Id for $t {
+			type Bytes = <$unsigned as Id>::Bytes;
+
+			const SIGNED: bool = true;
+
+			fn to_bytes(&self) -> Self::Bytes {
+				((*self as $unsigned) ^ (1 << (<$unsigned>::BITS - 1))).to_bytes()
+			}
+
+			fn from_bytes(bytes: Self::Bytes) -> ::core::result::Result<Self, ::strong_id::Error> {
+				Ok((<$unsigned>::from_bytes(bytes)? ^ (1 << (<$unsigned>::BITS - 1))) as $t)
+			}
+
+			$(
+				fn decode_with<E: Encoding, T: AsRef<str>>(
+					val: T,
+				) -> ::core::result::Result<Self, ::strong_id::Error> {
+					$check_len::<E>(val.as_ref().len())?;
+					decode_bytes::<Self, E>(val.as_ref())
+				}
+			)?
+		}
+	};
+}
+
+impl_strong_int!(i8, u8);
+impl_strong_int!(i16, u16);
+impl_strong_int!(i32, u32);
+impl_strong_int!(i64, u64);
+impl_strong_int!(i128, u128);
+#[cfg(not(feature = "portable-usize"))]
+impl_strong_int!(isize, usize, check_pointer_width);
+#[cfg(feature = "portable-usize")]
+impl_strong_portable!(isize, i64);
+
+macro_rules! impl_strong_nonzero {
+	($t:ty, $inner:ty) => {
+		impl Id for $t {
+			type Bytes = <$inner as Id>::Bytes;
+
+			fn to_bytes(&self) -> Self::Bytes {
+				self.get().to_bytes()
+			}
+
+			fn from_bytes(bytes: Self::Bytes) -> ::core::result::Result<Self, ::strong_id::Error> {
+				<$t>::new(<$inner>::from_bytes(bytes)?).ok_or(::strong_id::Error::Zero)
+			}
+		}
+	};
+}
+
+impl_strong_nonzero!(core::num::NonZeroU8, u8);
+impl_strong_nonzero!(core::num::NonZeroU16, u16);
+impl_strong_nonzero!(core::num::NonZeroU32, u32);
+impl_strong_nonzero!(core::num::NonZeroU64, u64);
+impl_strong_nonzero!(core::num::NonZeroU128, u128);
+impl_strong_nonzero!(core::num::NonZeroUsize, usize);
+
+// Wrapping integers are encoded like the integers they wrap, e.g. for IDs which are assigned by
+// counters that wrap around
+impl<T: Id> Id for core::num::Wrapping<T> {
+	type Bytes = T::Bytes;
+
+	const ENCODED_LEN: usize = T::ENCODED_LEN;
+
+	const SIGNED: bool = T::SIGNED;
+
+	fn to_bytes(&self) -> Self::Bytes {
+		self.0.to_bytes()
+	}
+
+	fn from_bytes(bytes: Self::Bytes) -> Result<Self, Error> {
+		T::from_bytes(bytes).map(core::num::Wrapping)
+	}
+
+	fn decode_with<E: Encoding, V: AsRef<str>>(val: V) -> Result<Self, Error> {
+		T::decode_with::<E, V>(val).map(core::num::Wrapping)
+	}
+}
 
 #[cfg(feature = "uuid")]
 impl Id for Uuid {
-	fn encode(&self) -> String {
-		let mut out = [0; 26];
-		base32::encode(self.as_bytes(), &mut out);
-		let encoded = unsafe { core::str::from_utf8_unchecked(&out) };
-		encoded.to_string()
-	}
+	type Bytes = [u8; 16];
 
-	fn decode<T: AsRef<str>>(val: T) -> Result<Self, Error> {
-		let val = val.as_ref();
-		if val.len() != 26 {
-			return Err(Error::InvalidLength(26, val.len()));
-		}
-		let mut out = [0; 16];
-		base32::decode(val.as_bytes(), &mut out)?;
+	fn to_bytes(&self) -> Self::Bytes {
+		*self.as_bytes()
+	}
 
-		Ok(Self::from_bytes(out))
+	fn from_bytes(bytes: Self::Bytes) -> Result<Self, Error> {
+		Ok(Self::from_bytes(bytes))
 	}
 }
 
 /// Errors which may occur when creating or parsing StrongIds
-#[derive(Error, Debug, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq)]
 pub enum Error {
 	/// Created from a [`Base32Error`]
-	#[error(transparent)]
-	Base32Error(#[from] Base32Error),
+	Base32Error(Base32Error),
 	/// A prefix was expected, but was not found
-	#[error("expected prefix `{0}`")]
 	MissingPrefix(String),
 	/// The given prefix did not match the expected prefix
-	#[error("invalid prefix. expected {0}, found {1}")]
 	InvalidPrefix(String, String),
 	/// A prefix was expected to be configured, but was not found
-	#[error("no prefix was given, but one was expected")]
 	PrefixExpected,
 	/// A prefix was given, but none was expected
-	#[error("found prefix `{0}`, none expected")]
 	NoPrefixExpected(String),
 	/// The length of the encoded value to be decoded was incorrect
-	#[error("invalid length. expected {0}, found {1}")]
 	InvalidLength(usize, usize),
 	/// The prefix is too long
-	#[error("prefix too long. should be less than 64 characters, found {0}")]
 	PrefixTooLong(usize),
 	/// A non-alphanumeric, non-lowercase character was found. When the "delimited" feature is
 	/// enabled, this will not include the `'_'` character.
-	#[error("prefix may only contain lowercase ascii characters, found `{0}`")]
 	IncorrectPrefixCharacter(char),
+	/// No ID type is registered for the prefix
+	UnknownPrefix(String),
+	/// No ID type is registered with the type tag
+	UnknownTypeTag(String),
+	/// The bytes to be parsed were not valid UTF-8
+	InvalidUtf8,
+	/// A zero value was decoded for a `NonZero` ID
+	Zero,
+	/// The value does not fit into the inner type of the target ID
+	OutOfRange,
+	/// A `usize` or `isize` ID was encoded on a target with pointers of the given width, in bits.
+	/// See the "portable-usize" feature, or decode the ID as a `u64` and convert it with
+	/// `usize::try_from`.
+	PointerWidth(u32),
+	/// The buffer to encode into was shorter than the encoded ID. Holds the length of the encoded
+	/// ID and of the buffer.
+	BufferTooSmall(usize, usize),
+	/// The prefix of a parsed ID contains `'_'`, which is only valid with the "delimited" feature,
+	/// e.g. `foo_bar` of `foo_bar_0343`
+	DelimitedPrefix(String),
+	/// The prefix of a parsed ID starts or ends with `'_'`, e.g. `user_` of `user__0343`
+	MisplacedDelimiter(String),
+	/// The prefix is reserved, see [`reserve_prefixes`]
+	ReservedPrefix(String),
 }
 
-/// Generate a StrongId
-///
-/// TODO examples
-#[macro_export]
-macro_rules! strong_id {
-    (
-        $(#[$outer:meta])*
-        $vis:vis struct $t:ident($inner_vis:vis $inner:ty)
-    ) => {
-        $crate::_internal_strong_id! {
-            $(#[$outer])*
-            $vis struct $t($inner_vis $inner)
-        }
-    };
-    (
-        $(#[$outer:meta])*
-        $vis:vis struct $t:ident($inner_vis:vis $inner:ty => $prefix:literal)
-    ) => {
-        $crate::_internal_strong_id! {
-            $(#[$outer])*
-            $vis struct $t($inner_vis $inner => $prefix)
-        }
-    };
-}
+impl Error {
+	/// The position of the invalid character in the ID, if the error was caused by one
+	///
+	/// ```rust
+	/// use strong_id::strong_id;
+	///
+	/// strong_id!(pub struct UserId(u16 => "user"));
+	///
+	/// let error = "user_0l43".parse::<UserId>().unwrap_err();
+	/// assert_eq!(error.position(), Some(6));
+	/// ```
+	pub fn position(&self) -> Option<usize> {
+		match self {
+			Self::Base32Error(Base32Error::InvalidByte(index, _)) => Some(*index),
+			_ => None,
+		}
+	}
 
-#[macro_export]
-#[doc(hidden)]
-macro_rules! _internal_strong_id {
-    (
-        $(#[$outer:meta])*
-        $vis:vis struct $t:ident($inner_vis:vis $inner:ty$( => $prefix:literal)?)
-    ) => {
-        $(#[$outer])*
-        #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
-        #[derive($crate::StrongId)]
-		#[strong_id($(prefix = $prefix, )?suffix = "suffix")]
-        $vis struct $t($inner_vis $inner);
+	/// Shift the position of an invalid character, e.g. from the suffix to the complete ID
+	#[doc(hidden)]
+	pub fn _internal_offset(self, offset: usize) -> Self {
+		match self {
+			Self::Base32Error(Base32Error::InvalidByte(index, c)) => {
+				Self::Base32Error(Base32Error::InvalidByte(index + offset, c))
+			}
+			error => error,
+		}
+	}
+
+	/// A stable, machine-readable code for the error
+	///
+	/// These codes will not change between releases, so clients can use them to translate errors
+	/// instead of matching on messages.
+	///
+	/// | Error                                    | Code                              |
+	/// |------------------------------------------|-----------------------------------|
+	/// | [`Base32Error::Empty`]                   | `ERR_ID_EMPTY`                    |
+	/// | [`Base32Error::InvalidByte`]             | `ERR_ID_INVALID_CHARACTER`        |
+	/// | [`Base32Error::InvalidFirstByte`]        | `ERR_ID_INVALID_FIRST_CHARACTER`  |
+	/// | [`Base32Error::InvalidOutBytesSize`]     | `ERR_ID_OUTPUT_SIZE`              |
+	/// | [`Base32Error::InvalidCheckSymbol`]      | `ERR_ID_CHECK_SYMBOL`             |
+	/// | [`Error::MissingPrefix`]                 | `ERR_ID_MISSING_PREFIX`           |
+	/// | [`Error::InvalidPrefix`]                 | `ERR_ID_PREFIX_MISMATCH`          |
+	/// | [`Error::PrefixExpected`]                | `ERR_ID_PREFIX_EXPECTED`          |
+	/// | [`Error::NoPrefixExpected`]              | `ERR_ID_UNEXPECTED_PREFIX`        |
+	/// | [`Error::InvalidLength`]                 | `ERR_ID_LENGTH`                   |
+	/// | [`Error::PrefixTooLong`]                 | `ERR_ID_PREFIX_TOO_LONG`          |
+	/// | [`Error::IncorrectPrefixCharacter`]      | `ERR_ID_PREFIX_CHARACTER`         |
+	/// | [`Error::UnknownPrefix`]                 | `ERR_ID_UNKNOWN_PREFIX`           |
+	/// | [`Error::UnknownTypeTag`]                | `ERR_ID_UNKNOWN_TYPE`             |
+	/// | [`Error::InvalidUtf8`]                   | `ERR_ID_INVALID_UTF8`             |
+	/// | [`Error::Zero`]                          | `ERR_ID_ZERO`                     |
+	/// | [`Error::OutOfRange`]                    | `ERR_ID_OUT_OF_RANGE`             |
+	/// | [`Error::PointerWidth`]                  | `ERR_ID_POINTER_WIDTH`            |
+	/// | [`Error::BufferTooSmall`]                | `ERR_ID_BUFFER_TOO_SMALL`         |
+	/// | [`Error::DelimitedPrefix`]               | `ERR_ID_DELIMITED_PREFIX`         |
+	/// | [`Error::MisplacedDelimiter`]            | `ERR_ID_MISPLACED_DELIMITER`      |
+	/// | [`Error::ReservedPrefix`]                | `ERR_ID_RESERVED_PREFIX`          |
+	pub fn code(&self) -> &'static str {
+		match self {
+			Self::Base32Error(Base32Error::Empty) => "ERR_ID_EMPTY",
+			Self::Base32Error(Base32Error::InvalidByte(_, _)) => "ERR_ID_INVALID_CHARACTER",
+			Self::Base32Error(Base32Error::InvalidFirstByte) => "ERR_ID_INVALID_FIRST_CHARACTER",
+			Self::Base32Error(Base32Error::InvalidOutBytesSize(_, _)) => "ERR_ID_OUTPUT_SIZE",
+			Self::Base32Error(Base32Error::InvalidCheckSymbol) => "ERR_ID_CHECK_SYMBOL",
+			Self::MissingPrefix(_) => "ERR_ID_MISSING_PREFIX",
+			Self::InvalidPrefix(_, _) => "ERR_ID_PREFIX_MISMATCH",
+			Self::PrefixExpected => "ERR_ID_PREFIX_EXPECTED",
+			Self::NoPrefixExpected(_) => "ERR_ID_UNEXPECTED_PREFIX",
+			Self::InvalidLength(_, _) => "ERR_ID_LENGTH",
+			Self::PrefixTooLong(_) => "ERR_ID_PREFIX_TOO_LONG",
+			Self::IncorrectPrefixCharacter(_) => "ERR_ID_PREFIX_CHARACTER",
+			Self::UnknownPrefix(_) => "ERR_ID_UNKNOWN_PREFIX",
+			Self::UnknownTypeTag(_) => "ERR_ID_UNKNOWN_TYPE",
+			Self::InvalidUtf8 => "ERR_ID_INVALID_UTF8",
+			Self::Zero => "ERR_ID_ZERO",
+			Self::OutOfRange => "ERR_ID_OUT_OF_RANGE",
+			Self::PointerWidth(_) => "ERR_ID_POINTER_WIDTH",
+			Self::BufferTooSmall(_, _) => "ERR_ID_BUFFER_TOO_SMALL",
+			Self::DelimitedPrefix(_) => "ERR_ID_DELIMITED_PREFIX",
+			Self::MisplacedDelimiter(_) => "ERR_ID_MISPLACED_DELIMITER",
+			Self::ReservedPrefix(_) => "ERR_ID_RESERVED_PREFIX",
+		}
+	}
+
+	/// A human-oriented hint on how to correct the input, if there is one
+	///
+	/// ```rust
+	/// use strong_id::strong_id;
+	///
+	/// strong_id!(pub struct UserId(u16 => "user"));
+	///
+	/// let error = "user_034".parse::<UserId>().unwrap_err();
+	/// assert_eq!(
+	///     error.help().unwrap(),
+	///     "expected 4 characters of the ID's encoding after the prefix"
+	/// );
+	/// ```
+	///
+	/// Errors don't know the encoding of the ID, so hints don't describe its characters. See the
+	/// `EXPECTED_FORMAT` of generated StrongIds, which describes the complete format.
+	pub fn help(&self) -> Option<String> {
+		Some(match self {
+			Self::Base32Error(Base32Error::Empty) => "expected an encoded ID".to_string(),
+			Self::Base32Error(Base32Error::InvalidByte(_, _)) => {
+				"the encoded ID contains a character which is not part of its encoding".to_string()
+			}
+			Self::Base32Error(Base32Error::InvalidFirstByte) => {
+				"the first character of the encoded ID is too large for the type of ID".to_string()
+			}
+			Self::Base32Error(Base32Error::InvalidCheckSymbol) => {
+				"the last character of the encoded ID does not match the rest, check it for typos"
+					.to_string()
+			}
+			Self::MissingPrefix(prefix) | Self::InvalidPrefix(prefix, _) => {
+				format!("expected \"{prefix}_\" followed by the encoded ID")
+			}
+			Self::PrefixExpected => "expected a prefix".to_string(),
+			Self::NoPrefixExpected(_) => "expected the encoded ID without a prefix".to_string(),
+			Self::InvalidLength(expected, _) => {
+				format!("expected {expected} characters of the ID's encoding after the prefix")
+			}
+			Self::PrefixTooLong(_) => "prefixes may be at most 63 characters".to_string(),
+			Self::IncorrectPrefixCharacter(_) if cfg!(feature = "delimited") => {
+				"prefixes may only contain lowercase ascii characters and underscores".to_string()
+			}
+			Self::IncorrectPrefixCharacter(_) => {
+				"prefixes may only contain lowercase ascii characters".to_string()
+			}
+			Self::PointerWidth(bits) => format!(
+				"the ID was encoded for {bits}-bit pointers, decode it as a u64 and convert it with \
+				 usize::try_from, or encode it with the portable-usize feature"
+			),
+			Self::DelimitedPrefix(_) => {
+				"expected a single \"_\" between the prefix and the encoded ID, prefixes may only \
+				 contain underscores with the delimited feature"
+					.to_string()
+			}
+			Self::MisplacedDelimiter(_) => {
+				"expected a single \"_\" between the prefix and the encoded ID".to_string()
+			}
+			Self::Base32Error(Base32Error::InvalidOutBytesSize(_, _))
+			| Self::UnknownPrefix(_)
+			| Self::UnknownTypeTag(_)
+			| Self::InvalidUtf8
+			| Self::Zero
+			| Self::OutOfRange
+			| Self::BufferTooSmall(_, _)
+			| Self::ReservedPrefix(_) => return None,
+		})
+	}
+}
+
+/// A description of the format of an ID, for showing to users
+///
+/// ```rust
+/// use strong_id::strong_id;
+///
+/// strong_id!(pub struct UserId(u16 => "user"));
+///
+/// assert_eq!(
+///     UserId::EXPECTED_FORMAT.to_string(),
+///     "\"user_\" followed by 4 characters of [0-9a-hjkmnp-tv-z]"
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ExpectedFormat<'a> {
+	prefix: Option<&'a str>,
+	suffix_len: usize,
+	symbols: &'a [u8],
+}
+
+impl<'a> ExpectedFormat<'a> {
+	/// Describe IDs with an optional prefix and a [`Base32`] encoded suffix of `suffix_len`
+	/// characters
+	pub const fn new(prefix: Option<&'a str>, suffix_len: usize) -> Self {
+		Self {
+			prefix,
+			suffix_len,
+			symbols: &base32::ALPHABET,
+		}
+	}
+
+	/// Describe IDs whose suffix is encoded with the given symbols, see [`Encoding::SYMBOLS`]
+	pub const fn with_symbols(self, symbols: &'a [u8]) -> Self {
+		Self { symbols, ..self }
+	}
+}
+
+impl<'a> core::fmt::Display for ExpectedFormat<'a> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		if let Some(prefix) = self.prefix {
+			write!(f, "\"{prefix}_\" followed by ")?;
+		}
+		write!(f, "{} characters of [", self.suffix_len)?;
+
+		// collapse runs of at least 3 consecutive symbols into ranges, e.g. `0-9`
+		let mut symbols = self.symbols;
+		while let Some((&first, rest)) = symbols.split_first() {
+			let run = rest
+				.iter()
+				.zip(1..)
+				.take_while(|&(&symbol, offset)| symbol as usize == first as usize + offset)
+				.count();
+
+			if run >= 2 {
+				write!(f, "{}-{}", first as char, rest[run - 1] as char)?;
+				symbols = &rest[run..];
+			} else if first == b'-' {
+				// escape `-`, so that it isn't mistaken for a range
+				f.write_str("\\-")?;
+				symbols = rest;
+			} else {
+				write!(f, "{}", first as char)?;
+				symbols = rest;
+			}
+		}
+
+		f.write_str("]")
+	}
+}
+
+#[cfg(not(feature = "wasm-slim"))]
+impl core::fmt::Display for Error {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			Self::Base32Error(error) => core::fmt::Display::fmt(error, f),
+			Self::MissingPrefix(prefix) => write!(f, "expected prefix `{prefix}`"),
+			Self::InvalidPrefix(expected, found) => {
+				write!(f, "invalid prefix. expected {expected}, found {found}")
+			}
+			Self::PrefixExpected => write!(f, "no prefix was given, but one was expected"),
+			Self::NoPrefixExpected(prefix) => write!(f, "found prefix `{prefix}`, none expected"),
+			Self::InvalidLength(expected, found) => {
+				write!(f, "invalid length. expected {expected}, found {found}")
+			}
+			Self::PrefixTooLong(len) => write!(
+				f,
+				"prefix too long. should be less than 64 characters, found {len}"
+			),
+			Self::IncorrectPrefixCharacter(c) => write!(
+				f,
+				"prefix may only contain lowercase ascii characters, found `{c}`"
+			),
+			Self::UnknownPrefix(prefix) => {
+				write!(f, "no ID type is registered for prefix `{prefix}`")
+			}
+			Self::UnknownTypeTag(tag) => write!(f, "no ID type is registered as `{tag}`"),
+			Self::InvalidUtf8 => write!(f, "invalid UTF-8"),
+			Self::Zero => write!(f, "the ID may not be zero"),
+			Self::OutOfRange => write!(f, "the ID is out of range of the target type"),
+			Self::PointerWidth(bits) => write!(
+				f,
+				"the ID was encoded for {bits}-bit pointers, but this target has {}-bit pointers",
+				usize::BITS
+			),
+			Self::BufferTooSmall(len, found) => write!(
+				f,
+				"buffer too small. expected at least {len} bytes, found {found}"
+			),
+			Self::DelimitedPrefix(prefix) => write!(
+				f,
+				"prefix `{prefix}` contains `_`, which requires the \"delimited\" feature"
+			),
+			Self::MisplacedDelimiter(prefix) => {
+				write!(f, "prefix `{prefix}` starts or ends with `_`")
+			}
+			Self::ReservedPrefix(prefix) => write!(f, "prefix `{prefix}` is reserved"),
+		}
+	}
+}
+
+#[cfg(feature = "wasm-slim")]
+impl core::fmt::Display for Error {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.write_str(match self {
+			Self::Base32Error(_) => "invalid base32",
+			Self::MissingPrefix(_) => "expected prefix",
+			Self::InvalidPrefix(_, _) => "invalid prefix",
+			Self::PrefixExpected => "no prefix was given, but one was expected",
+			Self::NoPrefixExpected(_) => "found prefix, none expected",
+			Self::InvalidLength(_, _) => "invalid length",
+			Self::PrefixTooLong(_) => "prefix too long",
+			Self::IncorrectPrefixCharacter(_) => {
+				"prefix may only contain lowercase ascii characters"
+			}
+			Self::UnknownPrefix(_) => "no ID type is registered for the prefix",
+			Self::UnknownTypeTag(_) => "no ID type is registered with the type tag",
+			Self::InvalidUtf8 => "invalid UTF-8",
+			Self::Zero => "the ID may not be zero",
+			Self::OutOfRange => "the ID is out of range of the target type",
+			Self::PointerWidth(_) => "the ID was encoded for another pointer width",
+			Self::BufferTooSmall(_, _) => "buffer too small",
+			Self::DelimitedPrefix(_) => "prefix contains `_`",
+			Self::MisplacedDelimiter(_) => "prefix starts or ends with `_`",
+			Self::ReservedPrefix(_) => "prefix is reserved",
+		})
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::Base32Error(error) => error.source(),
+			_ => None,
+		}
+	}
+}
+
+impl From<Base32Error> for Error {
+	fn from(value: Base32Error) -> Self {
+		Self::Base32Error(value)
+	}
+}
+
+/// Generate a StrongId
+///
+/// The struct derives `Copy`, `Clone`, `Debug`, `Eq`, `PartialEq`, `Hash` and [`StrongId`].
+/// Attributes on the declaration, including further derives and their helper attributes, are
+/// forwarded onto the generated struct. `#[strong_id(...)]` attributes configure the
+/// [`StrongId`](macro@StrongId) derive, e.g. `#[strong_id(deref)]`.
+///
+/// The inner field is private unless a visibility is given before its type, e.g. `pub(crate) u64`,
+/// so that IDs can be constructed and matched on within a crate. Accessors for the inner value can
+/// be generated with `#[strong_id(accessor = "...")]`.
+///
+/// Well-known IDs, e.g. of system actors, can be declared as associated constants in a block after
+/// the struct. Each ID is validated at compile-time, so a mistyped constant fails to compile.
+/// Constants are supported for IDs backed by fixed-width integers and `Uuid` with the default
+/// encoding.
+///
+/// ```rust
+/// use strong_id::strong_id;
+///
+/// strong_id!(pub struct UserId(u64 => "user") {
+///     /// The user which scheduled jobs run as
+///     pub const SYSTEM = "user_0000000000343";
+/// });
+///
+/// assert_eq!(UserId::SYSTEM, UserId::from(3203));
+/// ```
+///
+/// Compound IDs of two segments, e.g. for tables keyed by tenant and entity, are declared with both
+/// inner types. The segments are encoded one after the other, so compound IDs sort by their first
+/// and then their second segment. Compound IDs implement `Display`, `FromStr`, conversions from and
+/// into a tuple of their segments, and serde when the "serde" feature is enabled.
+///
+/// ```rust
+/// use strong_id::strong_id;
+///
+/// strong_id!(pub struct OrgUserId(u32, u64 => "orguser"));
+///
+/// let id = OrgUserId::new(7, 3203);
+/// assert_eq!(id.to_string(), "orguser_00000070000000000343");
+/// assert_eq!("orguser_00000070000000000343".parse::<OrgUserId>(), Ok(id));
+/// assert_eq!((id.first(), id.second()), (&7, &3203));
+/// ```
+///
+/// ```compile_fail
+/// use strong_id::strong_id;
+///
+/// strong_id!(pub struct UserId(u64 => "user") {
+///     pub const SYSTEM = "usr_0000000000343";
+/// });
+/// ```
+///
+/// ```rust
+/// use strong_id::strong_id;
+///
+/// strong_id!(pub struct UserId(u64 => "user"));
+///
+/// strong_id! {
+///     /// The ID of an order
+///     #[derive(PartialOrd, Ord)]
+///     #[strong_id(unordered, deref, accessor = "get")]
+///     pub struct OrderId(pub(crate) u64 => "order")
+/// }
+///
+/// let id = "order_0000000000343".parse::<OrderId>().unwrap();
+/// assert_eq!(id.pow(2), 3203 * 3203);
+/// assert_eq!(*id.get(), 3203);
+/// assert!(id < OrderId(3204));
+/// ```
+///
+/// `deny_sequential` rejects narrow integers, also when they are wrapped:
+///
+/// ```compile_fail
+/// use strong_id::strong_id;
+///
+/// strong_id! {
+///     #[strong_id(deny_sequential)]
+///     pub struct UserId(core::num::Wrapping<u64> => "user")
+/// }
+/// ```
+#[macro_export]
+macro_rules! strong_id {
+    (
+        $(#[$outer:meta])*
+        $vis:vis struct $t:ident($inner_vis:vis $inner:ty)
+        $({ $($constants:tt)* })?
+    ) => {
+        $crate::_internal_strong_id! {
+            $(#[$outer])*
+            $vis struct $t($inner_vis $inner)
+            { $($($constants)*)? }
+        }
+    };
+    (
+        $(#[$outer:meta])*
+        $vis:vis struct $t:ident($inner_vis:vis $inner:ty => $prefix:literal)
+        $({ $($constants:tt)* })?
+    ) => {
+        $crate::_internal_strong_id! {
+            $(#[$outer])*
+            $vis struct $t($inner_vis $inner => $prefix)
+            { $($($constants)*)? }
+        }
+    };
+    (
+        $(#[$outer:meta])*
+        $vis:vis struct $t:ident($first_vis:vis $first:ty, $second_vis:vis $second:ty$( => $prefix:literal)?)
+    ) => {
+        $crate::_internal_strong_compound_id! {
+            $(#[$outer])*
+            $vis struct $t($first_vis $first, $second_vis $second$( => $prefix)?)
+        }
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! _internal_strong_compound_id {
+    (
+        $(#[$outer:meta])*
+        $vis:vis struct $t:ident($first_vis:vis $first:ty, $second_vis:vis $second:ty$( => $prefix:literal)?)
+    ) => {
+        #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+        $(#[$outer])*
+        $vis struct $t($first_vis $first, $second_vis $second);
+
+		impl $t {
+			/// The prefix of this ID type, if any
+			pub const PREFIX: Option<&'static str> = $crate::_internal_optional_prefix!($($prefix)?);
+
+			/// The length of an encoded ID of this type, including the prefix and separator
+			pub const ENCODED_LEN: usize = match Self::PREFIX {
+				Some(prefix) => prefix.len() + 1,
+				None => 0,
+			} + $crate::_internal_compound_suffix_len::<$first, $second>();
+
+			/// Create the ID from its segments
+			pub fn new(first: $first, second: $second) -> Self {
+				Self(first, second)
+			}
+
+			/// The first segment of the ID
+			pub fn first(&self) -> &$first {
+				&self.0
+			}
+
+			/// The second segment of the ID
+			pub fn second(&self) -> &$second {
+				&self.1
+			}
+
+			/// Consume the ID and return its segments
+			pub fn into_parts(self) -> ($first, $second) {
+				(self.0, self.1)
+			}
+		}
+
+		impl ::core::fmt::Display for $t {
+			fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+				$crate::_internal_fmt_compound(Self::PREFIX, &self.0, &self.1, f)
+			}
+		}
+
+		impl ::core::str::FromStr for $t {
+			type Err = $crate::Error;
+
+			fn from_str(value: &str) -> ::core::result::Result<Self, Self::Err> {
+				$crate::_internal_parse_compound(Self::PREFIX, value)
+					.map(|(first, second)| Self(first, second))
+			}
+		}
+
+		impl ::core::convert::TryFrom<&str> for $t {
+			type Error = $crate::Error;
+
+			fn try_from(value: &str) -> ::core::result::Result<Self, Self::Error> {
+				value.parse()
+			}
+		}
+
+		impl From<($first, $second)> for $t {
+			fn from((first, second): ($first, $second)) -> Self {
+				Self(first, second)
+			}
+		}
+
+		impl From<$t> for ($first, $second) {
+			fn from(value: $t) -> Self {
+				(value.0, value.1)
+			}
+		}
+
+		$crate::_internal_impl_serde_from_str!($t);
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! _internal_optional_prefix {
+	() => {
+		None
+	};
+	($prefix:literal) => {{
+		// validates the prefix at compile-time. `prefix!` isn't used, as it refers to `::strong_id`,
+		// which may only be re-exported by the crate using the macro
+		const _: $crate::Prefix<'static> = $crate::Prefix::from_static($prefix);
+		Some($prefix)
+	}};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! _internal_strong_id {
+    (
+        $(#[$outer:meta])*
+        $vis:vis struct $t:ident($inner_vis:vis $inner:ty$( => $prefix:literal)?)
+        { $($constants:tt)* }
+    ) => {
+        #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+        #[derive($crate::StrongId)]
+		#[strong_id(crate = $crate, ord, into_inner$(, prefix = $prefix)?)]
+        $(#[$outer])*
+        $vis struct $t($inner_vis $inner);
 
 		$crate::_internal_impl_common!(@@internal $t($inner));
 
 		$crate::_internal_impl_from_str!(@@internal $t($inner => $($prefix)?));
+
+		$crate::_internal_impl_constants!($t($inner, ($($prefix)?)) $($constants)*);
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! _internal_impl_constants {
+	($t:ident($inner:ty, $prefix:tt)) => {};
+	(
+		$t:ident($inner:ty, $prefix:tt)
+		$(
+			$(#[$outer:meta])*
+			$vis:vis const $constant:ident = $value:literal;
+		)+
+	) => {
+		impl $t {
+			$(
+				$(#[$outer])*
+				$vis const $constant: Self = {
+					::core::assert!(
+						Self::_INTERNAL_DEFAULT_ENCODING,
+						"constants can only be declared for IDs with the default encoding"
+					);
+					$t($crate::_internal_constant!($inner, $prefix, $value))
+				};
+			)+
+		}
+	};
+}
+
+/// Generate many StrongIds in one invocation
+///
+/// Each ID is declared as with [`strong_id`], or with [`strong_uuid`] when it is backed by `Uuid`,
+/// and ends with a semicolon. Starting with an `enum` declaration also generates an enum over all
+/// of the IDs with [`strong_id_enum`], with a variant named after each ID type.
+///
+#[cfg_attr(feature = "uuid", doc = "```rust")]
+#[cfg_attr(not(feature = "uuid"), doc = "```rust,ignore")]
+/// use strong_id::strong_ids;
+///
+/// strong_ids! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum AnyId;
+///
+///     pub struct UserId(Uuid => "user");
+///     pub struct OrderId(u64 => "order");
+///     pub struct ItemId(u32);
+/// }
+///
+/// let id = "order_000000000009d".parse::<AnyId>().unwrap();
+/// assert_eq!(id, AnyId::OrderId(OrderId::from(301)));
+/// ```
+#[macro_export]
+macro_rules! strong_ids {
+    (
+        $(#[$enum_outer:meta])*
+        $enum_vis:vis enum $any:ident;
+        $(
+            $(#[$outer:meta])*
+            $vis:vis struct $t:ident($($decl:tt)*);
+        )+
+    ) => {
+        $crate::strong_ids! {
+            $(
+                $(#[$outer])*
+                $vis struct $t($($decl)*);
+            )+
+        }
+
+        $crate::strong_id_enum! {
+            $(#[$enum_outer])*
+            $enum_vis enum $any {
+                $($t($t)),+
+            }
+        }
+    };
+    (
+        $(
+            $(#[$outer:meta])*
+            $vis:vis struct $t:ident($($decl:tt)*);
+        )*
+    ) => {
+        $(
+            $crate::_internal_strong_ids_item! {
+                $(#[$outer])*
+                $vis struct $t($($decl)*)
+            }
+        )*
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! _internal_strong_ids_item {
+    (
+        $(#[$outer:meta])*
+        $vis:vis struct $t:ident($inner_vis:vis Uuid$( => $prefix:literal)?)
+    ) => {
+        $crate::strong_uuid! {
+            $(#[$outer])*
+            $vis struct $t($inner_vis Uuid$( => $prefix)?)
+        }
+    };
+    (
+        $(#[$outer:meta])*
+        $vis:vis struct $t:ident($inner_vis:vis $inner:ty$( => $prefix:literal)?)
+    ) => {
+        $crate::strong_id! {
+            $(#[$outer])*
+            $vis struct $t($inner_vis $inner$( => $prefix)?)
+        }
     };
 }
 
 /// Generate a StrongId backed by a [`Uuid`].
 ///
 /// This is a convenient wrapper around to [`strong_id`] which derives [`StrongUuid`].
+///
+/// `#[strong_id(default = v4)]` or `#[strong_id(default = v7)]` generates a `new()` constructor
+/// for the given UUID version, so the version of each type is chosen in one place.
+///
+#[cfg_attr(feature = "uuid-v4", doc = "```rust")]
+#[cfg_attr(not(feature = "uuid-v4"), doc = "```rust,ignore")]
+/// use strong_id::{strong_uuid, StrongId};
+///
+/// strong_uuid! {
+///     #[strong_id(default = v4)]
+///     pub struct UserId(Uuid => "user")
+/// }
+///
+/// assert_eq!(UserId::new().id().get_version_num(), 4);
+/// ```
 #[cfg(feature = "uuid")]
 #[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
 #[macro_export]
@@ -402,19 +1661,23 @@ macro_rules! strong_uuid {
     (
         $(#[$outer:meta])*
         $vis:vis struct $t:ident($inner_vis:vis Uuid)
+        $({ $($constants:tt)* })?
     ) => {
         $crate::_internal_strong_uuid! {
             $(#[$outer])*
             $vis struct $t($inner_vis Uuid)
+            { $($($constants)*)? }
         }
     };
     (
         $(#[$outer:meta])*
         $vis:vis struct $t:ident($inner_vis:vis Uuid => $prefix:literal)
+        $({ $($constants:tt)* })?
     ) => {
         $crate::_internal_strong_uuid! {
             $(#[$outer])*
             $vis struct $t($inner_vis Uuid => $prefix)
+            { $($($constants)*)? }
         }
     };
 }
@@ -426,11 +1689,13 @@ macro_rules! _internal_strong_uuid {
     (
         $(#[$outer:meta])*
         $vis:vis struct $t:ident($inner_vis:vis Uuid$( => $prefix:literal)?)
+        { $($constants:tt)* }
     ) => {
 		$crate::strong_id!{
 			#[derive($crate::StrongUuid)]
             $(#[$outer])*
 			$vis struct $t($inner_vis $crate::uuid::Uuid$( => $prefix)?)
+			{ $($constants)* }
 		}
     };
 }
@@ -441,17 +1706,30 @@ macro_rules! _internal_impl_common {
 	(@@internal $t:ident($inner:ty)) => {
 		impl ::core::fmt::Display for $t {
 			fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-				use $crate::{Id, StrongId};
-				match self.prefix() {
-					Some(prefix) => write!(f, "{}_{}", prefix, self.0.encode()),
-					None => write!(f, "{}", self.0.encode()),
-				}
+				use $crate::StrongId;
+				$crate::_internal_fmt_canonical(f, |f| {
+					if let Some(prefix) = self.prefix() {
+						f.write_str(prefix)?;
+						f.write_str("_")?;
+					}
+					if f.alternate() {
+						f.write_str(&self._internal_suffix_uppercase())
+					} else {
+						f.write_str(&self.suffix_encoded())
+					}
+				})
 			}
 		}
 
-		impl From<$t> for $inner {
-			fn from(value: $t) -> Self {
-				value.0
+		impl $t {
+			/// The ID with an uppercase suffix, e.g. for printing on labels. The same as formatting
+			/// the ID with `{:#}`.
+			///
+			/// Uppercase IDs only parse with a case-insensitive encoding, e.g.
+			/// `Base32<CrockfordLenient>`.
+			#[allow(clippy::wrong_self_convention)]
+			pub fn to_uppercase_string(&self) -> ::std::string::String {
+				::std::format!("{self:#}")
 			}
 		}
 
@@ -460,6 +1738,18 @@ macro_rules! _internal_impl_common {
 				Self(value)
 			}
 		}
+
+		impl PartialEq<$inner> for $t {
+			fn eq(&self, other: &$inner) -> bool {
+				self.0 == *other
+			}
+		}
+
+		impl PartialEq<$t> for $inner {
+			fn eq(&self, other: &$t) -> bool {
+				*self == other.0
+			}
+		}
 	};
 }
 
@@ -472,62 +1762,319 @@ macro_rules! _internal_impl_from_str {
 
             #[inline]
             fn from_str(value: &str) -> ::core::result::Result<Self, Self::Err> {
-				let split = value.rsplit_once('_');
+				if Self::_INTERNAL_CASE_INSENSITIVE {
+					Self::from_str_relaxed(value)
+				} else {
+					Self::_internal_from_str_strict(value)
+				}
+			}
+		}
+
+		impl $t {
+			/// Parse an ID, also accepting uppercase prefixes and suffixes, e.g. for IDs typed by
+			/// people or passed through systems which fold case
+			///
+			/// `FromStr` only accepts IDs in their canonical case, unless the type is marked with
+			/// `#[strong_id(case_insensitive)]`.
+			pub fn from_str_relaxed(value: &str) -> ::core::result::Result<Self, $crate::Error> {
+				match Self::_internal_split_suffix(value) {
+					Some((prefix, suffix)) => Self::_internal_from_str_strict(&::std::format!(
+						"{}_{}",
+						prefix.to_ascii_lowercase(),
+						Self::_internal_normalize_suffix(suffix),
+					)),
+					None => Self::_internal_from_str_strict(&Self::_internal_normalize_suffix(value)),
+				}
+			}
+
+            fn _internal_from_str_strict(value: &str) -> ::core::result::Result<Self, $crate::Error> {
+				let canonical = $crate::_internal_canonicalize(value);
+				let value: &str = &canonical;
+				let split = Self::_internal_split_suffix(value);
 
 				#[allow(unused_mut)]
 				#[allow(unused_assignments)]
 				let mut prefix: Option<&str> = None;
 				$(prefix = Some($prefix);)?
 
-                let suffix = match prefix {
+                let id = match prefix {
                     Some(prefix) => {
 						 match split {
 							  None => return Err($crate::Error::MissingPrefix(prefix.into())),
 							  Some((parsed_prefix, _suffix)) if parsed_prefix.is_empty() => return Err($crate::Error::MissingPrefix(prefix.into())),
 							  Some((parsed_prefix, suffix)) => {
+								  $crate::_internal_check_delimiters(parsed_prefix)?;
+
 								  if parsed_prefix != prefix {
 									  return Err($crate::Error::InvalidPrefix(prefix.to_string(), parsed_prefix.to_string()));
 								  }
 
-								  <$inner as $crate::Id>::decode(suffix)?
+								  Self::from_suffix_encoded(suffix)
+									  .map_err(|error| error._internal_offset(parsed_prefix.len() + 1))?
 							  },
 						 }
 					},
                     None => {
 						 match split {
 							  Some((parsed_prefix, _suffix)) => return Err($crate::Error::NoPrefixExpected(parsed_prefix.to_string())),
-							  None => <$inner as $crate::Id>::decode(value)?
+							  None => Self::from_suffix_encoded(value)?
 						 }
 					}
                 };
 
-				Ok(Self(suffix))
+				Ok(id)
             }
         }
-	}
-}
 
-#[cfg(test)]
-mod tests {
-	use super::*;
+		impl ::core::convert::TryFrom<&str> for $t {
+			type Error = $crate::Error;
 
-	#[test]
-	fn u32_prefix_valid() {
-		strong_id!(pub struct PrefixU32(u32 => "prefix"));
+			fn try_from(value: &str) -> ::core::result::Result<Self, Self::Error> {
+				value.parse()
+			}
+		}
 
-		assert_eq!(PrefixU32::from(0).prefix(), Some("prefix"));
+		impl ::core::convert::TryFrom<::std::string::String> for $t {
+			type Error = $crate::Error;
 
-		struct Case(&'static str, u32);
-		let cases = vec![
-			Case("prefix_0000000", u32::MIN),
-			Case("prefix_3zzzzzz", u32::MAX),
-			Case("prefix_000009d", 301),
-		];
+			fn try_from(value: ::std::string::String) -> ::core::result::Result<Self, Self::Error> {
+				value.parse()
+			}
+		}
 
-		for case in cases {
-			let id = PrefixU32::from(case.1);
-			assert_eq!(&format!("{id}"), case.0);
-			assert_eq!(*id.id(), case.1);
+		impl ::core::convert::TryFrom<&[u8]> for $t {
+			type Error = $crate::Error;
+
+			fn try_from(value: &[u8]) -> ::core::result::Result<Self, Self::Error> {
+				::core::str::from_utf8(value)
+					.map_err(|_| $crate::Error::InvalidUtf8)?
+					.parse()
+			}
+		}
+
+		impl $t {
+			/// The ID as a URL path segment or query value, which never needs to be percent-encoded
+			///
+			/// This is the same as the `Display` output, unless the encoding of the ID uses symbols
+			/// which are reserved in URLs, e.g. the check symbols of `Base32Check`, which are
+			/// percent-encoded. For shorter URLs, e.g. of UUID-backed IDs, select the `Base64Url`
+			/// encoding with `#[strong_id(encoding = Base64Url)]`.
+			#[allow(clippy::wrong_self_convention)]
+			pub fn to_url_component(&self) -> ::std::string::String {
+				$crate::_internal_to_url_component(::std::string::ToString::to_string(self))
+			}
+
+			/// Parse an ID from a URL path segment or query value, see
+			/// [`to_url_component`](Self::to_url_component)
+			pub fn from_url_component(value: &str) -> ::core::result::Result<Self, $crate::Error> {
+				$crate::_internal_from_url_component(value)?.parse()
+			}
+		}
+	}
+}
+
+/// Generate an enum over a closed set of StrongId types
+///
+/// The enum dispatches on prefix to implement [`FromStr`](core::str::FromStr), and implements
+/// `Display`, [`ErasedStrongId`], `From` for each of its variants and, when the "serde" feature is
+/// enabled, `Serialize` and `Deserialize`.
+///
+/// ```rust
+/// use strong_id::{strong_id, strong_id_enum};
+///
+/// strong_id!(pub struct UserId(u16 => "user"));
+/// strong_id!(pub struct OrderId(u32 => "order"));
+///
+/// strong_id_enum! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum AnyId {
+///         User(UserId),
+///         Order(OrderId),
+///     }
+/// }
+///
+/// let id = "order_000009d".parse::<AnyId>().unwrap();
+/// assert_eq!(id, AnyId::Order(OrderId::from(301)));
+/// assert_eq!(id.to_string(), "order_000009d");
+/// assert_eq!(AnyId::from(UserId::from(3203)).to_string(), "user_0343");
+/// ```
+///
+/// The same impls, along with `is_*` and `as_*` accessors for each variant, are generated by
+/// deriving [`StrongId`](derive@StrongId) on an enum.
+///
+/// ```rust
+/// use strong_id::{strong_id, StrongId};
+///
+/// strong_id!(pub struct UserId(u16 => "user"));
+/// strong_id!(pub struct OrderId(u32 => "order"));
+///
+/// #[derive(Debug, PartialEq, StrongId)]
+/// pub enum AnyId {
+///     User(UserId),
+///     Order(OrderId),
+/// }
+///
+/// let id = "order_000009d".parse::<AnyId>().unwrap();
+/// assert!(id.is_order());
+/// assert_eq!(id.as_order(), Some(&OrderId::from(301)));
+/// assert_eq!(id.as_user(), None);
+/// ```
+#[macro_export]
+macro_rules! strong_id_enum {
+	(
+		$(#[$outer:meta])*
+		$vis:vis enum $t:ident {
+			$($variant:ident($inner:ty)),+ $(,)?
+		}
+	) => {
+		$(#[$outer])*
+		$vis enum $t {
+			$($variant($inner)),+
+		}
+
+		$crate::_internal_impl_strong_id_enum!($t { $($variant($inner)),+ });
+	};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! _internal_impl_strong_id_enum {
+	($t:ident { $($variant:ident($inner:ty)),+ }) => {
+		$(
+			impl From<$inner> for $t {
+				fn from(value: $inner) -> Self {
+					Self::$variant(value)
+				}
+			}
+		)+
+
+		impl ::core::fmt::Display for $t {
+			fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+				match self {
+					$(Self::$variant(id) => ::core::fmt::Display::fmt(id, f)),+
+				}
+			}
+		}
+
+		impl $crate::ErasedStrongId for $t {
+			fn erased_prefix(&self) -> Option<&str> {
+				match self {
+					$(Self::$variant(id) => $crate::ErasedStrongId::erased_prefix(id)),+
+				}
+			}
+
+			fn encoded(&self) -> ::std::string::String {
+				match self {
+					$(Self::$variant(id) => $crate::ErasedStrongId::encoded(id)),+
+				}
+			}
+		}
+
+		impl ::core::str::FromStr for $t {
+			type Err = $crate::Error;
+
+			fn from_str(value: &str) -> ::core::result::Result<Self, Self::Err> {
+				$(
+					let prefix = <$inner>::_internal_split_suffix(value).map(|(prefix, _suffix)| prefix);
+					if prefix == <$inner as $crate::StaticPrefix>::PREFIX {
+						return value.parse::<$inner>().map(Self::$variant);
+					}
+				)+
+
+				let prefix = value.rsplit_once('_').map(|(prefix, _suffix)| prefix);
+				Err($crate::Error::UnknownPrefix(prefix.unwrap_or_default().to_string()))
+			}
+		}
+
+		$crate::_internal_impl_serde_from_str!($t);
+	};
+}
+
+#[cfg(feature = "serde")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! _internal_impl_serde_from_str {
+	($t:ident) => {
+		impl $crate::serde::Serialize for $t {
+			fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+			where
+				S: $crate::serde::Serializer,
+			{
+				serializer.serialize_str(&self.to_string())
+			}
+		}
+
+		impl<'de> $crate::serde::Deserialize<'de> for $t {
+			fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+			where
+				D: $crate::serde::Deserializer<'de>,
+			{
+				<::std::string::String as $crate::serde::Deserialize>::deserialize(deserializer)?
+					.parse::<Self>()
+					.map_err(|error| $crate::serde::de::Error::custom(error.to_string()))
+			}
+		}
+	};
+}
+
+#[cfg(not(feature = "serde"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! _internal_impl_serde_from_str {
+	($t:ident) => {};
+}
+
+/// Assert that a generated StrongId round-trips through its encoding
+///
+/// Intended to be called from a test in downstream crates, this checks boundary and pseudo-random
+/// valid IDs of the type:
+///
+/// - parsing and encoding produces the same string, of length `ENCODED_LEN`
+/// - when the "serde" feature is enabled, serializing and deserializing produces the same ID
+/// - mutations of each ID, such as invalid characters, truncation, or a missing or different
+///   prefix, are rejected
+///
+/// Failures panic with the offending input. The inputs are deterministic, so failures are
+/// reproducible.
+///
+/// ```rust
+/// use strong_id::{assert_roundtrip, strong_id};
+///
+/// strong_id!(pub struct UserId(u16 => "user"));
+///
+/// assert_roundtrip!(UserId);
+/// ```
+#[macro_export]
+macro_rules! assert_roundtrip {
+	($t:ty) => {
+		$crate::_internal_assert_roundtrip::<$t>(<$t>::ENCODED_LEN)
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn u32_prefix_valid() {
+		strong_id!(pub struct PrefixU32(u32 => "prefix"));
+
+		assert_eq!(PrefixU32::from(0).prefix(), Some("prefix"));
+		assert_eq!(PrefixU32::PREFIX, Some("prefix"));
+		assert_eq!(PrefixU32::ENCODED_LEN, 14);
+		assert_eq!(PrefixU32::from(301).suffix_encoded(), "000009d");
+
+		struct Case(&'static str, u32);
+		let cases = vec![
+			Case("prefix_0000000", u32::MIN),
+			Case("prefix_3zzzzzz", u32::MAX),
+			Case("prefix_000009d", 301),
+		];
+
+		for case in cases {
+			let id = PrefixU32::from(case.1);
+			assert_eq!(&format!("{id}"), case.0);
+			assert_eq!(*id.id(), case.1);
 
 			let parsed = case.0.parse::<PrefixU32>().unwrap();
 			assert_eq!(parsed.0, case.1);
@@ -564,11 +2111,103 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn multiple_underscores() {
+		strong_id!(pub struct PrefixU16(u16 => "prefix"));
+		strong_id!(pub struct NoPrefixU16(u16));
+
+		// a prefix with inner underscores is only a mismatch with the "delimited" feature
+		let delimited = |found: &str| {
+			if cfg!(feature = "delimited") {
+				Error::InvalidPrefix("prefix".into(), found.into())
+			} else {
+				Error::DelimitedPrefix(found.into())
+			}
+		};
+
+		struct Case(&'static str, Error);
+		let cases = vec![
+			Case("prefix_foo_0343", delimited("prefix_foo")),
+			Case("foo_prefix_0343", delimited("foo_prefix")),
+			Case("a_b_c_0343", delimited("a_b_c")),
+			Case("prefix__0343", Error::MisplacedDelimiter("prefix_".into())),
+			Case("_prefix_0343", Error::MisplacedDelimiter("_prefix".into())),
+			Case("__0343", Error::MisplacedDelimiter("_".into())),
+			Case("_0343", Error::MissingPrefix("prefix".into())),
+		];
+
+		for case in cases {
+			assert_eq!(
+				case.0.parse::<PrefixU16>().unwrap_err(),
+				case.1,
+				"{}",
+				case.0
+			);
+		}
+
+		// any prefix is unexpected
+		struct NoPrefixCase(&'static str, &'static str);
+		let cases = vec![
+			NoPrefixCase("foo_bar_0343", "foo_bar"),
+			NoPrefixCase("foo__0343", "foo_"),
+			NoPrefixCase("_0343", ""),
+		];
+
+		for case in cases {
+			assert_eq!(
+				case.0.parse::<NoPrefixU16>().unwrap_err(),
+				Error::NoPrefixExpected(case.1.into())
+			);
+		}
+	}
+
+	#[cfg(feature = "delimited")]
+	#[test]
+	fn multiple_underscores_delimited() {
+		strong_id!(pub struct DelimitedU16(u16 => "foo_bar"));
+
+		assert_eq!("foo_bar_0343".parse::<DelimitedU16>().unwrap(), 3203);
+		assert_eq!(
+			"foo__bar_0343".parse::<DelimitedU16>().unwrap_err(),
+			Error::InvalidPrefix("foo_bar".into(), "foo__bar".into())
+		);
+		assert_eq!(
+			"foo_bar__0343".parse::<DelimitedU16>().unwrap_err(),
+			Error::MisplacedDelimiter("foo_bar_".into())
+		);
+		assert_eq!(
+			"foo_0343".parse::<DelimitedU16>().unwrap_err(),
+			Error::InvalidPrefix("foo_bar".into(), "foo".into())
+		);
+	}
+
+	#[test]
+	fn error_codes() {
+		strong_id!(pub struct PrefixU32(u32 => "prefix"));
+
+		struct Case(&'static str, &'static str);
+		let cases = vec![
+			Case("0000000", "ERR_ID_MISSING_PREFIX"),
+			Case("prefix_0000000000", "ERR_ID_LENGTH"),
+			Case("prefix_z000000", "ERR_ID_INVALID_FIRST_CHARACTER"),
+			Case("prefix_000000l", "ERR_ID_INVALID_CHARACTER"),
+			Case("dyn_3000000", "ERR_ID_PREFIX_MISMATCH"),
+			Case("prefix__3000000", "ERR_ID_MISPLACED_DELIMITER"),
+		];
+
+		for case in cases {
+			let error = case.0.parse::<PrefixU32>().unwrap_err();
+			assert_eq!(error.code(), case.1);
+		}
+	}
+
 	#[test]
 	fn u16_no_prefix_valid() {
 		strong_id!(pub struct NoPrefixU16(u16));
 
 		assert_eq!(NoPrefixU16::from(0).prefix(), None);
+		assert_eq!(NoPrefixU16::PREFIX, None);
+		assert_eq!(NoPrefixU16::ENCODED_LEN, 4);
 
 		struct Case(&'static str, u16);
 		let cases = vec![
@@ -607,6 +2246,32 @@ mod tests {
 		}
 	}
 
+	#[cfg(feature = "serde")]
+	#[test]
+	fn skip_serde() {
+		strong_id! {
+			#[strong_id(skip_serde)]
+			pub struct LegacyId(u32 => "legacy")
+		}
+
+		// stored as a bare integer by an older service
+		impl serde::Serialize for LegacyId {
+			fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				serializer.serialize_u32(self.0)
+			}
+		}
+
+		impl<'de> serde::Deserialize<'de> for LegacyId {
+			fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				u32::deserialize(deserializer).map(Self)
+			}
+		}
+
+		let id = LegacyId::from(301);
+		assert_eq!(serde_json::to_string(&id).unwrap(), "301");
+		assert_eq!(serde_json::from_str::<LegacyId>("301").unwrap(), id);
+	}
+
 	#[cfg(feature = "serde")]
 	#[test]
 	fn u32_prefix_serde() {
@@ -618,4 +2283,1176 @@ mod tests {
 		let value = serde_json::to_string(&value).unwrap();
 		assert_eq!("\"prefix_000009d\"", value);
 	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn deny_sequential() {
+		strong_id! {
+			#[strong_id(deny_sequential)]
+			pub struct UserId(Uuid => "user")
+		}
+		strong_id! {
+			#[strong_id(deny_sequential)]
+			pub struct OrderId(u128 => "order")
+		}
+		strong_id! {
+			#[strong_id(deny_sequential)]
+			pub struct TicketId(core::num::Wrapping<u128> => "ticket")
+		}
+
+		assert_eq!(
+			UserId::from(Uuid::nil()).to_string(),
+			"user_00000000000000000000000000"
+		);
+		assert_eq!(
+			OrderId::from(0).to_string(),
+			"order_00000000000000000000000000"
+		);
+		assert_eq!(
+			TicketId::from(core::num::Wrapping(0)).to_string(),
+			"ticket_00000000000000000000000000"
+		);
+	}
+
+	#[test]
+	fn roundtrip() {
+		strong_id!(pub struct Plain(u8));
+		strong_id!(pub struct UserId(u16 => "user"));
+		strong_id!(pub struct OrderId(u32 => "order"));
+		strong_id! {
+			#[strong_id(serde = "struct")]
+			pub struct InvoiceId(u64 => "invoice")
+		}
+		strong_id!(pub struct AccountId(u128 => "account"));
+		strong_id!(pub struct NonZeroId(core::num::NonZeroU8 => "nonzero"));
+
+		assert_roundtrip!(Plain);
+		assert_roundtrip!(NonZeroId);
+		assert_roundtrip!(UserId);
+		assert_roundtrip!(OrderId);
+		assert_roundtrip!(InvoiceId);
+		assert_roundtrip!(AccountId);
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn uuid_roundtrip() {
+		strong_uuid!(pub struct UserId(Uuid => "user"));
+		strong_uuid!(pub struct Plain(Uuid));
+
+		assert_roundtrip!(UserId);
+		assert_roundtrip!(Plain);
+	}
+
+	#[test]
+	fn compare_inner() {
+		strong_id!(pub struct UserId(u32 => "user"));
+
+		let id = UserId::from(301);
+		assert!(id == 301);
+		assert!(301 == id);
+		assert!(id != 302);
+		assert!(id < 302);
+		assert!(300 < id);
+	}
+
+	#[test]
+	fn derive_ord() {
+		// the derive only implements ordering with `ord`, so that it can be derived instead
+		#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord, StrongId)]
+		#[strong_id(prefix = "user", standalone)]
+		pub struct UserId(u16);
+
+		#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, StrongId)]
+		#[strong_id(prefix = "order", standalone, ord)]
+		pub struct OrderId(u16);
+
+		assert!(UserId(1) < UserId(2));
+		assert!(OrderId(1) < OrderId(2));
+		assert!(OrderId(1) < 2);
+	}
+
+	#[test]
+	fn derive_into_inner() {
+		// the derive only implements `From<Id> for Inner` with `into_inner`, so that it can be
+		// implemented by hand instead
+		#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, StrongId)]
+		#[strong_id(prefix = "user", standalone)]
+		pub struct UserId(u16);
+
+		impl From<UserId> for u16 {
+			fn from(value: UserId) -> Self {
+				value.0 + 1
+			}
+		}
+
+		#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, StrongId)]
+		#[strong_id(prefix = "order", standalone, into_inner)]
+		pub struct OrderId(u16);
+
+		assert_eq!(u16::from(UserId(1)), 2);
+		assert_eq!(u16::from(OrderId(1)), 1);
+	}
+
+	#[test]
+	fn unordered() {
+		strong_id! {
+			#[strong_id(unordered)]
+			pub struct SessionId(u32 => "session")
+		}
+
+		let mut ids = [SessionId::from(2), SessionId::from(1)];
+		ids.sort_by_key(|id| id.into_inner());
+		assert_eq!(ids, [SessionId::from(1), SessionId::from(2)]);
+
+		let set = ids
+			.iter()
+			.copied()
+			.collect::<std::collections::HashSet<_>>();
+		assert!(set.contains(&SessionId::from(1)));
+		assert!(SessionId::from(1) == 1);
+	}
+
+	#[test]
+	fn base64url_suffix() {
+		strong_id! {
+			#[strong_id(encoding = Base64Url)]
+			pub struct UserId(u16 => "user")
+		}
+		strong_id! {
+			#[strong_id(encoding = Base64Url)]
+			pub struct Plain(u16)
+		}
+		strong_id_enum! {
+			#[derive(Debug, PartialEq)]
+			pub enum AnyId {
+				User(UserId),
+				Plain(Plain),
+			}
+		}
+
+		// `0xfbff` encodes to `-_8`, which contains the separator
+		let id = UserId::from(0xFBFF);
+		assert_eq!(id.to_string(), "user_-_8");
+		assert_eq!("user_-_8".parse::<UserId>(), Ok(id));
+		assert_eq!("user__8A".parse::<UserId>(), Ok(UserId::from(0xFFC0)));
+		assert_eq!("_8A".parse::<Plain>(), Ok(Plain::from(0xFFC0)));
+		assert_eq!("user_-_8".parse::<AnyId>(), Ok(AnyId::User(id)));
+		assert_eq!(
+			"-_8".parse::<AnyId>(),
+			Ok(AnyId::Plain(Plain::from(0xFBFF)))
+		);
+		roundtrip::RoundtripSerde::assert_serde_roundtrip(&id);
+
+		assert_eq!(
+			"-_8".parse::<UserId>(),
+			Err(Error::MissingPrefix("user".into()))
+		);
+		assert_eq!(
+			"account_-_8".parse::<UserId>(),
+			Err(Error::InvalidPrefix("user".into(), "account".into()))
+		);
+		assert_eq!(
+			UserId::EXPECTED_FORMAT.to_string(),
+			"\"user_\" followed by 3 characters of [A-Za-z0-9\\-_]"
+		);
+	}
+
+	#[test]
+	fn derive_standalone() {
+		#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, StrongId)]
+		#[strong_id(prefix = "user", standalone)]
+		pub struct UserId(u16);
+
+		#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, StrongId)]
+		#[strong_id(standalone)]
+		pub struct PlainId(u16);
+
+		let id = "user_0343".parse::<UserId>().unwrap();
+		assert_eq!(id, UserId::from(3203));
+		assert_eq!(id, 3203);
+		assert_eq!(id.to_string(), "user_0343");
+		assert_eq!(UserId::try_from("user_0343"), Ok(id));
+		assert_eq!(
+			"order_0343".parse::<UserId>(),
+			Err(Error::InvalidPrefix("user".into(), "order".into()))
+		);
+
+		assert_eq!("0343".parse::<PlainId>().unwrap().to_string(), "0343");
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn derive_standalone_uuid() {
+		#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, StrongId, StrongUuid)]
+		#[strong_id(prefix = "user", standalone)]
+		pub struct UserId(Uuid);
+
+		let id = UserId::from_u128(3203);
+		assert_eq!(id.to_string(), "user_00000000000000000000000343");
+		assert_eq!(id.to_string().parse::<UserId>(), Ok(id));
+	}
+
+	#[test]
+	fn derive_enum() {
+		strong_id!(pub struct UserId(u16 => "user"));
+		strong_id!(pub struct OrderLineId(u32 => "line"));
+
+		#[derive(Debug, PartialEq, StrongId)]
+		pub enum AnyId {
+			User(UserId),
+			OrderLine(OrderLineId),
+		}
+
+		let id = "line_000009d".parse::<AnyId>().unwrap();
+		assert_eq!(id, AnyId::OrderLine(OrderLineId::from(301)));
+		assert_eq!(id.to_string(), "line_000009d");
+		assert_eq!(id.erased_prefix(), Some("line"));
+		assert!(id.is_order_line());
+		assert!(!id.is_user());
+		assert_eq!(id.as_order_line(), Some(&OrderLineId::from(301)));
+		assert_eq!(id.as_user(), None);
+
+		let id = AnyId::from(UserId::from(3203));
+		assert_eq!(id.as_user(), Some(&UserId::from(3203)));
+		assert_eq!(
+			"team_0343".parse::<AnyId>(),
+			Err(Error::UnknownPrefix("team".into()))
+		);
+	}
+
+	#[test]
+	fn derive_named_field() {
+		use core::{fmt, marker::PhantomData, str::FromStr};
+
+		#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, StrongId)]
+		#[strong_id(prefix = "user", field = "value")]
+		pub struct NamedId {
+			value: u16,
+			marker: PhantomData<()>,
+		}
+
+		impl fmt::Display for NamedId {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				write!(f, "user_{}", self.suffix_encoded())
+			}
+		}
+
+		impl FromStr for NamedId {
+			type Err = Error;
+
+			fn from_str(value: &str) -> Result<Self, Self::Err> {
+				match value.strip_prefix("user_") {
+					Some(suffix) => Self::from_suffix_encoded(suffix),
+					None => Err(Error::MissingPrefix("user".into())),
+				}
+			}
+		}
+
+		impl PartialEq<u16> for NamedId {
+			fn eq(&self, other: &u16) -> bool {
+				self.value == *other
+			}
+		}
+
+		impl PartialEq<NamedId> for u16 {
+			fn eq(&self, other: &NamedId) -> bool {
+				*self == other.value
+			}
+		}
+
+		let id = NamedId::from_suffix_encoded("0343").unwrap();
+		assert_eq!(*id.id(), 3203);
+		assert_eq!(id.marker, PhantomData);
+		assert_eq!(id.to_string(), "user_0343");
+		assert_eq!(id.into_inner(), 3203);
+		assert_eq!("user_0343".parse::<NamedId>().unwrap(), 3203);
+	}
+
+	#[test]
+	fn derive_generic() {
+		use core::{fmt, marker::PhantomData, str::FromStr};
+
+		#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, StrongId)]
+		#[strong_id(prefix = "scoped", ord)]
+		pub struct Scoped<T: Id>(T);
+
+		impl<T: Id> fmt::Display for Scoped<T> {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				write!(f, "scoped_{}", self.suffix_encoded())
+			}
+		}
+
+		impl<T: Id> FromStr for Scoped<T> {
+			type Err = Error;
+
+			fn from_str(value: &str) -> Result<Self, Self::Err> {
+				match value.strip_prefix("scoped_") {
+					Some(suffix) => Self::from_suffix_encoded(suffix),
+					None => Err(Error::MissingPrefix("scoped".into())),
+				}
+			}
+		}
+
+		let id = Scoped::<u16>::from_suffix_encoded("0343").unwrap();
+		assert_eq!(*id.id(), 3203);
+		assert_eq!(id.to_string(), "scoped_0343");
+		assert_eq!(Scoped::<u16>::ENCODED_LEN, 11);
+		assert_eq!(Scoped::<u32>::ENCODED_LEN, 14);
+		assert!(Scoped(1u16) < Scoped(2u16));
+		assert_eq!(
+			"scoped_0343".parse::<Scoped<u16>>().unwrap().into_inner(),
+			3203
+		);
+
+		#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, StrongId)]
+		#[strong_id(prefix = "tenant", field = "value", ord, into_inner)]
+		pub struct TenantId<T>
+		where
+			T: Eq + 'static,
+		{
+			value: u64,
+			tenant: PhantomData<&'static T>,
+		}
+
+		impl<T: Eq> fmt::Display for TenantId<T> {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				write!(f, "tenant_{}", self.suffix_encoded())
+			}
+		}
+
+		impl<T: Eq> FromStr for TenantId<T> {
+			type Err = Error;
+
+			fn from_str(value: &str) -> Result<Self, Self::Err> {
+				match value.strip_prefix("tenant_") {
+					Some(suffix) => Self::from_suffix_encoded(suffix),
+					None => Err(Error::MissingPrefix("tenant".into())),
+				}
+			}
+		}
+
+		impl<T: Eq> PartialEq<u64> for TenantId<T> {
+			fn eq(&self, other: &u64) -> bool {
+				self.value == *other
+			}
+		}
+
+		impl<T: Eq> PartialEq<TenantId<T>> for u64 {
+			fn eq(&self, other: &TenantId<T>) -> bool {
+				*self == other.value
+			}
+		}
+
+		let id = TenantId::<()>::from_suffix_encoded("0000000000343").unwrap();
+		assert_eq!(u64::from(id), 3203);
+		assert!(id < 3204);
+	}
+
+	#[test]
+	fn field_visibility() {
+		mod ids {
+			strong_id! {
+				#[strong_id(accessor = "get", accessor = "value")]
+				pub struct UserId(pub(crate) u16 => "user")
+			}
+		}
+
+		let id = ids::UserId(3203);
+		let ids::UserId(value) = id;
+		assert_eq!(value, 3203);
+		assert_eq!(id.get(), &3203);
+		assert_eq!(id.value(), &3203);
+	}
+
+	#[test]
+	fn constants() {
+		strong_id!(pub struct UserId(u16 => "user") {
+			pub const SYSTEM = "user_0343";
+			const MAX = "user_1zzz";
+		});
+		strong_id!(pub struct BalanceId(i32) {
+			pub const MIN = "0000000";
+		});
+		strong_id!(pub struct OrderId(core::num::NonZeroU64 => "order") {
+			pub const FIRST = "order_0000000000001";
+		});
+
+		assert_eq!(UserId::SYSTEM, UserId::from(3203));
+		assert_eq!(UserId::MAX, UserId::from(u16::MAX));
+		assert_eq!(BalanceId::MIN, BalanceId::from(i32::MIN));
+		assert_eq!(BalanceId::MIN.to_string(), "0000000");
+		assert_eq!(
+			OrderId::FIRST,
+			OrderId::from(core::num::NonZeroU64::new(1).unwrap())
+		);
+	}
+
+	#[cfg(all(feature = "uuid-v4", feature = "uuid-v7"))]
+	#[test]
+	fn default_version() {
+		strong_uuid! {
+			#[strong_id(default = v4)]
+			pub struct SessionId(Uuid => "session")
+		}
+		strong_uuid! {
+			#[strong_id(default = "v7")]
+			pub struct EventId(Uuid => "event")
+		}
+
+		assert_eq!(SessionId::new().id().get_version_num(), 4);
+		assert_eq!(EventId::new().id().get_version_num(), 7);
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn uuid_constants() {
+		strong_uuid!(pub struct UserId(Uuid => "user") {
+			pub const SYSTEM = "user_01h455vb4pex5vsknk084sn02q";
+		});
+
+		assert_eq!(
+			UserId::SYSTEM,
+			"user_01h455vb4pex5vsknk084sn02q".parse::<UserId>().unwrap()
+		);
+	}
+
+	#[test]
+	fn default_nil() {
+		strong_id! {
+			#[strong_id(default_nil)]
+			pub struct UserId(u64 => "user")
+		}
+
+		assert_eq!(UserId::default(), UserId::from(0));
+		assert_eq!(UserId::default().to_string(), "user_0000000000000");
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn default_nil_uuid() {
+		strong_uuid! {
+			#[strong_id(default_nil)]
+			pub struct SessionId(Uuid => "session")
+		}
+
+		assert_eq!(SessionId::default(), SessionId::from(Uuid::nil()));
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn forwarded_attributes() {
+		strong_uuid! {
+			#[derive(Default, PartialOrd, Ord)]
+			#[strong_id(unordered)]
+			#[repr(transparent)]
+			pub struct SessionId(Uuid => "session")
+		}
+
+		assert_eq!(SessionId::default(), SessionId::from(Uuid::nil()));
+		assert!(SessionId::from_u128(1) < SessionId::from_u128(2));
+		assert_eq!(core::mem::size_of::<SessionId>(), 16);
+	}
+
+	#[test]
+	fn strong_ids() {
+		strong_ids! {
+			pub struct UserId(u16 => "user");
+			#[strong_id(encoding = Hex)]
+			pub struct HexId(pub u16 => "hex");
+			pub struct PlainId(u32);
+		}
+
+		assert_eq!(UserId::from(3203).to_string(), "user_0343");
+		assert_eq!(HexId(0x0c83).to_string(), "hex_0c83");
+		assert_eq!(PlainId::from(301).to_string(), "000009d");
+
+		strong_ids! {
+			#[derive(Debug, PartialEq)]
+			pub enum AnyId;
+
+			pub struct TeamId(u16 => "team");
+			pub struct OrgId(u16 => "org");
+		}
+
+		assert_eq!(
+			"org_0343".parse::<AnyId>(),
+			Ok(AnyId::OrgId(OrgId::from(3203)))
+		);
+		assert_eq!(AnyId::from(TeamId::from(3203)).to_string(), "team_0343");
+	}
+
+	#[test]
+	fn encode_into() {
+		strong_id!(pub struct UserId(u16 => "user"));
+		strong_id!(pub struct Plain(u16));
+		strong_id! {
+			#[strong_id(encoding = Hex)]
+			pub struct HexId(u16 => "hex")
+		}
+
+		let mut buf = [0u8; 16];
+		let len = UserId::from(3203).encode_into(&mut buf).unwrap();
+		assert_eq!(len, UserId::ENCODED_LEN);
+		assert_eq!(&buf[..len], b"user_0343");
+
+		let len = Plain::from(3203).encode_into(&mut buf).unwrap();
+		assert_eq!(&buf[..len], b"0343");
+
+		let len = HexId::from(0x0c83).encode_into(&mut buf).unwrap();
+		assert_eq!(&buf[..len], HexId::from(0x0c83).to_string().as_bytes());
+
+		assert_eq!(
+			UserId::from(3203).encode_into(&mut buf[..8]),
+			Err(Error::BufferTooSmall(9, 8))
+		);
+		assert_eq!(
+			3203u16.encode_into(&mut [0u8; 3]),
+			Err(Error::BufferTooSmall(4, 3))
+		);
+
+		// through the trait, which keeps the encoding of generated IDs
+		fn encode_dyn<T: Id>(id: &impl StrongId<T>) -> Vec<u8> {
+			let mut buf = [0u8; 16];
+			let len = id.encode_into(&mut buf).unwrap();
+			buf[..len].to_vec()
+		}
+		assert_eq!(encode_dyn(&UserId::from(3203)), b"user_0343");
+		assert_eq!(encode_dyn(&&HexId::from(0x0c83)), b"hex_0c83");
+		assert_eq!(
+			encode_dyn(&DynamicStrongId::new("user", 3203u16).unwrap()),
+			b"user_0343"
+		);
+		assert_eq!(
+			DynamicStrongId::new_plain(3203u16).encode_into(&mut buf),
+			Ok(4)
+		);
+		assert_eq!(
+			DynamicStrongId::new("user", 3203u16)
+				.unwrap()
+				.encode_into(&mut buf[..8]),
+			Err(Error::BufferTooSmall(9, 8))
+		);
+	}
+
+	#[test]
+	fn url_component() {
+		strong_id!(pub struct UserId(u16 => "user"));
+		strong_id! {
+			#[strong_id(encoding = Base32Check)]
+			pub struct TicketId(u16 => "ticket")
+		}
+
+		let user_id = UserId::from(3203);
+		assert_eq!(user_id.to_url_component(), "user_0343");
+		assert_eq!(UserId::from_url_component("user_0343"), Ok(user_id));
+
+		// the check symbols `*`, `$` and `=` are reserved
+		let ticket_id = TicketId::from(32);
+		assert_eq!(ticket_id.to_string(), "ticket_0010*");
+		assert_eq!(ticket_id.to_url_component(), "ticket_0010%2A");
+		assert_eq!(
+			TicketId::from_url_component("ticket_0010%2A"),
+			Ok(ticket_id)
+		);
+		assert_eq!(
+			TicketId::from_url_component("ticket_0010%2a"),
+			Ok(ticket_id)
+		);
+		assert_eq!(TicketId::from_url_component("ticket_0010*"), Ok(ticket_id));
+		assert_eq!(
+			TicketId::from_url_component("ticket_0010%2"),
+			Err(Error::InvalidLength(5, 6))
+		);
+		assert_eq!(
+			TicketId::from_url_component("ticket_0010%FF"),
+			Err(Error::InvalidUtf8)
+		);
+	}
+
+	#[test]
+	fn uppercase() {
+		strong_id!(pub struct UserId(u16 => "user"));
+		strong_id! {
+			#[strong_id(encoding = Base32<CrockfordLenient>)]
+			pub struct VoucherId(u16 => "voucher")
+		}
+		strong_id! {
+			#[strong_id(encoding = Base64Url)]
+			pub struct SessionId(u16 => "session")
+		}
+
+		let user_id = UserId::from(4095);
+		assert_eq!(format!("{user_id:#}"), "user_03ZZ");
+		assert_eq!(user_id.to_uppercase_string(), "user_03ZZ");
+		assert_eq!(user_id.to_string(), "user_03zz");
+		assert!("user_03ZZ".parse::<UserId>().is_err());
+
+		let voucher_id = VoucherId::from(4095);
+		assert_eq!(
+			voucher_id.to_uppercase_string().parse::<VoucherId>(),
+			Ok(voucher_id)
+		);
+
+		// base64url is case-sensitive, so it is never uppercased
+		assert_eq!(SessionId::from(0xFBFF).to_uppercase_string(), "session_-_8");
+	}
+
+	#[test]
+	fn case_insensitive() {
+		strong_id!(pub struct UserId(u16 => "user"));
+		strong_id! {
+			#[strong_id(case_insensitive)]
+			pub struct VoucherId(u16 => "voucher")
+		}
+		strong_id! {
+			#[strong_id(encoding = Base32Check, case_insensitive)]
+			pub struct TicketId(u16)
+		}
+
+		let user_id = UserId::from(4095);
+		assert_eq!(
+			"user_03ZZ".parse::<UserId>(),
+			Err(Error::Base32Error(Base32Error::InvalidByte(7, 'Z')))
+		);
+		assert_eq!(UserId::from_str_relaxed("user_03ZZ"), Ok(user_id));
+		assert_eq!(UserId::from_str_relaxed("USER_03zZ"), Ok(user_id));
+		assert_eq!(UserId::from_str_relaxed("user_03zz"), Ok(user_id));
+
+		assert_eq!(
+			"VOUCHER_03ZZ".parse::<VoucherId>(),
+			Ok(VoucherId::from(4095))
+		);
+		assert_eq!(
+			"TEAM_03ZZ".parse::<VoucherId>(),
+			Err(Error::InvalidPrefix("voucher".into(), "team".into()))
+		);
+		assert_eq!("0010*".parse::<TicketId>(), Ok(TicketId::from(32)));
+		assert_eq!("0014U".parse::<TicketId>(), Ok(TicketId::from(36)));
+	}
+
+	#[test]
+	fn no_into_inner() {
+		strong_id! {
+			#[strong_id(no_into_inner)]
+			pub struct UserId(u64 => "user")
+		}
+
+		// a blanket `From` impl would conflict with `From<UserId> for u64`, if it were implemented
+		struct Total(u64);
+		impl<T: Into<u64>> From<T> for Total {
+			fn from(value: T) -> Self {
+				Self(value.into())
+			}
+		}
+		impl From<UserId> for Total {
+			fn from(value: UserId) -> Self {
+				Self(value.into_inner())
+			}
+		}
+
+		assert_eq!(Total::from(UserId::from(301)).0, 301);
+		assert_eq!(UserId::from(301), 301);
+	}
+
+	#[test]
+	fn invalid_character_position() {
+		strong_id!(pub struct UserId(u16 => "user"));
+		strong_id!(pub struct Plain(u16));
+
+		assert_eq!(
+			"user_0l43".parse::<UserId>(),
+			Err(Error::Base32Error(Base32Error::InvalidByte(6, 'l')))
+		);
+		assert_eq!(
+			"0l43".parse::<Plain>(),
+			Err(Error::Base32Error(Base32Error::InvalidByte(1, 'l')))
+		);
+		assert_eq!(
+			UserId::from_suffix_encoded("0l43"),
+			Err(Error::Base32Error(Base32Error::InvalidByte(1, 'l')))
+		);
+		// the invalid character is reported, rather than its first byte
+		assert_eq!(
+			"user_0\u{e9}3".parse::<UserId>(),
+			Err(Error::Base32Error(Base32Error::InvalidByte(6, '\u{e9}')))
+		);
+		assert_eq!(
+			"user_0l43".parse::<DynamicStrongId<u16>>(),
+			Err(Error::Base32Error(Base32Error::InvalidByte(6, 'l')))
+		);
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn compare_uuid() {
+		strong_uuid!(pub struct UserId(Uuid => "user"));
+
+		let uuid = Uuid::from_u128(0x0189466f_a14b_77c2_9348_55ffc06f2188);
+		let id = UserId::from(uuid);
+		assert_eq!(id, uuid);
+		assert_eq!(uuid, id);
+		assert!(id > Uuid::nil());
+	}
+
+	#[cfg(not(feature = "portable-usize"))]
+	#[test]
+	fn pointer_width() {
+		// IDs encoded on a target with another pointer width
+		let (bits, encoded) = if cfg!(target_pointer_width = "64") {
+			(32, 301u32.encode())
+		} else {
+			(64, 301u64.encode())
+		};
+
+		assert_eq!(usize::decode(&encoded), Err(Error::PointerWidth(bits)));
+		assert_eq!(isize::decode(&encoded), Err(Error::PointerWidth(bits)));
+		assert_eq!(usize::decode(301usize.encode()), Ok(301));
+		assert_eq!(isize::decode((-301isize).encode()), Ok(-301));
+
+		// the portable path, for IDs encoded as 64-bit integers
+		let encoded = u64::from(u32::MAX).encode();
+		assert_eq!(
+			u64::decode(&encoded).map(usize::try_from),
+			Ok(Ok(u32::MAX as usize))
+		);
+		if cfg!(target_pointer_width = "32") {
+			assert!(usize::try_from(u64::decode(u64::MAX.encode()).unwrap()).is_err());
+		}
+	}
+
+	#[cfg(feature = "portable-usize")]
+	#[test]
+	fn portable_usize() {
+		assert_eq!(<usize as Id>::ENCODED_LEN, <u64 as Id>::ENCODED_LEN);
+		assert_eq!(<isize as Id>::ENCODED_LEN, <i64 as Id>::ENCODED_LEN);
+
+		assert_eq!(301usize.encode(), 301u64.encode());
+		assert_eq!((-301isize).encode(), (-301i64).encode());
+		assert_eq!(usize::decode("000000000009d"), Ok(301));
+		assert_eq!(isize::decode(isize::MIN.encode()), Ok(isize::MIN));
+
+		if usize::BITS < 64 {
+			assert_eq!(usize::decode(u64::MAX.encode()), Err(Error::OutOfRange));
+		}
+	}
+
+	#[test]
+	fn signed() {
+		strong_id!(pub struct LegacyId(i64 => "legacy"));
+
+		struct Case(&'static str, i64);
+		let cases = vec![
+			Case("legacy_0000000000000", i64::MIN),
+			Case("legacy_7zzzzzzzzzzzz", -1),
+			Case("legacy_8000000000000", 0),
+			Case("legacy_800000000009d", 301),
+			Case("legacy_fzzzzzzzzzzzz", i64::MAX),
+		];
+
+		for case in &cases {
+			let id = LegacyId::from(case.1);
+			assert_eq!(id.to_string(), case.0);
+			assert_eq!(case.0.parse::<LegacyId>(), Ok(id));
+		}
+
+		// encoded IDs sort in the same order as their values
+		for pair in cases.windows(2) {
+			assert!(pair[0].0 < pair[1].0);
+		}
+
+		strong_id!(pub struct Signed8(i8));
+		assert_eq!(Signed8::from(-128).to_string(), "00");
+		assert_eq!(Signed8::from(127).to_string(), "7z");
+		assert_roundtrip!(Signed8);
+		assert_roundtrip!(LegacyId);
+	}
+
+	#[test]
+	fn help() {
+		strong_id!(pub struct UserId(u16 => "user"));
+		strong_id!(pub struct Plain(u16));
+
+		assert_eq!(
+			UserId::EXPECTED_FORMAT.to_string(),
+			"\"user_\" followed by 4 characters of [0-9a-hjkmnp-tv-z]"
+		);
+		assert_eq!(
+			Plain::EXPECTED_FORMAT.to_string(),
+			"4 characters of [0-9a-hjkmnp-tv-z]"
+		);
+
+		struct Case(&'static str, Option<&'static str>);
+		let cases = vec![
+			Case(
+				"0343",
+				Some("expected \"user_\" followed by the encoded ID"),
+			),
+			Case(
+				"order_0343",
+				Some("expected \"user_\" followed by the encoded ID"),
+			),
+			Case(
+				"user_03433",
+				Some("expected 4 characters of the ID's encoding after the prefix"),
+			),
+			Case(
+				"user_034u",
+				Some("the encoded ID contains a character which is not part of its encoding"),
+			),
+		];
+
+		for case in cases {
+			let error = case.0.parse::<UserId>().unwrap_err();
+			assert_eq!(error.help().as_deref(), case.1, "{}", case.0);
+		}
+
+		// hints don't describe the characters of the encoding, as the error doesn't know it
+		strong_id! {
+			#[strong_id(encoding = Hex)]
+			pub struct HexId(u16 => "hex")
+		}
+		assert_eq!(
+			"hex_0fff0".parse::<HexId>().unwrap_err().help().as_deref(),
+			Some("expected 4 characters of the ID's encoding after the prefix")
+		);
+
+		assert_eq!(Error::UnknownPrefix("user".into()).help(), None);
+	}
+
+	#[test]
+	fn resize() {
+		strong_id!(pub struct OrderId32(u32 => "order"));
+		strong_id!(pub struct OrderId64(u64 => "order"));
+
+		let id: OrderId64 = OrderId32::from(301).widen();
+		assert_eq!(id, OrderId64::from(301));
+
+		let id: OrderId32 = OrderId64::from(301).narrow().unwrap();
+		assert_eq!(id, OrderId32::from(301));
+
+		assert_eq!(
+			OrderId64::from(u64::MAX).narrow::<OrderId32, _>(),
+			Err(Error::OutOfRange)
+		);
+	}
+
+	#[test]
+	fn non_zero() {
+		use core::num::{NonZeroU16, NonZeroU32};
+
+		strong_id!(pub struct UserId(NonZeroU32 => "user"));
+		strong_id!(pub struct Plain(NonZeroU16));
+
+		assert_eq!(
+			core::mem::size_of::<Option<UserId>>(),
+			core::mem::size_of::<UserId>()
+		);
+		assert_eq!(UserId::ENCODED_LEN, 12);
+
+		let id = UserId::from(NonZeroU32::new(301).unwrap());
+		assert_eq!(id.to_string(), "user_000009d");
+		assert_eq!("user_000009d".parse::<UserId>(), Ok(id));
+		assert_eq!("user_0000000".parse::<UserId>(), Err(Error::Zero));
+		assert_eq!(Error::Zero.code(), "ERR_ID_ZERO");
+
+		assert_eq!("0000".parse::<Plain>(), Err(Error::Zero));
+		assert_eq!(
+			"1zzz".parse::<Plain>().unwrap().into_inner().get(),
+			u16::MAX
+		);
+	}
+
+	#[test]
+	fn try_from() {
+		strong_id!(pub struct UserId(u16 => "user"));
+
+		let id = UserId::from(3203);
+		assert_eq!(UserId::try_from("user_0343"), Ok(id));
+		assert_eq!(UserId::try_from(String::from("user_0343")), Ok(id));
+		assert_eq!(UserId::try_from(&b"user_0343"[..]), Ok(id));
+		assert_eq!(
+			UserId::try_from(&b"user_034\xff"[..]),
+			Err(Error::InvalidUtf8)
+		);
+		assert_eq!(
+			UserId::try_from("order_0343"),
+			Err(Error::InvalidPrefix("user".into(), "order".into()))
+		);
+	}
+
+	#[test]
+	fn deref() {
+		strong_id! {
+			#[strong_id(deref)]
+			pub struct UserId(u32 => "user")
+		}
+
+		let id = UserId::from(301);
+		assert_eq!(*id, 301);
+		assert_eq!(id.pow(2), 90601);
+		assert_eq!(id.as_ref(), &301);
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn deref_uuid() {
+		strong_uuid! {
+			#[strong_id(deref)]
+			pub struct UserId(Uuid => "user")
+		}
+
+		fn version(uuid: &Uuid) -> usize {
+			uuid.get_version_num()
+		}
+
+		fn bytes<U: AsRef<Uuid>>(uuid: U) -> [u8; 16] {
+			uuid.as_ref().into_bytes()
+		}
+
+		let uuid = Uuid::from_u128(0x0189466f_a14b_77c2_9348_55ffc06f2188);
+		let id = UserId::from(uuid);
+		assert_eq!(version(&id), 7);
+		assert_eq!(bytes(id), uuid.into_bytes());
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn uuid_bytes() {
+		strong_uuid!(pub struct UserId(Uuid => "user"));
+
+		let uuid = Uuid::from_u128(0x0189466f_a14b_77c2_9348_55ffc06f2188);
+		let id = UserId::from(uuid);
+
+		assert_eq!(id.into_inner(), uuid);
+		assert_eq!(id.into_parts(), (Some("user".into()), uuid));
+		assert_eq!(id.as_bytes(), uuid.as_bytes());
+		assert_eq!(id.to_bytes(), *uuid.as_bytes());
+		assert_eq!(<[u8; 16]>::from(id), *uuid.as_bytes());
+	}
+
+	#[cfg(feature = "uuid-v7")]
+	#[test]
+	fn new_v7_at() {
+		use std::time::{Duration, UNIX_EPOCH};
+
+		strong_uuid!(pub struct UserId(Uuid => "user"));
+
+		let id = UserId::new_v7_at(UNIX_EPOCH + Duration::from_millis(1_689_338_443_003));
+		assert_eq!(
+			id.partition_key(Granularity::Hour).as_deref(),
+			Some("2023-07-14T12")
+		);
+		assert_eq!(id.id().get_version_num(), 7);
+		assert_eq!(
+			id.id().get_timestamp().map(|ts| ts.to_unix()),
+			Some((1_689_338_443, 3_000_000))
+		);
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn partition_key() {
+		strong_uuid!(pub struct UserId(Uuid => "user"));
+
+		let id = UserId::from(Uuid::from_u128(0x0189546a_f4fb_7c2a_9e8b_6fe9f1d7fe21));
+		assert_eq!(
+			id.partition_key(Granularity::Day).as_deref(),
+			Some("2023-07-14")
+		);
+		assert_eq!(id.partition_number(Granularity::Month), Some(202307));
+		assert_eq!(
+			UserId::from(Uuid::nil()).partition_key(Granularity::Day),
+			None
+		);
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn uuid_fmt() {
+		strong_uuid!(pub struct UserId(Uuid => "user"));
+
+		let uuid = Uuid::from_u128(0x0189466f_a14b_77c2_9348_55ffc06f2188);
+		let id = UserId::from(uuid);
+
+		assert_eq!(UserId::from(uuid.hyphenated()), id);
+		assert_eq!(UserId::from(uuid.simple()), id);
+		assert_eq!(UserId::from(uuid.urn()), id);
+		assert_eq!(UserId::from(uuid.braced()), id);
+		assert_eq!(
+			id.to_hyphenated_string(),
+			"0189466f-a14b-77c2-9348-55ffc06f2188"
+		);
+	}
+
+	#[test]
+	fn crate_path() {
+		mod prelude {
+			pub use crate as reexported;
+		}
+
+		strong_id! {
+			#[strong_id(crate = "prelude::reexported")]
+			pub struct PrefixU32(u32 => "prefix")
+		}
+
+		assert_eq!(PrefixU32::from(301).prefix(), Some("prefix"));
+		assert_eq!(PrefixU32::from(301).into_inner(), 301);
+	}
+
+	#[test]
+	fn cast() {
+		strong_id!(pub struct DraftId(u32 => "draft"));
+		strong_id!(pub struct DocumentId(u32 => "document"));
+
+		let draft = DraftId::from(301);
+		assert_eq!(DocumentId::from_id(draft).to_string(), "document_000009d");
+
+		let document: DocumentId = draft.cast();
+		assert_eq!(document, DocumentId::from(301));
+	}
+
+	#[test]
+	fn erased() {
+		strong_id!(pub struct PrefixU32(u32 => "prefix"));
+		strong_id!(pub struct NoPrefixU16(u16));
+
+		let ids: Vec<Box<dyn ErasedStrongId>> = vec![
+			Box::new(PrefixU32::from(301)),
+			Box::new(NoPrefixU16::from(301)),
+			Box::new(DynamicStrongId::new("dyn", 157u8).unwrap()),
+		];
+
+		let erased = ids
+			.iter()
+			.map(|id| (id.erased_prefix(), id.encoded(), id.to_string()))
+			.collect::<Vec<_>>();
+
+		assert_eq!(
+			erased,
+			vec![
+				(
+					Some("prefix"),
+					"000009d".to_string(),
+					"prefix_000009d".to_string()
+				),
+				(None, "009d".to_string(), "009d".to_string()),
+				(Some("dyn"), "4x".to_string(), "dyn_4x".to_string()),
+			]
+		);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn u32_prefix_serde_struct() {
+		strong_id! {
+			#[strong_id(serde = "struct")]
+			pub struct PrefixU32(u32 => "prefix")
+		}
+		strong_id! {
+			#[strong_id(serde = "struct")]
+			pub struct NoPrefixU32(u32)
+		}
+
+		let value: PrefixU32 =
+			serde_json::from_str(r#"{"prefix":"prefix","suffix":"000009d"}"#).unwrap();
+		assert_eq!(*value.id(), 301);
+
+		let value: PrefixU32 = serde_json::from_str("\"prefix_000009d\"").unwrap();
+		assert_eq!(*value.id(), 301);
+
+		let value = serde_json::to_string(&value).unwrap();
+		assert_eq!(r#"{"prefix":"prefix","suffix":"000009d"}"#, value);
+
+		assert!(
+			serde_json::from_str::<PrefixU32>(r#"{"prefix":"dyn","suffix":"000009d"}"#).is_err()
+		);
+
+		let value: NoPrefixU32 =
+			serde_json::from_str(r#"{"prefix":null,"suffix":"000009d"}"#).unwrap();
+		assert_eq!(*value.id(), 301);
+
+		let value = serde_json::to_string(&value).unwrap();
+		assert_eq!(r#"{"prefix":null,"suffix":"000009d"}"#, value);
+	}
+
+	#[test]
+	fn deref_impls() {
+		use std::{rc::Rc, sync::Arc};
+
+		fn describe<I: StrongId<u32>>(id: I) -> (Option<String>, u32, String) {
+			(id.prefix().map(String::from), *id.id(), id.to_string())
+		}
+
+		let id = DynamicStrongId::new("prefix", 301u32).unwrap();
+		let expected = (
+			Some("prefix".to_string()),
+			301,
+			"prefix_000009d".to_string(),
+		);
+
+		assert_eq!(describe(&id), expected);
+		assert_eq!(describe(Box::new(id.clone())), expected);
+		assert_eq!(describe(Rc::new(id.clone())), expected);
+		assert_eq!(describe(Arc::new(id)), expected);
+	}
+
+	#[test]
+	fn enum_dispatch() {
+		strong_id!(pub struct PrefixU32(u32 => "prefix"));
+		strong_id!(pub struct OtherU16(u16 => "other"));
+		strong_id!(pub struct NoPrefixU8(u8));
+
+		strong_id_enum! {
+			#[derive(Debug, PartialEq)]
+			pub enum AnyId {
+				Prefix(PrefixU32),
+				Other(OtherU16),
+				NoPrefix(NoPrefixU8),
+			}
+		}
+
+		struct Case(&'static str, AnyId);
+		let cases = vec![
+			Case("prefix_000009d", AnyId::Prefix(PrefixU32::from(301))),
+			Case("other_009d", AnyId::Other(OtherU16::from(301))),
+			Case("4x", AnyId::NoPrefix(NoPrefixU8::from(157))),
+		];
+
+		for case in cases {
+			let parsed = case.0.parse::<AnyId>().unwrap();
+			assert_eq!(parsed, case.1);
+			assert_eq!(parsed.to_string(), case.0);
+		}
+
+		assert_eq!(
+			"dyn_009d".parse::<AnyId>().unwrap_err(),
+			Error::UnknownPrefix("dyn".into())
+		);
+		assert_eq!(
+			"other_000009d".parse::<AnyId>().unwrap_err(),
+			Error::InvalidLength(4, 7)
+		);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn enum_dispatch_serde() {
+		strong_id!(pub struct PrefixU32(u32 => "prefix"));
+		strong_id!(pub struct OtherU16(u16 => "other"));
+
+		strong_id_enum! {
+			#[derive(Debug, PartialEq)]
+			pub enum AnyId {
+				Prefix(PrefixU32),
+				Other(OtherU16),
+			}
+		}
+
+		let value: AnyId = serde_json::from_str("\"other_009d\"").unwrap();
+		assert_eq!(value, AnyId::Other(OtherU16::from(301)));
+
+		let value = serde_json::to_string(&value).unwrap();
+		assert_eq!("\"other_009d\"", value);
+	}
 }
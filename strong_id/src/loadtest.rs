@@ -0,0 +1,209 @@
+//! Random IDs for synthesizing load
+//!
+//! [`LoadTest`] generates an endless stream of plausible IDs for a prefix and backing type,
+//! optionally paced to a target rate and reproducible with a fixed seed.
+//!
+//! ```rust
+//! use strong_id::{loadtest::LoadTest, StrongId};
+//!
+//! let ids = LoadTest::<u32>::new("user").unwrap().seed(42).take(3).collect::<Vec<_>>();
+//! assert!(ids.iter().all(|id| id.prefix() == Some("user")));
+//!
+//! // the same seed generates the same IDs
+//! let again = LoadTest::<u32>::new("user").unwrap().seed(42).take(3).collect::<Vec<_>>();
+//! assert_eq!(ids, again);
+//! ```
+
+use crate::{dynamic::map_prefix, rng::Rng, DynamicStrongId, Error, Id, Prefix};
+use std::{
+	collections::hash_map::RandomState,
+	hash::{BuildHasher, Hasher},
+	thread,
+	time::{Duration, Instant},
+};
+
+/// Types which can be sampled by [`LoadTest`]
+///
+/// The default implementation fills the bytes of the type with random data and skips values which
+/// are invalid, e.g. zero for non-zero integers. Implement this trait for custom backing types, or
+/// override [`Sample::sample`] to shape the random data into realistic values.
+pub trait Sample: Id + Sized {
+	/// Create a value from random bytes, or `None` to draw new bytes
+	fn sample(random: Self::Bytes) -> Option<Self> {
+		Self::from_bytes(random).ok()
+	}
+}
+
+macro_rules! impl_sample {
+	($($t:ty),*) => {
+		$(impl Sample for $t {})*
+	};
+}
+
+impl_sample!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_sample!(
+	core::num::NonZeroU8,
+	core::num::NonZeroU16,
+	core::num::NonZeroU32,
+	core::num::NonZeroU64,
+	core::num::NonZeroU128,
+	core::num::NonZeroUsize
+);
+
+/// Random UUIDs carry the version and variant of v4 UUIDs
+#[cfg(feature = "uuid")]
+impl Sample for uuid::Uuid {
+	fn sample(random: Self::Bytes) -> Option<Self> {
+		Some(uuid::Builder::from_random_bytes(random).into_uuid())
+	}
+}
+
+/// An iterator of random IDs with a prefix, see the [module docs](self)
+pub struct LoadTest<'p, T: Sample> {
+	prefix: Option<Prefix<'p>>,
+	rng: Rng,
+	interval: Option<Duration>,
+	started: Option<Instant>,
+	generated: u32,
+	_marker: core::marker::PhantomData<T>,
+}
+
+impl<'p, T: Sample> LoadTest<'p, T> {
+	/// Generate IDs with the given prefix
+	pub fn new<I: Into<Prefix<'p>>>(prefix: I) -> Result<Self, Error> {
+		Ok(Self::with_prefix(Some(map_prefix(prefix)?)))
+	}
+
+	/// Generate IDs without a prefix
+	pub fn new_plain() -> Self {
+		Self::with_prefix(None)
+	}
+
+	fn with_prefix(prefix: Option<Prefix<'p>>) -> Self {
+		let mut hasher = RandomState::new().build_hasher();
+		hasher.write_u32(0);
+
+		Self {
+			prefix,
+			rng: Rng::new(hasher.finish()),
+			interval: None,
+			started: None,
+			generated: 0,
+			_marker: core::marker::PhantomData,
+		}
+	}
+
+	/// Use a fixed seed, so that the same IDs are generated on every run
+	pub fn seed(mut self, seed: u64) -> Self {
+		self.rng = Rng::new(seed);
+		self
+	}
+
+	/// Generate at most `per_second` IDs per second, blocking the calling thread between IDs
+	///
+	/// The pace is kept relative to the first generated ID, so slow consumers catch up rather than
+	/// drifting below the target rate. Panics if `per_second` is not positive and finite.
+	pub fn rate(mut self, per_second: f64) -> Self {
+		assert!(
+			per_second.is_finite() && per_second > 0.0,
+			"rate must be positive and finite, got {per_second}"
+		);
+		self.interval = Some(Duration::from_secs_f64(1.0 / per_second));
+		self
+	}
+
+	fn wait(&mut self) {
+		let interval = match self.interval {
+			Some(interval) => interval,
+			None => return,
+		};
+
+		let now = Instant::now();
+		let started = *self.started.get_or_insert(now);
+		let due = started + interval * self.generated;
+		if due > now {
+			thread::sleep(due - now);
+		}
+		self.generated = self.generated.saturating_add(1);
+	}
+
+	fn sample(&mut self) -> T {
+		loop {
+			let mut random = T::Bytes::default();
+			self.rng.fill(random.as_mut());
+			if let Some(value) = T::sample(random) {
+				return value;
+			}
+		}
+	}
+}
+
+impl<'p, T: Sample> Iterator for LoadTest<'p, T> {
+	type Item = DynamicStrongId<'p, T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.wait();
+		let value = self.sample();
+		Some(match &self.prefix {
+			Some(prefix) => match DynamicStrongId::new(prefix.clone(), value) {
+				Ok(id) => id,
+				Err(_) => unreachable!("the prefix is validated in LoadTest::new"),
+			},
+			None => DynamicStrongId::new_plain(value),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::LoadTest;
+	use crate::{Error, StrongId};
+	use core::num::NonZeroU8;
+	use std::time::{Duration, Instant};
+
+	#[test]
+	fn seeded() {
+		let first = LoadTest::<u64>::new("user").unwrap().seed(7).take(16);
+		let second = LoadTest::<u64>::new("user").unwrap().seed(7).take(16);
+		assert!(first.eq(second));
+
+		let plain = LoadTest::<u64>::new_plain().seed(7).next().unwrap();
+		assert_eq!(plain.prefix(), None);
+	}
+
+	#[test]
+	fn valid_values() {
+		// zero is drawn often enough in 4096 bytes to exercise the retry
+		assert_eq!(
+			LoadTest::<NonZeroU8>::new_plain()
+				.seed(1)
+				.take(4096)
+				.count(),
+			4096
+		);
+	}
+
+	#[test]
+	fn invalid_prefix() {
+		assert!(matches!(
+			LoadTest::<u32>::new("User"),
+			Err(Error::IncorrectPrefixCharacter(_))
+		));
+	}
+
+	#[test]
+	fn rate() {
+		let started = Instant::now();
+		assert_eq!(LoadTest::<u32>::new_plain().rate(200.0).take(5).count(), 5);
+		// the first ID is immediate, the following four are paced 5ms apart
+		assert!(started.elapsed() >= Duration::from_millis(20));
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn uuid() {
+		for id in LoadTest::<uuid::Uuid>::new("user").unwrap().take(16) {
+			assert_eq!(id.into_inner().get_version_num(), 4);
+		}
+	}
+}
@@ -0,0 +1,190 @@
+use uuid::Uuid;
+
+/// The size of the time buckets of partition keys, see [`StrongUuid::partition_key`](crate::StrongUuid::partition_key)
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Granularity {
+	/// e.g. `2024`
+	Year,
+	/// e.g. `2024-06`
+	Month,
+	/// e.g. `2024-06-01`
+	Day,
+	/// e.g. `2024-06-01T13`
+	Hour,
+}
+
+/// A UTC date and hour
+struct Civil {
+	year: u32,
+	month: u32,
+	day: u32,
+	hour: u32,
+}
+
+/// The 100ns intervals between the start of the gregorian calendar and the unix epoch
+const GREGORIAN_TICKS_BEFORE_EPOCH: u64 = 0x01B2_1DD2_1381_4000;
+
+/// The 100ns intervals since the start of the gregorian calendar of v1 and v6 UUIDs
+fn gregorian_ticks(uuid: &Uuid) -> Option<u64> {
+	let (d1, d2, d3, _d4) = uuid.as_fields();
+	let (d1, d2, d3) = (u64::from(d1), u64::from(d2), u64::from(d3 & 0x0FFF));
+
+	match uuid.get_version_num() {
+		1 => Some(d3 << 48 | d2 << 32 | d1),
+		6 => Some(d1 << 28 | d2 << 12 | d3),
+		_ => None,
+	}
+}
+
+impl Civil {
+	/// The UTC date and hour of the timestamp of a time-based UUID
+	///
+	/// Returns `None` for v1 and v6 UUIDs from before the unix epoch, whose unix timestamps wrap
+	/// around.
+	fn of(uuid: &Uuid) -> Option<Self> {
+		if matches!(gregorian_ticks(uuid), Some(ticks) if ticks < GREGORIAN_TICKS_BEFORE_EPOCH) {
+			return None;
+		}
+
+		let (secs, _nanos) = uuid.get_timestamp()?.to_unix();
+
+		let days = secs / 86_400;
+		let hour = (secs % 86_400 / 3_600) as u32;
+
+		// http://howardhinnant.github.io/date_algorithms.html#civil_from_days, for days since the
+		// unix epoch, which are never negative as earlier timestamps are rejected
+		let z = days + 719_468;
+		let era = z / 146_097;
+		let doe = z - era * 146_097;
+		let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+		let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+		let mp = (5 * doy + 2) / 153;
+		let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+		let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+		let year = (yoe + era * 400) as u32 + u32::from(month <= 2);
+
+		Some(Self {
+			year,
+			month,
+			day,
+			hour,
+		})
+	}
+}
+
+impl Granularity {
+	/// The partition key of the timestamp of a time-based UUID, e.g. `2024-06-01`
+	///
+	/// Returns `None` if the UUID is not time-based, or is a v1 or v6 UUID from before 1970.
+	pub fn key(self, uuid: &Uuid) -> Option<String> {
+		let Civil {
+			year,
+			month,
+			day,
+			hour,
+		} = Civil::of(uuid)?;
+
+		Some(match self {
+			Self::Year => format!("{year:04}"),
+			Self::Month => format!("{year:04}-{month:02}"),
+			Self::Day => format!("{year:04}-{month:02}-{day:02}"),
+			Self::Hour => format!("{year:04}-{month:02}-{day:02}T{hour:02}"),
+		})
+	}
+
+	/// The partition key of the timestamp of a time-based UUID as a number, e.g. `20240601`
+	///
+	/// Returns `None` if the UUID is not time-based, is a v1 or v6 UUID from before 1970, or if the
+	/// number doesn't fit in a `u32`, e.g.
+	/// for [`Hour`](Self::Hour) keys past the year 4294.
+	pub fn number(self, uuid: &Uuid) -> Option<u32> {
+		let Civil {
+			year,
+			month,
+			day,
+			hour,
+		} = Civil::of(uuid)?;
+
+		let append = |number: u32, part: u32| number.checked_mul(100)?.checked_add(part);
+
+		match self {
+			Self::Year => Some(year),
+			Self::Month => append(year, month),
+			Self::Day => append(append(year, month)?, day),
+			Self::Hour => append(append(append(year, month)?, day)?, hour),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Granularity;
+	use uuid::Uuid;
+
+	#[test]
+	fn partition() {
+		// 2023-07-14T12:40:43.003Z
+		let uuid = Uuid::from_u128(0x0189546a_f4fb_7c2a_9e8b_6fe9f1d7fe21);
+
+		// the largest v7 timestamp, 10889-08-02T05:31:50.655Z
+		let future = Uuid::from_u128((0xFFFF_FFFF_FFFF << 80) | (0x7 << 76) | (0x8 << 60));
+		// a v1 timestamp at the start of the gregorian calendar
+		let gregorian = Uuid::from_fields(0, 0, 0x1000, &[0x80, 0, 0, 0, 0, 0, 0, 0]);
+
+		struct Case(Granularity, Uuid, Option<&'static str>, Option<u32>);
+		let cases = vec![
+			Case(Granularity::Year, uuid, Some("2023"), Some(2023)),
+			Case(Granularity::Month, uuid, Some("2023-07"), Some(202307)),
+			Case(Granularity::Day, uuid, Some("2023-07-14"), Some(20230714)),
+			Case(
+				Granularity::Hour,
+				uuid,
+				Some("2023-07-14T12"),
+				Some(2023071412),
+			),
+			Case(Granularity::Year, future, Some("10889"), Some(10889)),
+			Case(
+				Granularity::Day,
+				future,
+				Some("10889-08-02"),
+				Some(108890802),
+			),
+			Case(Granularity::Hour, future, Some("10889-08-02T05"), None),
+			Case(Granularity::Year, gregorian, None, None),
+			Case(Granularity::Hour, gregorian, None, None),
+		];
+
+		for case in cases {
+			assert_eq!(
+				case.0.key(&case.1).as_deref(),
+				case.2,
+				"{:?} {}",
+				case.0,
+				case.1
+			);
+			assert_eq!(case.0.number(&case.1), case.3, "{:?} {}", case.0, case.1);
+		}
+
+		// the first v1 timestamp after the unix epoch
+		let epoch = Uuid::from_fields(0x1381_4000, 0x1DD2, 0x11B2, &[0x80, 0, 0, 0, 0, 0, 0, 0]);
+		assert_eq!(Granularity::Day.key(&epoch).as_deref(), Some("1970-01-01"));
+
+		// leap days and year boundaries
+		let at = |millis: u128| Uuid::from_u128((millis << 80) | (0x7 << 76) | (0x8 << 60));
+		assert_eq!(
+			Granularity::Hour.key(&at(951_782_400_000)).as_deref(),
+			Some("2000-02-29T00")
+		);
+		assert_eq!(
+			Granularity::Hour.key(&at(1_704_067_199_999)).as_deref(),
+			Some("2023-12-31T23")
+		);
+		assert_eq!(Granularity::Day.key(&at(0)).as_deref(), Some("1970-01-01"));
+
+		assert_eq!(Granularity::Day.key(&Uuid::nil()), None);
+		assert_eq!(
+			Granularity::Day.number(&Uuid::from_u128((0x4 << 76) | (0x8 << 60))),
+			None
+		);
+	}
+}
@@ -0,0 +1,188 @@
+use crate::{DynamicStrongId, DynamicStrongIdBuf, Error, Prefix, StrongId};
+use core::convert::TryFrom;
+use uuid::Uuid;
+
+/// The maximum length of a prefix, and the capacity of [`RawTypeId::prefix`]
+const PREFIX_CAPACITY: usize = 63;
+
+/// A UUID-backed ID with a fixed, C-compatible layout, for placing IDs in shared-memory queues and
+/// memory-mapped files shared with C or C++ components
+///
+/// The layout is equivalent to the C struct:
+///
+/// ```c
+/// struct raw_type_id {
+///     uint8_t prefix_len;
+///     uint8_t prefix[63];
+///     uint8_t suffix[16];
+/// };
+/// ```
+///
+/// `prefix` holds `prefix_len` bytes of the prefix, followed by zeroes, and `suffix` holds the bytes
+/// of the UUID in big-endian order. The struct has no padding, so it is 80 bytes with an alignment
+/// of 1.
+///
+/// Raw IDs may come from untrusted memory, so converting them back into IDs validates them like
+/// parsing an ID.
+///
+/// ```rust
+/// use strong_id::{DynamicStrongId, RawTypeId};
+///
+/// let id = DynamicStrongId::from_u128("user", 3203).unwrap();
+///
+/// let raw = RawTypeId::try_from(&id).unwrap();
+/// assert_eq!(raw.prefix_len, 4);
+/// assert_eq!(raw.to_bytes().len(), 80);
+///
+/// assert_eq!(DynamicStrongId::try_from(raw), Ok(id));
+/// ```
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct RawTypeId {
+	/// The length of the prefix, `0` for IDs without a prefix
+	pub prefix_len: u8,
+	/// The prefix, padded with zeroes
+	pub prefix: [u8; PREFIX_CAPACITY],
+	/// The UUID, in big-endian order
+	pub suffix: [u8; 16],
+}
+
+impl RawTypeId {
+	/// The size of a raw ID in bytes
+	pub const SIZE: usize = 1 + PREFIX_CAPACITY + 16;
+
+	/// Create a raw ID from any UUID-backed ID
+	///
+	/// Fails with [`Error::PrefixTooLong`] if the prefix doesn't fit, which can only happen for
+	/// prefixes which were never validated.
+	pub fn from_id<S: StrongId<Uuid>>(id: &S) -> Result<Self, Error> {
+		let prefix_str = id.prefix().unwrap_or_default();
+		if prefix_str.len() > PREFIX_CAPACITY {
+			return Err(Error::PrefixTooLong(prefix_str.len()));
+		}
+
+		let mut prefix = [0u8; PREFIX_CAPACITY];
+		prefix[..prefix_str.len()].copy_from_slice(prefix_str.as_bytes());
+
+		Ok(Self {
+			prefix_len: prefix_str.len() as u8,
+			prefix,
+			suffix: *id.id().as_bytes(),
+		})
+	}
+
+	/// The UUID of the ID
+	pub fn uuid(&self) -> Uuid {
+		Uuid::from_bytes(self.suffix)
+	}
+
+	/// The raw ID as bytes, in the layout of the struct
+	pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+		let mut bytes = [0u8; Self::SIZE];
+		bytes[0] = self.prefix_len;
+		bytes[1..=PREFIX_CAPACITY].copy_from_slice(&self.prefix);
+		bytes[PREFIX_CAPACITY + 1..].copy_from_slice(&self.suffix);
+		bytes
+	}
+
+	/// Read a raw ID from bytes in the layout of the struct
+	///
+	/// The bytes are not validated until the raw ID is converted into an ID.
+	pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Self {
+		let mut prefix = [0u8; PREFIX_CAPACITY];
+		prefix.copy_from_slice(&bytes[1..=PREFIX_CAPACITY]);
+		let mut suffix = [0u8; 16];
+		suffix.copy_from_slice(&bytes[PREFIX_CAPACITY + 1..]);
+
+		Self {
+			prefix_len: bytes[0],
+			prefix,
+			suffix,
+		}
+	}
+}
+
+impl<'p> TryFrom<&DynamicStrongId<'p, Uuid>> for RawTypeId {
+	type Error = Error;
+
+	fn try_from(value: &DynamicStrongId<'p, Uuid>) -> Result<Self, Self::Error> {
+		Self::from_id(value)
+	}
+}
+
+impl TryFrom<RawTypeId> for DynamicStrongIdBuf<Uuid> {
+	type Error = Error;
+
+	fn try_from(value: RawTypeId) -> Result<Self, Self::Error> {
+		let len = value.prefix_len as usize;
+		if len > PREFIX_CAPACITY {
+			return Err(Error::PrefixTooLong(len));
+		}
+		if len == 0 {
+			return Ok(DynamicStrongId::new_plain(value.uuid()));
+		}
+
+		let prefix = core::str::from_utf8(&value.prefix[..len]).map_err(|_| Error::InvalidUtf8)?;
+		DynamicStrongId::new(Prefix::new(prefix.to_string())?, value.uuid())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::RawTypeId;
+	use crate::{strong_uuid, DynamicStrongId, DynamicStrongIdBuf, Error, StrongUuid};
+	use core::convert::TryFrom;
+	use uuid::Uuid;
+
+	#[test]
+	fn layout() {
+		assert_eq!(core::mem::size_of::<RawTypeId>(), RawTypeId::SIZE);
+		assert_eq!(core::mem::align_of::<RawTypeId>(), 1);
+	}
+
+	#[test]
+	fn roundtrip() {
+		let id = DynamicStrongId::from_u128("user", 3203).unwrap();
+		let raw = RawTypeId::try_from(&id).unwrap();
+		assert_eq!(&raw.prefix[..5], b"user\0");
+		assert_eq!(raw.uuid(), Uuid::from_u128(3203));
+
+		let bytes = raw.to_bytes();
+		assert_eq!(RawTypeId::from_bytes(&bytes), raw);
+		assert_eq!(DynamicStrongIdBuf::try_from(raw), Ok(id));
+
+		let plain = DynamicStrongId::from_u128_plain(3203);
+		let raw = RawTypeId::try_from(&plain).unwrap();
+		assert_eq!(raw.prefix_len, 0);
+		assert_eq!(DynamicStrongIdBuf::try_from(raw), Ok(plain));
+
+		strong_uuid!(pub struct UserId(Uuid => "user"));
+		let raw = RawTypeId::from_id(&UserId::from_u128(3203)).unwrap();
+		assert_eq!(
+			DynamicStrongIdBuf::try_from(raw).unwrap().to_string(),
+			"user_00000000000000000000000343"
+		);
+	}
+
+	#[test]
+	fn invalid() {
+		let mut raw =
+			RawTypeId::try_from(&DynamicStrongId::from_u128("user", 3203).unwrap()).unwrap();
+
+		raw.prefix_len = 64;
+		assert_eq!(
+			DynamicStrongIdBuf::try_from(raw),
+			Err(Error::PrefixTooLong(64))
+		);
+
+		raw.prefix_len = 5;
+		assert_eq!(
+			DynamicStrongIdBuf::try_from(raw),
+			Err(Error::IncorrectPrefixCharacter('\0'))
+		);
+
+		raw.prefix_len = 4;
+		raw.prefix[0] = 0xff;
+		assert_eq!(DynamicStrongIdBuf::try_from(raw), Err(Error::InvalidUtf8));
+	}
+}
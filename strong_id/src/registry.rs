@@ -0,0 +1,419 @@
+use crate::{ErasedStrongId, Error, Prefix, StaticPrefix};
+use core::{
+	any::Any,
+	fmt::{Debug, Display, Formatter},
+	str::FromStr,
+};
+use std::{borrow::Cow, collections::HashMap};
+
+trait AnyStrongId: ErasedStrongId + Send + Sync {
+	fn as_any(&self) -> &dyn Any;
+	fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<S: ErasedStrongId + Any + Send + Sync> AnyStrongId for S {
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn into_any(self: Box<Self>) -> Box<dyn Any> {
+		self
+	}
+}
+
+/// A StrongId of any type registered with a [`Registry`]
+pub struct AnyId {
+	type_name: &'static str,
+	inner: Box<dyn AnyStrongId>,
+}
+
+impl AnyId {
+	/// The name of the type the ID was parsed into
+	pub fn type_name(&self) -> &'static str {
+		self.type_name
+	}
+
+	/// Whether the ID is of type `S`
+	pub fn is<S: Any>(&self) -> bool {
+		(*self.inner).as_any().is::<S>()
+	}
+
+	/// Borrow the ID as type `S`, if it is one
+	pub fn downcast_ref<S: Any>(&self) -> Option<&S> {
+		(*self.inner).as_any().downcast_ref::<S>()
+	}
+
+	/// Convert into type `S`, or return the ID unchanged if it is not one
+	pub fn downcast<S: Any>(self) -> Result<S, Self> {
+		if !self.is::<S>() {
+			return Err(self);
+		}
+
+		match self.inner.into_any().downcast::<S>() {
+			Ok(id) => Ok(*id),
+			Err(_) => unreachable!("type was checked"),
+		}
+	}
+}
+
+impl ErasedStrongId for AnyId {
+	fn erased_prefix(&self) -> Option<&str> {
+		self.inner.erased_prefix()
+	}
+
+	fn encoded(&self) -> String {
+		self.inner.encoded()
+	}
+}
+
+impl Display for AnyId {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		Display::fmt(&self.inner, f)
+	}
+}
+
+impl Debug for AnyId {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("AnyId")
+			.field("type_name", &self.type_name)
+			.field("id", &self.inner.to_string())
+			.finish()
+	}
+}
+
+/// Identifies an ID type registered with a [`Registry`]
+///
+/// The tag of a registered type is its name without the module path, e.g. `UserId`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct TypeTag(Cow<'static, str>);
+
+impl TypeTag {
+	/// Create a tag from a type name
+	pub fn new<I: Into<Cow<'static, str>>>(value: I) -> Self {
+		Self(value.into())
+	}
+
+	/// The tag as a `&str`
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+
+	fn of<S>() -> Self {
+		let type_name = core::any::type_name::<S>();
+		let name = type_name.split('<').next().unwrap_or(type_name);
+		Self::new(&type_name[name.rfind("::").map_or(0, |index| index + 2)..])
+	}
+}
+
+impl Display for TypeTag {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for TypeTag {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		serializer.serialize_str(&self.0)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for TypeTag {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		Ok(Self::new(String::deserialize(deserializer)?))
+	}
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+	type_name: &'static str,
+	tag: TypeTag,
+	prefix: Option<&'static str>,
+	backing: &'static str,
+	parse: fn(&str) -> Result<AnyId, Error>,
+}
+
+/// An ID type registered with a [`Registry`], see [`Registry::declared_prefixes`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct DeclaredPrefix {
+	/// The prefix of the type, if any
+	pub prefix: Option<&'static str>,
+	/// The full name of the type, e.g. `my_app::ids::UserId`
+	pub type_name: &'static str,
+	/// The full name of the inner value of the type, e.g. `u64` or `uuid::Uuid`
+	pub backing: &'static str,
+}
+
+fn parse<S>(value: &str) -> Result<AnyId, Error>
+where
+	S: StaticPrefix + ErasedStrongId + FromStr<Err = Error> + Send + Sync + 'static,
+{
+	Ok(AnyId {
+		type_name: core::any::type_name::<S>(),
+		inner: Box::new(value.parse::<S>()?),
+	})
+}
+
+/// Parse IDs of any registered type by dispatching on their prefix
+///
+/// ```rust
+/// use strong_id::{strong_id, Registry};
+///
+/// strong_id!(pub struct UserId(u16 => "user"));
+/// strong_id!(pub struct OrderId(u32 => "order"));
+///
+/// let mut registry = Registry::new();
+/// registry.register::<UserId>().register::<OrderId>();
+///
+/// let id = registry.parse("order_000009d").unwrap();
+/// assert_eq!(id.downcast_ref::<OrderId>(), Some(&OrderId::from(301)));
+/// ```
+#[derive(Debug, Default)]
+pub struct Registry {
+	entries: HashMap<Option<&'static str>, Entry>,
+}
+
+impl Registry {
+	/// Create an empty registry
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Register an ID type
+	///
+	/// # Panics
+	///
+	/// Panics if a type with the same prefix has already been registered.
+	pub fn register<S>(&mut self) -> &mut Self
+	where
+		S: StaticPrefix + ErasedStrongId + FromStr<Err = Error> + Send + Sync + 'static,
+	{
+		let entry = Entry {
+			type_name: core::any::type_name::<S>(),
+			tag: TypeTag::of::<S>(),
+			prefix: S::PREFIX,
+			backing: core::any::type_name::<S::Inner>(),
+			parse: parse::<S>,
+		};
+
+		if let Some(existing) = self.entries.insert(S::PREFIX, entry) {
+			panic!(
+				"prefix {:?} is already registered to {}",
+				S::PREFIX,
+				existing.type_name
+			);
+		}
+
+		self
+	}
+
+	/// Create a registry from a prefix to type routing table, e.g. loaded from configuration
+	///
+	/// Each entry is validated against the types registered with `types`: the tag must refer to a
+	/// registered type, and the prefix must be that type's prefix. Only the types in the table are
+	/// registered in the new registry.
+	///
+	/// ```rust
+	/// use std::collections::HashMap;
+	/// use strong_id::{strong_id, Prefix, Registry, TypeTag};
+	///
+	/// strong_id!(pub struct UserId(u16 => "user"));
+	/// strong_id!(pub struct OrderId(u32 => "order"));
+	///
+	/// let mut types = Registry::new();
+	/// types.register::<UserId>().register::<OrderId>();
+	///
+	/// let mut config = HashMap::new();
+	/// config.insert(Prefix::new("user").unwrap(), TypeTag::new("UserId"));
+	///
+	/// let registry = Registry::from_config(config, &types).unwrap();
+	/// assert!(registry.parse("user_009d").is_ok());
+	/// assert!(registry.parse("order_000009d").is_err());
+	/// ```
+	pub fn from_config(
+		config: HashMap<Prefix<'static>, TypeTag>,
+		types: &Registry,
+	) -> Result<Self, Error> {
+		let mut registry = Self::new();
+
+		for (prefix, tag) in config {
+			let entry = types
+				.entries
+				.values()
+				.find(|entry| entry.tag == tag)
+				.ok_or_else(|| Error::UnknownTypeTag(tag.to_string()))?;
+
+			if entry.prefix != Some(prefix.as_str()) {
+				return Err(Error::InvalidPrefix(
+					entry.prefix.unwrap_or_default().to_string(),
+					prefix.to_string(),
+				));
+			}
+
+			registry.entries.insert(entry.prefix, entry.clone());
+		}
+
+		Ok(registry)
+	}
+
+	/// The registered types, sorted by their prefix
+	///
+	/// This is the complete set of IDs a service parses, e.g. for logging it at startup or
+	/// comparing it with the tables of a database schema to detect drift.
+	///
+	/// ```rust
+	/// use strong_id::{strong_id, DeclaredPrefix, Registry};
+	///
+	/// strong_id!(pub struct UserId(u16 => "user"));
+	/// strong_id!(pub struct OrderId(u32 => "order"));
+	///
+	/// let mut registry = Registry::new();
+	/// registry.register::<UserId>().register::<OrderId>();
+	///
+	/// let declared = registry.declared_prefixes().collect::<Vec<_>>();
+	/// assert_eq!(declared[0].prefix, Some("order"));
+	/// assert_eq!(declared[0].backing, "u32");
+	/// assert!(declared[1].type_name.ends_with("UserId"));
+	/// ```
+	pub fn declared_prefixes(&self) -> impl Iterator<Item = DeclaredPrefix> {
+		let mut declared = self
+			.entries
+			.values()
+			.map(|entry| DeclaredPrefix {
+				prefix: entry.prefix,
+				type_name: entry.type_name,
+				backing: entry.backing,
+			})
+			.collect::<Vec<_>>();
+		declared.sort_by_key(|declared| declared.prefix);
+		declared.into_iter()
+	}
+
+	/// Parse an ID into the type registered for its prefix
+	pub fn parse(&self, value: &str) -> Result<AnyId, Error> {
+		let prefix = value.rsplit_once('_').map(|(prefix, _suffix)| prefix);
+
+		match self.entries.get(&prefix) {
+			Some(entry) => (entry.parse)(value),
+			None => Err(Error::UnknownPrefix(prefix.unwrap_or_default().to_string())),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{strong_id, Error, Registry};
+
+	strong_id!(pub struct UserId(u16 => "user"));
+	strong_id!(pub struct OrderId(u32 => "order"));
+	strong_id!(pub struct PlainId(u8));
+
+	#[test]
+	fn parse() {
+		let mut registry = Registry::new();
+		registry
+			.register::<UserId>()
+			.register::<OrderId>()
+			.register::<PlainId>();
+
+		let id = registry.parse("user_009d").unwrap();
+		assert!(id.is::<UserId>());
+		assert_eq!(id.to_string(), "user_009d");
+		assert_eq!(id.downcast::<UserId>().unwrap(), UserId::from(301));
+
+		let id = registry.parse("order_000009d").unwrap();
+		assert!(id.type_name().ends_with("OrderId"));
+		assert_eq!(id.downcast_ref::<UserId>(), None);
+		assert_eq!(id.downcast_ref::<OrderId>(), Some(&OrderId::from(301)));
+
+		let id = registry.parse("4x").unwrap();
+		assert_eq!(id.downcast::<PlainId>().unwrap(), PlainId::from(157));
+
+		assert_eq!(
+			registry.parse("invoice_009d").unwrap_err(),
+			Error::UnknownPrefix("invoice".into())
+		);
+		assert_eq!(
+			registry.parse("user_zzzz").unwrap_err().code(),
+			"ERR_ID_INVALID_FIRST_CHARACTER"
+		);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn from_config() {
+		use crate::{Prefix, TypeTag};
+		use std::collections::HashMap;
+
+		let mut types = Registry::new();
+		types.register::<UserId>().register::<OrderId>();
+
+		let config: HashMap<Prefix, TypeTag> =
+			serde_json::from_str(r#"{"user":"UserId","order":"OrderId"}"#).unwrap();
+		let registry = Registry::from_config(config, &types).unwrap();
+		assert!(registry.parse("user_009d").unwrap().is::<UserId>());
+		assert!(registry.parse("order_000009d").unwrap().is::<OrderId>());
+
+		let config: HashMap<Prefix, TypeTag> =
+			serde_json::from_str(r#"{"user":"InvoiceId"}"#).unwrap();
+		assert_eq!(
+			Registry::from_config(config, &types).unwrap_err(),
+			Error::UnknownTypeTag("InvoiceId".into())
+		);
+
+		let config: HashMap<Prefix, TypeTag> = serde_json::from_str(r#"{"usr":"UserId"}"#).unwrap();
+		assert_eq!(
+			Registry::from_config(config, &types).unwrap_err(),
+			Error::InvalidPrefix("user".into(), "usr".into())
+		);
+
+		assert!(serde_json::from_str::<HashMap<Prefix, TypeTag>>(r#"{"User":"UserId"}"#).is_err());
+	}
+
+	#[test]
+	fn declared_prefixes() {
+		let mut registry = Registry::new();
+		registry
+			.register::<UserId>()
+			.register::<PlainId>()
+			.register::<OrderId>();
+
+		let declared = registry
+			.declared_prefixes()
+			.map(|declared| (declared.prefix, declared.backing))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			declared,
+			vec![(None, "u8"), (Some("order"), "u32"), (Some("user"), "u16")]
+		);
+
+		assert_eq!(
+			registry
+				.declared_prefixes()
+				.map(|declared| declared.type_name)
+				.collect::<Vec<_>>(),
+			vec![
+				core::any::type_name::<PlainId>(),
+				core::any::type_name::<OrderId>(),
+				core::any::type_name::<UserId>(),
+			]
+		);
+	}
+
+	#[test]
+	#[should_panic]
+	fn duplicate_prefix() {
+		strong_id!(pub struct OtherUserId(u32 => "user"));
+
+		Registry::new()
+			.register::<UserId>()
+			.register::<OtherUserId>();
+	}
+}
@@ -0,0 +1,119 @@
+use crate::{global::Global, Error, Prefix};
+
+/// The reserved prefixes, which are only ever added to
+static RESERVED: Global<Vec<String>> = Global::new();
+
+/// Reserve prefixes for the application, e.g. `admin` or `internal`
+///
+/// [`DynamicStrongId`](crate::DynamicStrongId)s can't be created or parsed with a reserved prefix,
+/// unless the prefix is explicitly allowed with [`Prefix::allow_reserved`]. This enforces which
+/// prefixes may be used where IDs enter the application, e.g. when parsing request inputs. The
+/// prefixes of generated StrongIds are chosen at compile-time, and are not checked.
+///
+/// Prefixes are validated, and are added to those already reserved. The reserved prefixes are
+/// global to the process, and can't be unreserved. Reserving prefixes is intended to be done once
+/// at startup, as every call copies the list and leaks the previous one, which may still be read by
+/// a concurrent parse.
+///
+/// ```rust
+/// use strong_id::{reserve_prefixes, DynamicStrongId, Error, Prefix};
+///
+/// reserve_prefixes(["admin", "internal"]).unwrap();
+///
+/// assert_eq!(
+///     "admin_0343".parse::<DynamicStrongId<u16>>(),
+///     Err(Error::ReservedPrefix("admin".into()))
+/// );
+///
+/// let prefix = Prefix::new("admin").unwrap().allow_reserved();
+/// assert!(DynamicStrongId::<u16>::parse_with_prefix(prefix, "admin_0343").is_ok());
+/// ```
+pub fn reserve_prefixes<I, S>(prefixes: I) -> Result<(), Error>
+where
+	I: IntoIterator<Item = S>,
+	S: Into<String>,
+{
+	let mut added = Vec::new();
+	for prefix in prefixes {
+		added.push(Prefix::new(prefix.into())?.as_str().to_string());
+	}
+
+	RESERVED.update(|current| {
+		let mut reserved = current.cloned().unwrap_or_default();
+		for prefix in &added {
+			if !reserved.contains(prefix) {
+				reserved.push(prefix.clone());
+			}
+		}
+		reserved
+	});
+
+	Ok(())
+}
+
+/// The prefixes reserved with [`reserve_prefixes`]
+pub fn reserved_prefixes() -> &'static [String] {
+	RESERVED.get().map_or(&[], Vec::as_slice)
+}
+
+pub(crate) fn is_reserved(prefix: &str) -> bool {
+	reserved_prefixes()
+		.iter()
+		.any(|reserved| reserved == prefix)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{reserve_prefixes, reserved_prefixes};
+	use crate::{DynamicStrongId, Error, Prefix};
+
+	// the reserved prefixes are global, so each test reserves its own
+
+	#[test]
+	fn reserved() {
+		reserve_prefixes(["reservedadmin", "reservedinternal"]).unwrap();
+		reserve_prefixes(vec![String::from("reservedadmin")]).unwrap();
+
+		let reserved = reserved_prefixes();
+		assert_eq!(reserved.iter().filter(|p| *p == "reservedadmin").count(), 1);
+		assert!(reserved.iter().any(|p| p == "reservedinternal"));
+
+		assert!(Prefix::new("reservedadmin").unwrap().is_reserved());
+		assert!(!Prefix::new("user").unwrap().is_reserved());
+
+		assert_eq!(
+			reserve_prefixes(["Reserved"]),
+			Err(Error::IncorrectPrefixCharacter('R'))
+		);
+	}
+
+	#[test]
+	fn dynamic() {
+		reserve_prefixes(["reservedsystem"]).unwrap();
+
+		assert_eq!(
+			DynamicStrongId::new("reservedsystem", 3203u16),
+			Err(Error::ReservedPrefix("reservedsystem".into()))
+		);
+		assert_eq!(
+			DynamicStrongId::new_with("reservedsystem", || 3203u16),
+			Err(Error::ReservedPrefix("reservedsystem".into()))
+		);
+		assert_eq!(
+			"reservedsystem_0343".parse::<DynamicStrongId<u16>>(),
+			Err(Error::ReservedPrefix("reservedsystem".into()))
+		);
+		assert_eq!(
+			DynamicStrongId::<u16>::parse_with_prefix("reservedsystem", "reservedsystem_0343"),
+			Err(Error::ReservedPrefix("reservedsystem".into()))
+		);
+
+		let allowed = Prefix::new("reservedsystem").unwrap().allow_reserved();
+		let id = DynamicStrongId::new(allowed.clone(), 3203u16).unwrap();
+		assert_eq!(id.to_string(), "reservedsystem_0343");
+		assert_eq!(
+			DynamicStrongId::<u16>::parse_with_prefix(allowed, "reservedsystem_0343"),
+			Ok(id)
+		);
+	}
+}
@@ -0,0 +1,32 @@
+/// A small xorshift generator, so that failures are reproducible without a dependency
+pub(crate) struct Rng(u64);
+
+impl Rng {
+	/// Create a generator from a seed. xorshift gets stuck on zero, so a zero seed is replaced.
+	pub(crate) const fn new(seed: u64) -> Self {
+		if seed == 0 {
+			Self(0x2545_f491_4f6c_dd1d)
+		} else {
+			Self(seed)
+		}
+	}
+
+	pub(crate) fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	pub(crate) fn below(&mut self, n: usize) -> usize {
+		(self.next() % n as u64) as usize
+	}
+
+	#[cfg(any(test, feature = "loadtest"))]
+	pub(crate) fn fill(&mut self, dst: &mut [u8]) {
+		for chunk in dst.chunks_mut(8) {
+			let random = self.next().to_le_bytes();
+			chunk.copy_from_slice(&random[..chunk.len()]);
+		}
+	}
+}
@@ -0,0 +1,385 @@
+use crate::{base32::ALPHABET, rng::Rng, StaticPrefix};
+use core::{fmt::Debug, fmt::Display, str::FromStr};
+
+const ITERATIONS: usize = 256;
+
+/// Bytes which are never part of an encoded suffix
+const INVALID_BYTES: &[u8] = b"uU!-+~. ";
+
+/// Serde round-trips, which are a no-op when the "serde" feature is disabled
+pub trait RoundtripSerde {
+	fn assert_serde_roundtrip(&self);
+}
+
+#[cfg(not(feature = "serde"))]
+impl<S> RoundtripSerde for S {
+	fn assert_serde_roundtrip(&self) {}
+}
+
+#[cfg(feature = "serde")]
+impl<S> RoundtripSerde for S
+where
+	S: serde::Serialize + serde::de::DeserializeOwned + Debug + PartialEq,
+{
+	fn assert_serde_roundtrip(&self) {
+		let captured = match self.serialize(capture::Serializer) {
+			Ok(captured) => captured,
+			Err(error) => panic!("failed to serialize {self:?}: {error}"),
+		};
+
+		match S::deserialize(captured.clone()) {
+			Ok(value) => assert_eq!(&value, self, "serde round-trip of {captured:?}"),
+			Err(error) => panic!("failed to deserialize {captured:?}: {error}"),
+		}
+	}
+}
+
+fn parse_ok<S>(value: &str) -> Option<S>
+where
+	S: FromStr,
+{
+	value.parse::<S>().ok()
+}
+
+fn assert_invalid<S>(value: &str)
+where
+	S: FromStr + Debug,
+{
+	if let Ok(parsed) = value.parse::<S>() {
+		panic!("expected {value:?} to be rejected, but it parsed as {parsed:?}");
+	}
+}
+
+fn assert_valid<S>(prefix: &str, suffix: &str, encoded_len: usize)
+where
+	S: StaticPrefix + FromStr + Display + Debug + PartialEq + RoundtripSerde,
+	S::Err: Debug,
+{
+	let encoded = format!("{prefix}{suffix}");
+
+	let id = match encoded.parse::<S>() {
+		Ok(id) => id,
+		Err(error) => panic!("expected {encoded:?} to parse: {error:?}"),
+	};
+
+	let display = id.to_string();
+	assert_eq!(display, encoded, "encoding {id:?}");
+	assert_eq!(display.len(), encoded_len, "length of {display:?}");
+	assert_eq!(
+		display.parse::<S>().ok().as_ref(),
+		Some(&id),
+		"parsing {display:?}"
+	);
+	id.assert_serde_roundtrip();
+
+	// mutations of a valid ID which must be rejected
+	let suffix = suffix.as_bytes();
+	for (index, invalid) in INVALID_BYTES.iter().enumerate() {
+		let mut mutated = suffix.to_vec();
+		mutated[index % suffix.len()] = *invalid;
+		assert_invalid::<S>(&format!("{prefix}{}", String::from_utf8_lossy(&mutated)));
+	}
+
+	assert_invalid::<S>(&format!(
+		"{prefix}{}",
+		String::from_utf8_lossy(&suffix[..suffix.len() - 1])
+	));
+	assert_invalid::<S>(&format!("{encoded}0"));
+
+	match S::PREFIX {
+		Some(expected) => {
+			assert_invalid::<S>(&String::from_utf8_lossy(suffix));
+			assert_invalid::<S>(&format!("_{}", String::from_utf8_lossy(suffix)));
+
+			let mut other = expected.as_bytes().to_vec();
+			other[0] = if other[0] == b'a' { b'b' } else { b'a' };
+			assert_invalid::<S>(&format!(
+				"{}_{}",
+				String::from_utf8_lossy(&other),
+				String::from_utf8_lossy(suffix)
+			));
+		}
+		None => assert_invalid::<S>(&format!("x_{encoded}")),
+	}
+}
+
+/// Run the round-trip assertions for `S`, see [`assert_roundtrip!`](crate::assert_roundtrip)
+pub fn assert_roundtrip<S>(encoded_len: usize)
+where
+	S: StaticPrefix + FromStr + Display + Debug + PartialEq + RoundtripSerde,
+	S::Err: Debug,
+{
+	let prefix = match S::PREFIX {
+		Some(prefix) => format!("{prefix}_"),
+		None => String::new(),
+	};
+	let suffix_len = encoded_len - prefix.len();
+
+	let rest = |byte: u8| String::from_utf8(vec![byte; suffix_len - 1]).unwrap();
+
+	// the largest first character depends on the number of bits which are padded
+	let max_first = ALPHABET
+		.iter()
+		.rposition(|first| {
+			parse_ok::<S>(&format!("{prefix}{}{}", *first as char, rest(b'z'))).is_some()
+		})
+		.unwrap_or_else(|| panic!("no valid encoding of length {encoded_len} found"));
+
+	// zero is rejected by `NonZero` IDs
+	let zero = "0".repeat(suffix_len);
+	let zero_valid = parse_ok::<S>(&format!("{prefix}{zero}")).is_some();
+	if zero_valid {
+		assert_valid::<S>(&prefix, &zero, encoded_len);
+	}
+	assert_valid::<S>(
+		&prefix,
+		&format!("{}{}", ALPHABET[max_first] as char, rest(b'z')),
+		encoded_len,
+	);
+
+	if let Some(overflow) = ALPHABET.get(max_first + 1) {
+		assert_invalid::<S>(&format!("{prefix}{}{}", *overflow as char, rest(b'z')));
+	}
+
+	let mut rng = Rng::new(0x2545_f491_4f6c_dd1d);
+	for _ in 0..ITERATIONS {
+		let mut suffix = String::with_capacity(suffix_len);
+		suffix.push(ALPHABET[rng.below(max_first + 1)] as char);
+		for _ in 1..suffix_len {
+			suffix.push(ALPHABET[rng.below(ALPHABET.len())] as char);
+		}
+
+		if !zero_valid && suffix == zero {
+			continue;
+		}
+
+		assert_valid::<S>(&prefix, &suffix, encoded_len);
+	}
+}
+
+/// A minimal serde data format, so that round-trips don't depend on a format crate
+#[cfg(feature = "serde")]
+mod capture {
+	use core::fmt::{Display, Formatter};
+	use serde::{
+		de::{self, IntoDeserializer, Visitor},
+		forward_to_deserialize_any, ser,
+		ser::Impossible,
+	};
+
+	#[derive(Clone, Debug)]
+	pub enum Captured {
+		Unit,
+		Str(String),
+		Some(Box<Captured>),
+		Struct(Vec<(&'static str, Captured)>),
+	}
+
+	#[derive(Debug)]
+	pub struct Error(String);
+
+	impl Display for Error {
+		fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+			f.write_str(&self.0)
+		}
+	}
+
+	impl std::error::Error for Error {}
+
+	impl ser::Error for Error {
+		fn custom<T: Display>(msg: T) -> Self {
+			Self(msg.to_string())
+		}
+	}
+
+	impl de::Error for Error {
+		fn custom<T: Display>(msg: T) -> Self {
+			Self(msg.to_string())
+		}
+	}
+
+	fn unsupported<T>() -> Result<T, Error> {
+		Err(Error("StrongIds serialize as strings or structs".into()))
+	}
+
+	macro_rules! unsupported {
+		($($method:ident($($ty:ty),*)),* $(,)?) => {
+			$(
+				fn $method(self, $(_: $ty),*) -> Result<Captured, Error> {
+					unsupported()
+				}
+			)*
+		};
+	}
+
+	pub struct Serializer;
+
+	impl ser::Serializer for Serializer {
+		type Ok = Captured;
+		type Error = Error;
+		type SerializeSeq = Impossible<Captured, Error>;
+		type SerializeTuple = Impossible<Captured, Error>;
+		type SerializeTupleStruct = Impossible<Captured, Error>;
+		type SerializeTupleVariant = Impossible<Captured, Error>;
+		type SerializeMap = Impossible<Captured, Error>;
+		type SerializeStruct = StructSerializer;
+		type SerializeStructVariant = Impossible<Captured, Error>;
+
+		unsupported!(
+			serialize_bool(bool),
+			serialize_i8(i8),
+			serialize_i16(i16),
+			serialize_i32(i32),
+			serialize_i64(i64),
+			serialize_u8(u8),
+			serialize_u16(u16),
+			serialize_u32(u32),
+			serialize_u64(u64),
+			serialize_f32(f32),
+			serialize_f64(f64),
+			serialize_char(char),
+			serialize_bytes(&[u8]),
+			serialize_unit_struct(&'static str),
+			serialize_unit_variant(&'static str, u32, &'static str),
+		);
+
+		fn serialize_str(self, v: &str) -> Result<Captured, Error> {
+			Ok(Captured::Str(v.to_string()))
+		}
+
+		fn serialize_none(self) -> Result<Captured, Error> {
+			Ok(Captured::Unit)
+		}
+
+		fn serialize_some<T: ?Sized + ser::Serialize>(self, value: &T) -> Result<Captured, Error> {
+			Ok(Captured::Some(Box::new(value.serialize(self)?)))
+		}
+
+		fn serialize_unit(self) -> Result<Captured, Error> {
+			Ok(Captured::Unit)
+		}
+
+		fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(
+			self,
+			_name: &'static str,
+			value: &T,
+		) -> Result<Captured, Error> {
+			value.serialize(self)
+		}
+
+		fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(
+			self,
+			_name: &'static str,
+			_variant_index: u32,
+			_variant: &'static str,
+			_value: &T,
+		) -> Result<Captured, Error> {
+			unsupported()
+		}
+
+		fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+			unsupported()
+		}
+
+		fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+			unsupported()
+		}
+
+		fn serialize_tuple_struct(
+			self,
+			_name: &'static str,
+			_len: usize,
+		) -> Result<Self::SerializeTupleStruct, Error> {
+			unsupported()
+		}
+
+		fn serialize_tuple_variant(
+			self,
+			_name: &'static str,
+			_variant_index: u32,
+			_variant: &'static str,
+			_len: usize,
+		) -> Result<Self::SerializeTupleVariant, Error> {
+			unsupported()
+		}
+
+		fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+			unsupported()
+		}
+
+		fn serialize_struct(
+			self,
+			_name: &'static str,
+			len: usize,
+		) -> Result<Self::SerializeStruct, Error> {
+			Ok(StructSerializer(Vec::with_capacity(len)))
+		}
+
+		fn serialize_struct_variant(
+			self,
+			_name: &'static str,
+			_variant_index: u32,
+			_variant: &'static str,
+			_len: usize,
+		) -> Result<Self::SerializeStructVariant, Error> {
+			unsupported()
+		}
+	}
+
+	pub struct StructSerializer(Vec<(&'static str, Captured)>);
+
+	impl ser::SerializeStruct for StructSerializer {
+		type Ok = Captured;
+		type Error = Error;
+
+		fn serialize_field<T: ?Sized + ser::Serialize>(
+			&mut self,
+			key: &'static str,
+			value: &T,
+		) -> Result<(), Error> {
+			self.0.push((key, value.serialize(Serializer)?));
+			Ok(())
+		}
+
+		fn end(self) -> Result<Captured, Error> {
+			Ok(Captured::Struct(self.0))
+		}
+	}
+
+	impl<'de> IntoDeserializer<'de, Error> for Captured {
+		type Deserializer = Self;
+
+		fn into_deserializer(self) -> Self {
+			self
+		}
+	}
+
+	impl<'de> de::Deserializer<'de> for Captured {
+		type Error = Error;
+
+		fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+			match self {
+				Captured::Unit => visitor.visit_unit(),
+				Captured::Str(value) => visitor.visit_string(value),
+				Captured::Some(value) => visitor.visit_some(*value),
+				Captured::Struct(fields) => {
+					visitor.visit_map(de::value::MapDeserializer::new(fields.into_iter()))
+				}
+			}
+		}
+
+		fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+			match self {
+				Captured::Unit => visitor.visit_none(),
+				Captured::Some(value) => visitor.visit_some(*value),
+				other => visitor.visit_some(other),
+			}
+		}
+
+		forward_to_deserialize_any! {
+			bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+			bytes byte_buf unit unit_struct newtype_struct seq tuple
+			tuple_struct map struct enum identifier ignored_any
+		}
+	}
+}
@@ -0,0 +1,237 @@
+use crate::{Error, Id, StrongId};
+use core::{fmt, marker::PhantomData, str::FromStr};
+
+/// The number of half rounds of the permutation, alternating between the two halves of the bytes
+const ROUNDS: usize = 8;
+
+/// A secret key for showing each tenant its own public IDs, see [`DisplaySalted`]
+///
+/// The key must be kept stable, as IDs shown with one key can only be parsed with the same key.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct SaltKey(u128);
+
+impl SaltKey {
+	/// Create a key from a secret, e.g. loaded from configuration
+	pub const fn new(key: u128) -> Self {
+		Self(key)
+	}
+
+	/// Display an ID as the given tenant sees it
+	pub fn display<'a, T, S, U>(&self, id: &'a S, tenant: U) -> DisplaySalted<'a, T, S>
+	where
+		T: Id,
+		S: StrongId<T> + From<T>,
+		U: AsRef<[u8]>,
+	{
+		DisplaySalted {
+			id,
+			keys: round_keys(self.0, tenant.as_ref()),
+			inner: PhantomData,
+		}
+	}
+
+	/// Parse an ID which was shown to the given tenant, returning the underlying ID
+	///
+	/// An ID shown to another tenant parses to a different ID, or fails to parse.
+	pub fn parse<S, T, U>(&self, value: &str, tenant: U) -> Result<S, Error>
+	where
+		T: Id,
+		S: StrongId<T> + From<T> + FromStr<Err = Error>,
+		U: AsRef<[u8]>,
+	{
+		let salted = value.parse::<S>()?;
+		let keys = round_keys(self.0, tenant.as_ref());
+		let mut bytes = salted.id().to_bytes();
+
+		// walk the cycle back past values which aren't valid, in reverse of `DisplaySalted`
+		loop {
+			unpermute(&keys, bytes.as_mut());
+			if let Ok(id) = T::from_bytes(copy_bytes::<T>(&bytes)) {
+				return Ok(S::from(id));
+			}
+		}
+	}
+}
+
+impl fmt::Debug for SaltKey {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("SaltKey(..)")
+	}
+}
+
+/// Displays an ID as a keyed permutation of itself, so that each tenant sees different public IDs
+/// for the same object
+///
+/// The salted ID has the prefix, length and encoding of the ID, so it is indistinguishable from any
+/// other ID of the type, and [`SaltKey::parse`] reverses it with the same key and tenant. IDs
+/// shown to one tenant can't be correlated with IDs shown to another without the key.
+///
+/// The permutation obfuscates IDs rather than encrypting them, and should not be relied on to keep
+/// the underlying IDs secret from a determined attacker.
+///
+/// ```rust
+/// use strong_id::{strong_id, SaltKey};
+///
+/// strong_id!(pub struct ListingId(u64 => "listing"));
+///
+/// let key = SaltKey::new(0x5eed);
+/// let id = ListingId::from(3203);
+///
+/// let shown = key.display(&id, "tenant_a").to_string();
+/// assert_ne!(shown, key.display(&id, "tenant_b").to_string());
+/// assert!(shown.starts_with("listing_"));
+///
+/// assert_eq!(key.parse::<ListingId, _, _>(&shown, "tenant_a").unwrap(), id);
+/// ```
+pub struct DisplaySalted<'a, T, S> {
+	id: &'a S,
+	keys: [u64; ROUNDS],
+	inner: PhantomData<fn() -> T>,
+}
+
+impl<'a, T, S> fmt::Display for DisplaySalted<'a, T, S>
+where
+	T: Id,
+	S: StrongId<T> + From<T>,
+{
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut bytes = self.id.id().to_bytes();
+
+		// permuted bytes may not be a valid value, e.g. zero for `NonZeroU64`, in which case
+		// they are permuted again until they are, which keeps the mapping reversible
+		loop {
+			permute(&self.keys, bytes.as_mut());
+			if let Ok(id) = T::from_bytes(copy_bytes::<T>(&bytes)) {
+				return fmt::Display::fmt(&S::from(id), f);
+			}
+		}
+	}
+}
+
+impl<'a, T, S: fmt::Debug> fmt::Debug for DisplaySalted<'a, T, S> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("DisplaySalted")
+			.field("id", self.id)
+			.finish_non_exhaustive()
+	}
+}
+
+fn copy_bytes<T: Id>(bytes: &T::Bytes) -> T::Bytes {
+	let mut copy = T::Bytes::default();
+	copy.as_mut().copy_from_slice(bytes.as_ref());
+	copy
+}
+
+/// Derive the keys of each round from the key and the tenant
+fn round_keys(key: u128, tenant: &[u8]) -> [u64; ROUNDS] {
+	let mut state = mix(key as u64 ^ mix((key >> 64) as u64) ^ tenant.len() as u64);
+	for chunk in tenant.chunks(8) {
+		let mut word = [0u8; 8];
+		word[..chunk.len()].copy_from_slice(chunk);
+		state = mix(state ^ u64::from_le_bytes(word));
+	}
+
+	let mut keys = [0; ROUNDS];
+	for (round, round_key) in keys.iter_mut().enumerate() {
+		state = mix(state.wrapping_add(0x9e37_79b9_7f4a_7c15 ^ round as u64));
+		*round_key = state;
+	}
+	keys
+}
+
+/// An unbalanced Feistel network over the bytes, in which each half round XORs one half with a
+/// keyed hash of the other, so that it is its own inverse
+fn permute(keys: &[u64; ROUNDS], bytes: &mut [u8]) {
+	for (round, key) in keys.iter().enumerate() {
+		half_round(*key, round, bytes);
+	}
+}
+
+fn unpermute(keys: &[u64; ROUNDS], bytes: &mut [u8]) {
+	for (round, key) in keys.iter().enumerate().rev() {
+		half_round(*key, round, bytes);
+	}
+}
+
+fn half_round(key: u64, round: usize, bytes: &mut [u8]) {
+	let (left, right) = bytes.split_at_mut(bytes.len() / 2);
+	let (src, dst) = if round % 2 == 0 {
+		(left, right)
+	} else {
+		(right, left)
+	};
+
+	let mut hash = mix(key ^ src.len() as u64);
+	for chunk in src.chunks(8) {
+		let mut word = [0u8; 8];
+		word[..chunk.len()].copy_from_slice(chunk);
+		hash = mix(hash ^ u64::from_le_bytes(word));
+	}
+
+	for (index, chunk) in dst.chunks_mut(8).enumerate() {
+		let stream = mix(hash.wrapping_add(index as u64)).to_le_bytes();
+		chunk
+			.iter_mut()
+			.zip(stream.iter())
+			.for_each(|(byte, stream)| *byte ^= stream);
+	}
+}
+
+/// The splitmix64 finalizer
+fn mix(mut x: u64) -> u64 {
+	x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+	x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+	x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::SaltKey;
+	use crate::{strong_id, Error};
+	use std::collections::HashSet;
+
+	strong_id!(pub struct ListingId(u64 => "listing"));
+	strong_id!(pub struct SmallId(core::num::NonZeroU8 => "small"));
+
+	const KEY: SaltKey = SaltKey::new(0x0123_4567_89ab_cdef_fedc_ba98_7654_3210);
+
+	#[test]
+	fn roundtrip() {
+		let id = ListingId::from(3203);
+
+		let shown = KEY.display(&id, "tenant_a").to_string();
+		assert_eq!(shown.len(), id.to_string().len());
+		assert!(shown.starts_with("listing_"));
+		assert_ne!(shown, id.to_string());
+		assert_eq!(KEY.parse::<ListingId, _, _>(&shown, "tenant_a"), Ok(id));
+
+		// another tenant, or another key, sees a different ID
+		assert_ne!(shown, KEY.display(&id, "tenant_b").to_string());
+		assert_ne!(shown, SaltKey::new(1).display(&id, "tenant_a").to_string());
+		assert_ne!(KEY.parse::<ListingId, _, _>(&shown, "tenant_b"), Ok(id));
+
+		assert_eq!(
+			KEY.parse::<ListingId, _, _>("user_0000000000343", "tenant_a"),
+			Err(Error::InvalidPrefix("listing".into(), "user".into()))
+		);
+	}
+
+	#[test]
+	fn permutation() {
+		// every valid value maps to a distinct valid value, skipping zero
+		let shown = (1..=u8::MAX)
+			.map(|value| {
+				let id = SmallId::from(core::num::NonZeroU8::new(value).unwrap());
+				let shown = KEY.display(&id, "tenant_a").to_string();
+				assert_eq!(KEY.parse::<SmallId, _, _>(&shown, "tenant_a"), Ok(id));
+				shown
+			})
+			.collect::<HashSet<_>>();
+		assert_eq!(shown.len(), 255);
+	}
+
+	#[test]
+	fn debug_hides_key() {
+		assert_eq!(format!("{KEY:?}"), "SaltKey(..)");
+	}
+}
@@ -0,0 +1,2221 @@
+//! Alternative serde representations for StrongIds
+//!
+//! These are usable with `#[serde(with = "...")]` on fields of any type which implements
+//! [`ErasedStrongId`], and are used by the derive when a representation is selected with the
+//! `#[strong_id(serde = "...")]` attribute.
+//!
+//! [`path`] reports where in a document an ID failed to deserialize.
+
+use crate::{DynamicStrongId, ErasedStrongId, Id, Prefix, StrongId};
+use core::{fmt::Display, marker::PhantomData, str::FromStr};
+use serde::{
+	de::{DeserializeSeed, Error, MapAccess, SeqAccess, Visitor},
+	ser::SerializeStruct,
+	Deserializer, Serializer,
+};
+
+/// Serialize IDs as a struct with separate `prefix` and `suffix` fields
+///
+/// ```json
+/// { "prefix": "user", "suffix": "01h455vb4pex5vsknk084sn02q" }
+/// ```
+///
+/// IDs without a prefix are serialized with a `null` prefix. Deserializing accepts both the struct
+/// form and the string form.
+///
+/// ```rust
+/// use strong_id::strong_id;
+///
+/// strong_id! {
+///     #[strong_id(serde = "struct")]
+///     pub struct UserId(u16 => "user")
+/// }
+///
+/// let user_id = UserId::from(3203);
+/// let json = serde_json::to_string(&user_id).unwrap();
+/// assert_eq!(json, r#"{"prefix":"user","suffix":"0343"}"#);
+///
+/// assert_eq!(serde_json::from_str::<UserId>(&json).unwrap(), user_id);
+/// assert_eq!(serde_json::from_str::<UserId>("\"user_0343\"").unwrap(), user_id);
+/// ```
+pub mod struct_form {
+	use super::*;
+
+	pub fn serialize<I, S>(id: &I, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		I: ErasedStrongId + ?Sized,
+		S: Serializer,
+	{
+		let mut state = serializer.serialize_struct("StrongId", 2)?;
+		state.serialize_field("prefix", &id.erased_prefix())?;
+		state.serialize_field("suffix", &id.encoded())?;
+		state.end()
+	}
+
+	pub fn deserialize<'de, I, D>(deserializer: D) -> Result<I, D::Error>
+	where
+		I: FromStr,
+		I::Err: Display,
+		D: Deserializer<'de>,
+	{
+		deserializer.deserialize_any(StructFormVisitor(PhantomData))
+	}
+
+	struct StructFormVisitor<I>(PhantomData<I>);
+
+	impl<'de, I> Visitor<'de> for StructFormVisitor<I>
+	where
+		I: FromStr,
+		I::Err: Display,
+	{
+		type Value = I;
+
+		fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+			f.write_str("a string or a struct with `prefix` and `suffix` fields")
+		}
+
+		fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+			value
+				.parse::<I>()
+				.map_err(|error| E::custom(error.to_string()))
+		}
+
+		fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+			self.visit_str(&join_struct_form(map)?)
+		}
+	}
+
+	/// Read the `prefix` and `suffix` fields of the struct form and join them into an encoded ID
+	pub(super) fn join_struct_form<'de, A: MapAccess<'de>>(mut map: A) -> Result<String, A::Error> {
+		let mut prefix: Option<Option<String>> = None;
+		let mut suffix: Option<String> = None;
+
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"prefix" if prefix.is_some() => return Err(A::Error::duplicate_field("prefix")),
+				"prefix" => prefix = Some(map.next_value()?),
+				"suffix" if suffix.is_some() => return Err(A::Error::duplicate_field("suffix")),
+				"suffix" => suffix = Some(map.next_value()?),
+				_ => return Err(A::Error::unknown_field(&key, &["prefix", "suffix"])),
+			}
+		}
+
+		let suffix = suffix.ok_or_else(|| A::Error::missing_field("suffix"))?;
+		Ok(match prefix.flatten() {
+			Some(prefix) => format!("{prefix}_{suffix}"),
+			None => suffix,
+		})
+	}
+}
+
+/// Serialize the bytes of a value as a big-endian integer, undoing the offset of the bytes of signed
+/// integers, see [`Id::SIGNED`]
+fn serialize_integer<S: Serializer>(
+	bytes: &[u8],
+	signed: bool,
+	serializer: S,
+) -> Result<S::Ok, S::Error> {
+	if bytes.is_empty() || bytes.len() > 16 {
+		return Err(serde::ser::Error::custom(
+			"the ID is too wide for the integer representation",
+		));
+	}
+
+	let value = bytes
+		.iter()
+		.fold(0u128, |value, byte| value << 8 | *byte as u128);
+
+	if signed {
+		// flip the sign bit back, and sign-extend the value to 128 bits
+		let unused = 128 - bytes.len() as u32 * 8;
+		let value = (((value ^ (1 << (127 - unused))) << unused) as i128) >> unused;
+
+		if bytes.len() <= 8 {
+			serializer.serialize_i64(value as i64)
+		} else {
+			serializer.serialize_i128(value)
+		}
+	} else if bytes.len() <= 8 {
+		serializer.serialize_u64(value as u64)
+	} else {
+		serializer.serialize_u128(value)
+	}
+}
+
+/// The bytes of a value from a big-endian integer, in two's complement for negative values, and
+/// offset like the bytes of signed integers if `signed` is set, see [`Id::SIGNED`]
+fn integer_bytes<B: AsMut<[u8]> + Default>(
+	value: u128,
+	negative: bool,
+	signed: bool,
+) -> Result<B, crate::Error> {
+	let mut bytes = B::default();
+	let len = bytes.as_mut().len();
+
+	let value = match (signed, negative) {
+		(false, true) => return Err(crate::Error::OutOfRange),
+		(false, false) => value,
+		(true, _) if len == 0 || len > 16 => return Err(crate::Error::OutOfRange),
+		(true, _) => {
+			let bits = len as u32 * 8;
+			let sign = 1u128 << (bits - 1);
+
+			// the value must fit once sign-extended, i.e. in -sign..sign
+			let fits = match negative {
+				true => bits == 128 || value >= sign.wrapping_neg(),
+				false => value < sign,
+			};
+			if !fits {
+				return Err(crate::Error::OutOfRange);
+			}
+
+			let mask = u128::MAX >> (128 - bits);
+			(value ^ sign) & mask
+		}
+	};
+
+	let value = value.to_be_bytes();
+
+	// the bytes of the ID are the trailing bytes of the value, the leading bytes must be zero
+	let (leading, trailing) = value.split_at(value.len().saturating_sub(len));
+	if leading.iter().any(|byte| *byte != 0) {
+		return Err(crate::Error::OutOfRange);
+	}
+	bytes.as_mut()[len - trailing.len()..].copy_from_slice(trailing);
+
+	Ok(bytes)
+}
+
+/// Serialize IDs as the bytes of their inner value, see [`Id::to_bytes`]
+///
+/// The prefix is not serialized, and is added back when deserializing. In human-readable formats,
+/// deserializing also accepts sequences of bytes, e.g. from JSON, and the string form.
+///
+/// ```rust
+/// use strong_id::strong_id;
+///
+/// strong_id! {
+///     #[strong_id(serde = "bytes")]
+///     pub struct UserId(u16 => "user")
+/// }
+///
+/// let user_id = UserId::from(3203);
+/// let json = serde_json::to_string(&user_id).unwrap();
+/// assert_eq!(json, "[12,131]");
+///
+/// assert_eq!(serde_json::from_str::<UserId>(&json).unwrap(), user_id);
+/// assert_eq!(serde_json::from_str::<UserId>("\"user_0343\"").unwrap(), user_id);
+/// ```
+pub mod bytes_form {
+	use super::*;
+	use crate::StaticPrefix;
+
+	pub fn serialize<I, S>(id: &I, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		I: StaticPrefix + StrongId<<I as StaticPrefix>::Inner>,
+		S: Serializer,
+	{
+		serializer.serialize_bytes(id.id().to_bytes().as_ref())
+	}
+
+	pub fn deserialize<'de, I, D>(deserializer: D) -> Result<I, D::Error>
+	where
+		I: StaticPrefix + From<<I as StaticPrefix>::Inner> + FromStr,
+		I::Err: Display,
+		D: Deserializer<'de>,
+	{
+		if deserializer.is_human_readable() {
+			deserializer.deserialize_any(InnerFormVisitor(PhantomData))
+		} else {
+			deserializer.deserialize_bytes(InnerFormVisitor(PhantomData))
+		}
+	}
+}
+
+/// Serialize IDs as the bytes of their inner value as a big-endian integer, which is the value
+/// itself for integer IDs, signed or not
+///
+/// The prefix is not serialized, and is added back when deserializing. In human-readable formats,
+/// deserializing also accepts the string form. Serializing fails for IDs wider than 128 bits.
+///
+/// ```rust
+/// use strong_id::strong_id;
+///
+/// strong_id! {
+///     #[strong_id(serde = "integer")]
+///     pub struct UserId(u32 => "user")
+/// }
+///
+/// let user_id = UserId::from(3203);
+/// let json = serde_json::to_string(&user_id).unwrap();
+/// assert_eq!(json, "3203");
+///
+/// assert_eq!(serde_json::from_str::<UserId>(&json).unwrap(), user_id);
+/// assert_eq!(serde_json::from_str::<UserId>("\"user_0000343\"").unwrap(), user_id);
+/// ```
+pub mod integer_form {
+	use super::*;
+	use crate::StaticPrefix;
+
+	pub fn serialize<I, S>(id: &I, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		I: StaticPrefix + StrongId<<I as StaticPrefix>::Inner>,
+		S: Serializer,
+	{
+		serialize_integer(
+			id.id().to_bytes().as_ref(),
+			<I::Inner as Id>::SIGNED,
+			serializer,
+		)
+	}
+
+	pub fn deserialize<'de, I, D>(deserializer: D) -> Result<I, D::Error>
+	where
+		I: StaticPrefix + From<<I as StaticPrefix>::Inner> + FromStr,
+		I::Err: Display,
+		D: Deserializer<'de>,
+	{
+		let narrow = core::mem::size_of::<<<I as StaticPrefix>::Inner as Id>::Bytes>() <= 8;
+
+		match (
+			deserializer.is_human_readable(),
+			<I::Inner as Id>::SIGNED,
+			narrow,
+		) {
+			(true, _, _) => deserializer.deserialize_any(InnerFormVisitor(PhantomData)),
+			(false, true, true) => deserializer.deserialize_i64(InnerFormVisitor(PhantomData)),
+			(false, true, false) => deserializer.deserialize_i128(InnerFormVisitor(PhantomData)),
+			(false, false, true) => deserializer.deserialize_u64(InnerFormVisitor(PhantomData)),
+			(false, false, false) => deserializer.deserialize_u128(InnerFormVisitor(PhantomData)),
+		}
+	}
+}
+
+/// Reads the forms of an ID which don't include the prefix, along with the string form
+struct InnerFormVisitor<I>(PhantomData<I>);
+
+impl<I> InnerFormVisitor<I>
+where
+	I: crate::StaticPrefix + From<<I as crate::StaticPrefix>::Inner>,
+{
+	fn decode_bytes<E: Error>(
+		bytes: <<I as crate::StaticPrefix>::Inner as Id>::Bytes,
+	) -> Result<I, E> {
+		<I as crate::StaticPrefix>::Inner::from_bytes(bytes)
+			.map(I::from)
+			.map_err(E::custom)
+	}
+}
+
+impl<'de, I> Visitor<'de> for InnerFormVisitor<I>
+where
+	I: crate::StaticPrefix + From<<I as crate::StaticPrefix>::Inner> + FromStr,
+	I::Err: Display,
+{
+	type Value = I;
+
+	fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		f.write_str("bytes, an integer or a string")
+	}
+
+	fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+		value
+			.parse::<I>()
+			.map_err(|error| E::custom(error.to_string()))
+	}
+
+	fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+		let mut bytes = <<I as crate::StaticPrefix>::Inner as Id>::Bytes::default();
+		if bytes.as_ref().len() != value.len() {
+			return Err(E::invalid_length(value.len(), &self));
+		}
+
+		bytes.as_mut().copy_from_slice(value);
+		Self::decode_bytes(bytes)
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+		let len = core::mem::size_of::<<<I as crate::StaticPrefix>::Inner as Id>::Bytes>();
+		let value = read_byte_seq(seq, len, &self)?;
+		self.visit_bytes(&value)
+	}
+
+	fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+		self.visit_u128(value as u128)
+	}
+
+	fn visit_u128<E: Error>(self, value: u128) -> Result<Self::Value, E> {
+		let signed = <I::Inner as Id>::SIGNED;
+		Self::decode_bytes(integer_bytes(value, false, signed).map_err(E::custom)?)
+	}
+
+	fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+		self.visit_i128(value as i128)
+	}
+
+	fn visit_i128<E: Error>(self, value: i128) -> Result<Self::Value, E> {
+		let signed = <I::Inner as Id>::SIGNED;
+		Self::decode_bytes(integer_bytes(value as u128, value < 0, signed).map_err(E::custom)?)
+	}
+}
+
+/// Read a sequence of bytes, failing once it is longer than the expected `len` so that the
+/// allocation isn't sized by an untrusted size hint
+fn read_byte_seq<'de, A: SeqAccess<'de>>(
+	mut seq: A,
+	len: usize,
+	expected: &dyn serde::de::Expected,
+) -> Result<Vec<u8>, A::Error> {
+	let mut value = Vec::with_capacity(seq.size_hint().unwrap_or_default().min(len));
+	while let Some(byte) = seq.next_element::<u8>()? {
+		if value.len() == len {
+			return Err(A::Error::invalid_length(len + 1, expected));
+		}
+		value.push(byte);
+	}
+
+	Ok(value)
+}
+
+/// Serialize UUID-backed IDs as their bare UUID in its hyphenated form, e.g.
+/// `"0189466f-a14b-77c2-9348-55ffc06f2188"`
+///
+/// The prefix is not serialized, and is added back when deserializing. Deserializing accepts any
+/// form of UUID which `Uuid::parse_str` accepts, along with the string form.
+///
+/// ```rust
+/// use strong_id::strong_uuid;
+///
+/// strong_uuid! {
+///     #[strong_id(serde = "uuid")]
+///     pub struct DeviceId(Uuid => "device")
+/// }
+///
+/// let device_id = "device_01h455vb4pex5vsknk084sn02q".parse::<DeviceId>().unwrap();
+/// let json = serde_json::to_string(&device_id).unwrap();
+/// assert_eq!(json, "\"01890a5d-ac96-774b-bcce-b302099a8057\"");
+///
+/// assert_eq!(serde_json::from_str::<DeviceId>(&json).unwrap(), device_id);
+/// ```
+#[cfg(feature = "uuid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
+pub mod uuid_form {
+	use crate::StrongUuid;
+	use core::{fmt::Display, marker::PhantomData, str::FromStr};
+	use serde::{
+		de::{Error, Visitor},
+		Deserializer, Serializer,
+	};
+
+	pub fn serialize<I, S>(id: &I, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		I: StrongUuid,
+		S: Serializer,
+	{
+		serializer.serialize_str(&id.to_hyphenated_string())
+	}
+
+	pub fn deserialize<'de, I, D>(deserializer: D) -> Result<I, D::Error>
+	where
+		I: StrongUuid + FromStr,
+		I::Err: Display,
+		D: Deserializer<'de>,
+	{
+		deserializer.deserialize_str(UuidFormVisitor(PhantomData))
+	}
+
+	struct UuidFormVisitor<I>(PhantomData<I>);
+
+	impl<'de, I> Visitor<'de> for UuidFormVisitor<I>
+	where
+		I: StrongUuid + FromStr,
+		I::Err: Display,
+	{
+		type Value = I;
+
+		fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+			f.write_str("a UUID or a string")
+		}
+
+		fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+			match uuid::Uuid::parse_str(value) {
+				Ok(uuid) => Ok(I::from_u128(uuid.as_u128())),
+				Err(_) => value
+					.parse::<I>()
+					.map_err(|error| E::custom(error.to_string())),
+			}
+		}
+	}
+}
+
+/// The 16 bytes of a UUID, shared by the binary representations of `arrow`, `cbor` and `rmp`
+#[cfg(any(feature = "arrow", feature = "cbor", feature = "rmp"))]
+mod uuid_bytes {
+	use serde::{
+		de::{Error, SeqAccess, Visitor},
+		Deserialize, Deserializer, Serialize, Serializer,
+	};
+
+	pub(super) struct Bytes<'a>(pub(super) &'a [u8; 16]);
+
+	impl<'a> Serialize for Bytes<'a> {
+		fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			serializer.serialize_bytes(self.0)
+		}
+	}
+
+	pub(super) struct UuidBytes(pub(super) [u8; 16]);
+
+	impl<'de> Deserialize<'de> for UuidBytes {
+		fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+			deserializer
+				.deserialize_bytes(UuidBytesVisitor)
+				.map(UuidBytes)
+		}
+	}
+
+	pub(super) struct UuidBytesVisitor;
+
+	impl<'de> Visitor<'de> for UuidBytesVisitor {
+		type Value = [u8; 16];
+
+		fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+			f.write_str("16 bytes")
+		}
+
+		fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+			value
+				.try_into()
+				.map_err(|_| E::invalid_length(value.len(), &self))
+		}
+
+		fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+			let mut bytes = [0u8; 16];
+			for (index, byte) in bytes.iter_mut().enumerate() {
+				*byte = seq
+					.next_element()?
+					.ok_or_else(|| A::Error::invalid_length(index, &self))?;
+			}
+			if seq.next_element::<u8>()?.is_some() {
+				return Err(A::Error::invalid_length(17, &self));
+			}
+
+			Ok(bytes)
+		}
+	}
+}
+
+/// Write UUID-backed IDs to Arrow and Parquet columns as 16-byte UUIDs
+///
+/// IDs are always serialized as 16 bytes, which [serde_arrow](https://docs.rs/serde_arrow) writes
+/// to `FixedSizeBinary(16)` columns. Annotating the column with the `arrow.uuid` extension type,
+/// e.g. with [`uuid_field`], makes Parquet writers store it with the UUID logical type. The prefix
+/// is not serialized, and is added back when reading the column into typed IDs.
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+/// use strong_id::{serde_helpers::arrow, strong_uuid};
+///
+/// strong_uuid!(pub struct DeviceId(Uuid => "device"));
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Row {
+///     #[serde(with = "arrow")]
+///     device_id: DeviceId,
+/// }
+///
+/// // the schema of the rows, e.g. for `serde_arrow::schema::SchemaLike::from_value`
+/// let fields = vec![arrow::uuid_field("device_id")];
+/// assert_eq!(
+///     serde_json::to_value(&fields).unwrap()[0]["data_type"],
+///     "FixedSizeBinary(16)"
+/// );
+/// ```
+#[cfg(feature = "arrow")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+pub mod arrow {
+	use super::uuid_bytes::{Bytes, UuidBytes};
+	use crate::StrongUuid;
+	use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+
+	/// The name of the canonical Arrow extension type of UUIDs
+	pub const EXTENSION_NAME: &str = "arrow.uuid";
+
+	/// The data type of UUID columns, in the notation of serde_arrow
+	pub const DATA_TYPE: &str = "FixedSizeBinary(16)";
+
+	/// A UUID column of an Arrow schema
+	///
+	/// Serializes to the field notation of serde_arrow, so that a list of fields can be passed to
+	/// `SchemaLike::from_value`.
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	pub struct Field {
+		pub name: String,
+		pub nullable: bool,
+	}
+
+	impl Field {
+		/// Allow null values, e.g. for columns of `Option<Id>`
+		pub fn nullable(self) -> Self {
+			Self {
+				nullable: true,
+				..self
+			}
+		}
+	}
+
+	/// A non-nullable UUID column with the `arrow.uuid` extension type
+	pub fn uuid_field(name: impl Into<String>) -> Field {
+		Field {
+			name: name.into(),
+			nullable: false,
+		}
+	}
+
+	impl Serialize for Field {
+		fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			struct Metadata;
+
+			impl Serialize for Metadata {
+				fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+					use serde::ser::SerializeMap;
+
+					let mut map = serializer.serialize_map(Some(1))?;
+					map.serialize_entry("ARROW:extension:name", EXTENSION_NAME)?;
+					map.end()
+				}
+			}
+
+			let mut field = serializer.serialize_struct("Field", 4)?;
+			field.serialize_field("name", &self.name)?;
+			field.serialize_field("data_type", DATA_TYPE)?;
+			field.serialize_field("nullable", &self.nullable)?;
+			field.serialize_field("metadata", &Metadata)?;
+			field.end()
+		}
+	}
+
+	pub fn serialize<I, S>(id: &I, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		I: StrongUuid,
+		S: Serializer,
+	{
+		Bytes(id.as_bytes()).serialize(serializer)
+	}
+
+	pub fn deserialize<'de, I, D>(deserializer: D) -> Result<I, D::Error>
+	where
+		I: StrongUuid,
+		D: Deserializer<'de>,
+	{
+		let bytes = UuidBytes::deserialize(deserializer)?;
+		Ok(I::from_u128(u128::from_be_bytes(bytes.0)))
+	}
+}
+
+/// Serialize UUID-backed IDs as CBOR UUIDs
+///
+/// In binary formats, IDs are serialized as 16-byte byte strings with CBOR tag 37 (UUID), following
+/// the tag convention of [ciborium](https://docs.rs/ciborium). The prefix is not serialized, and is
+/// added back when deserializing. Deserializing also accepts untagged byte strings. In human-readable
+/// formats, IDs are serialized as strings with their prefix.
+///
+/// Only ciborium is supported. Other CBOR serializers, e.g. `serde_cbor`, don't recognize the tag
+/// convention, and serialize the tag as an enum instead.
+///
+/// ```rust
+/// use strong_id::strong_uuid;
+///
+/// strong_uuid! {
+///     #[strong_id(serde = "cbor")]
+///     pub struct DeviceId(Uuid => "device")
+/// }
+///
+/// let device_id = "device_01h455vb4pex5vsknk084sn02q".parse::<DeviceId>().unwrap();
+/// let json = serde_json::to_string(&device_id).unwrap();
+/// assert_eq!(json, "\"device_01h455vb4pex5vsknk084sn02q\"");
+/// ```
+#[cfg(feature = "cbor")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cbor")))]
+pub mod cbor {
+	use super::uuid_bytes::{Bytes, UuidBytes, UuidBytesVisitor};
+	use crate::StrongUuid;
+	use core::{fmt::Display, str::FromStr};
+	use serde::{
+		de::{EnumAccess, Error, SeqAccess, VariantAccess, Visitor},
+		ser::SerializeTupleVariant,
+		Deserialize, Deserializer, Serializer,
+	};
+
+	/// The CBOR tag of UUIDs
+	pub const UUID_TAG: u64 = 37;
+
+	// ciborium represents tagged items as a tuple variant of this enum
+	const TAG_ENUM: &str = "@@TAG@@";
+	const UNTAGGED: &str = "@@UNTAGGED@@";
+	const TAGGED: &str = "@@TAGGED@@";
+
+	pub fn serialize<I, S>(id: &I, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		I: StrongUuid + Display,
+		S: Serializer,
+	{
+		if serializer.is_human_readable() {
+			return serializer.collect_str(id);
+		}
+
+		let mut tagged = serializer.serialize_tuple_variant(TAG_ENUM, 1, TAGGED, 2)?;
+		tagged.serialize_field(&UUID_TAG)?;
+		tagged.serialize_field(&Bytes(id.as_bytes()))?;
+		tagged.end()
+	}
+
+	pub fn deserialize<'de, I, D>(deserializer: D) -> Result<I, D::Error>
+	where
+		I: StrongUuid + FromStr,
+		I::Err: Display,
+		D: Deserializer<'de>,
+	{
+		if deserializer.is_human_readable() {
+			return String::deserialize(deserializer)?
+				.parse::<I>()
+				.map_err(D::Error::custom);
+		}
+
+		let bytes = deserializer.deserialize_enum(TAG_ENUM, &[UNTAGGED, TAGGED], TagVisitor)?;
+		Ok(I::from_u128(u128::from_be_bytes(bytes)))
+	}
+
+	struct TagVisitor;
+
+	impl<'de> Visitor<'de> for TagVisitor {
+		type Value = [u8; 16];
+
+		fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+			f.write_str("a UUID byte string, optionally with CBOR tag 37")
+		}
+
+		fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+			let (tagged, variant) = data.variant::<Variant>()?;
+			if tagged.0 {
+				variant.tuple_variant(2, TaggedVisitor)
+			} else {
+				Ok(variant.newtype_variant::<UuidBytes>()?.0)
+			}
+		}
+
+		fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+			UuidBytesVisitor.visit_bytes(value)
+		}
+	}
+
+	struct TaggedVisitor;
+
+	impl<'de> Visitor<'de> for TaggedVisitor {
+		type Value = [u8; 16];
+
+		fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+			f.write_str("a UUID byte string with CBOR tag 37")
+		}
+
+		fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+			let tag = seq
+				.next_element::<u64>()?
+				.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+			if tag != UUID_TAG {
+				return Err(A::Error::custom(format!(
+					"expected CBOR tag {UUID_TAG}, found {tag}"
+				)));
+			}
+
+			let bytes = seq
+				.next_element::<UuidBytes>()?
+				.ok_or_else(|| A::Error::invalid_length(1, &self))?;
+			Ok(bytes.0)
+		}
+	}
+
+	/// Whether the item is tagged, deserialized from the variant of the tag enum
+	struct Variant(bool);
+
+	impl<'de> Deserialize<'de> for Variant {
+		fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+			struct VariantVisitor;
+
+			impl<'de> Visitor<'de> for VariantVisitor {
+				type Value = Variant;
+
+				fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+					f.write_str("a CBOR tag variant")
+				}
+
+				fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+					match value {
+						0 => Ok(Variant(false)),
+						1 => Ok(Variant(true)),
+						_ => Err(E::invalid_value(
+							serde::de::Unexpected::Unsigned(value),
+							&self,
+						)),
+					}
+				}
+
+				fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+					match value {
+						UNTAGGED => Ok(Variant(false)),
+						TAGGED => Ok(Variant(true)),
+						_ => Err(E::unknown_variant(value, &[UNTAGGED, TAGGED])),
+					}
+				}
+			}
+
+			deserializer.deserialize_identifier(VariantVisitor)
+		}
+	}
+}
+
+/// Serialize UUID-backed IDs as MessagePack extension types
+///
+/// In binary formats, IDs are serialized as an extension type carrying the 16 bytes of the UUID,
+/// following the extension convention of [rmp-serde](https://docs.rs/rmp-serde). The extension type
+/// is application-assigned, and defaults to [`EXT_TYPE`]. It can be set with
+/// `#[strong_id(ext_type = ...)]`. The prefix is not serialized, and is added back when
+/// deserializing. Deserializing also accepts strings with their prefix, e.g. from peers which don't
+/// use the extension type yet. In human-readable formats, IDs are serialized as strings.
+///
+/// ```rust
+/// use strong_id::strong_uuid;
+///
+/// strong_uuid! {
+///     #[strong_id(serde = "rmp", ext_type = 5)]
+///     pub struct SessionId(Uuid => "session")
+/// }
+///
+/// let session_id = "session_01h455vb4pex5vsknk084sn02q".parse::<SessionId>().unwrap();
+/// let json = serde_json::to_string(&session_id).unwrap();
+/// assert_eq!(json, "\"session_01h455vb4pex5vsknk084sn02q\"");
+/// ```
+#[cfg(feature = "rmp")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rmp")))]
+pub mod rmp {
+	use super::uuid_bytes::{Bytes, UuidBytes, UuidBytesVisitor};
+	use crate::StrongUuid;
+	use core::{fmt::Display, marker::PhantomData, str::FromStr};
+	use serde::{
+		de::{Error, SeqAccess, Visitor},
+		Deserialize, Deserializer, Serializer,
+	};
+
+	/// The default extension type of UUIDs, matching the CBOR tag of UUIDs
+	pub const EXT_TYPE: i8 = 37;
+
+	// rmp-serde represents extension types as a newtype struct with this name, wrapping a tuple of
+	// the type and the data
+	const EXT_STRUCT: &str = "_ExtStruct";
+
+	pub fn serialize<I, S>(id: &I, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		I: StrongUuid + Display,
+		S: Serializer,
+	{
+		serialize_with_ext_type(id, EXT_TYPE, serializer)
+	}
+
+	pub fn deserialize<'de, I, D>(deserializer: D) -> Result<I, D::Error>
+	where
+		I: StrongUuid + FromStr,
+		I::Err: Display,
+		D: Deserializer<'de>,
+	{
+		deserialize_with_ext_type(EXT_TYPE, deserializer)
+	}
+
+	pub fn serialize_with_ext_type<I, S>(
+		id: &I,
+		ext_type: i8,
+		serializer: S,
+	) -> Result<S::Ok, S::Error>
+	where
+		I: StrongUuid + Display,
+		S: Serializer,
+	{
+		if serializer.is_human_readable() {
+			return serializer.collect_str(id);
+		}
+
+		serializer.serialize_newtype_struct(EXT_STRUCT, &(ext_type, Bytes(id.as_bytes())))
+	}
+
+	pub fn deserialize_with_ext_type<'de, I, D>(
+		ext_type: i8,
+		deserializer: D,
+	) -> Result<I, D::Error>
+	where
+		I: StrongUuid + FromStr,
+		I::Err: Display,
+		D: Deserializer<'de>,
+	{
+		if deserializer.is_human_readable() {
+			return String::deserialize(deserializer)?
+				.parse::<I>()
+				.map_err(D::Error::custom);
+		}
+
+		// rmp-serde only reads extension types when asked for `EXT_STRUCT`, which rules out
+		// strings. It also passes extension types to `visit_newtype_struct` when asked for anything.
+		deserializer.deserialize_any(ExtVisitor {
+			ext_type,
+			id: PhantomData,
+		})
+	}
+
+	struct ExtVisitor<I> {
+		ext_type: i8,
+		id: PhantomData<I>,
+	}
+
+	impl<'de, I> Visitor<'de> for ExtVisitor<I>
+	where
+		I: StrongUuid + FromStr,
+		I::Err: Display,
+	{
+		type Value = I;
+
+		fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+			write!(
+				f,
+				"a UUID with MessagePack extension type {}, or a string",
+				self.ext_type
+			)
+		}
+
+		fn visit_newtype_struct<D: Deserializer<'de>>(
+			self,
+			deserializer: D,
+		) -> Result<Self::Value, D::Error> {
+			deserializer.deserialize_any(self)
+		}
+
+		fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+			let ext_type = seq
+				.next_element::<i8>()?
+				.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+			if ext_type != self.ext_type {
+				return Err(A::Error::custom(format!(
+					"expected MessagePack extension type {}, found {ext_type}",
+					self.ext_type
+				)));
+			}
+
+			let bytes = seq
+				.next_element::<UuidBytes>()?
+				.ok_or_else(|| A::Error::invalid_length(1, &self))?;
+			Ok(I::from_u128(u128::from_be_bytes(bytes.0)))
+		}
+
+		fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+			value.parse::<I>().map_err(E::custom)
+		}
+
+		fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+			let bytes = UuidBytesVisitor.visit_bytes(value)?;
+			Ok(I::from_u128(u128::from_be_bytes(bytes)))
+		}
+	}
+}
+
+/// The serde representation of a [`DynamicStrongId`], selected at runtime with [`ReprId`]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum SerdeRepr {
+	/// The encoded ID, e.g. `"user_0343"`, which is how IDs are serialized by default
+	String,
+	/// A struct with separate `prefix` and `suffix` fields, see [`struct_form`]
+	Struct,
+	/// The bytes of the value, see [`Id::to_bytes`]. The prefix is not serialized.
+	RawBytes,
+	/// The bytes of the value as a big-endian integer, which is the value itself for integer IDs,
+	/// signed or not. The prefix is not serialized.
+	Integer,
+}
+
+/// A [`DynamicStrongId`] which is serialized with a [`SerdeRepr`] selected at runtime
+///
+/// Deserializing reads any representation from self-describing formats such as JSON, and records
+/// the representation which was read. This allows storage layers to migrate between
+/// representations gradually, reading data written in an older representation and rewriting it in
+/// the new one. Use a [`ReprSeed`] to supply the prefix of representations which don't include
+/// it, or to read a single representation from formats which aren't self-describing.
+///
+/// ```rust
+/// use strong_id::{
+///     serde_helpers::{ReprId, ReprSeed, SerdeRepr},
+///     DynamicStrongId,
+/// };
+/// use serde::de::DeserializeSeed;
+///
+/// let id = DynamicStrongId::new("user", 3203u16).unwrap();
+///
+/// let written = serde_json::to_string(&ReprId::new(id.clone(), SerdeRepr::Integer)).unwrap();
+/// assert_eq!(written, "3203");
+///
+/// // old data written as a string is still read, and can be rewritten as an integer
+/// let mut deserializer = serde_json::Deserializer::from_str("\"user_0343\"");
+/// let mut read = ReprSeed::new().prefix("user").deserialize(&mut deserializer).unwrap();
+/// assert_eq!(read.repr, SerdeRepr::String);
+/// assert_eq!(read.id, id);
+///
+/// read.repr = SerdeRepr::Integer;
+/// assert_eq!(serde_json::to_string(&read).unwrap(), written);
+///
+/// let mut deserializer = serde_json::Deserializer::from_str(&written);
+/// let read = ReprSeed::new().prefix("user").deserialize(&mut deserializer).unwrap();
+/// assert_eq!(read.repr, SerdeRepr::Integer);
+/// assert_eq!(read.id, id);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ReprId<'p, T: Id> {
+	/// The ID
+	pub id: DynamicStrongId<'p, T>,
+	/// The representation the ID is serialized with
+	pub repr: SerdeRepr,
+}
+
+impl<'p, T: Id> ReprId<'p, T> {
+	/// Serialize an ID with the given representation
+	pub fn new(id: DynamicStrongId<'p, T>, repr: SerdeRepr) -> Self {
+		Self { id, repr }
+	}
+}
+
+impl<'p, T: Id> serde::Serialize for ReprId<'p, T> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self.repr {
+			SerdeRepr::String => serializer.serialize_str(&self.id.to_string()),
+			SerdeRepr::Struct => struct_form::serialize(&self.id, serializer),
+			SerdeRepr::RawBytes => serializer.serialize_bytes(self.id.id().to_bytes().as_ref()),
+			SerdeRepr::Integer => {
+				serialize_integer(self.id.id().to_bytes().as_ref(), T::SIGNED, serializer)
+			}
+		}
+	}
+}
+
+impl<'de, 'p, T: Id> serde::Deserialize<'de> for ReprId<'p, T> {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		ReprSeed::new().deserialize(deserializer)
+	}
+}
+
+/// Deserialize a [`ReprId`] with an expected prefix, or a single representation, see [`ReprId`]
+pub struct ReprSeed<'p, T: Id> {
+	prefix: Option<Prefix<'p>>,
+	repr: Option<SerdeRepr>,
+	_marker: PhantomData<T>,
+}
+
+impl<'p, T: Id> ReprSeed<'p, T> {
+	/// Read any representation of IDs without a prefix
+	pub fn new() -> Self {
+		Self {
+			prefix: None,
+			repr: None,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Expect IDs with the given prefix. The prefix is added to representations which don't
+	/// include it, and checked for representations which do.
+	pub fn prefix<I: Into<Prefix<'p>>>(self, prefix: I) -> Self {
+		Self {
+			prefix: Some(prefix.into()),
+			..self
+		}
+	}
+
+	/// Only read the given representation, e.g. from formats which aren't self-describing
+	pub fn repr(self, repr: SerdeRepr) -> Self {
+		Self {
+			repr: Some(repr),
+			..self
+		}
+	}
+}
+
+impl<'p, T: Id> Default for ReprSeed<'p, T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<'de, 'p, T: Id> DeserializeSeed<'de> for ReprSeed<'p, T> {
+	type Value = ReprId<'p, T>;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		let visitor = ReprVisitor {
+			prefix: self.prefix,
+			_marker: PhantomData,
+		};
+
+		match self.repr {
+			None => deserializer.deserialize_any(visitor),
+			Some(SerdeRepr::String) => deserializer.deserialize_str(visitor),
+			Some(SerdeRepr::Struct) => {
+				deserializer.deserialize_struct("StrongId", &["prefix", "suffix"], visitor)
+			}
+			Some(SerdeRepr::RawBytes) => deserializer.deserialize_bytes(visitor),
+			Some(SerdeRepr::Integer) => {
+				match (T::SIGNED, T::Bytes::default().as_ref().len() <= 8) {
+					(true, true) => deserializer.deserialize_i64(visitor),
+					(true, false) => deserializer.deserialize_i128(visitor),
+					(false, true) => deserializer.deserialize_u64(visitor),
+					(false, false) => deserializer.deserialize_u128(visitor),
+				}
+			}
+		}
+	}
+}
+
+struct ReprVisitor<'p, T> {
+	prefix: Option<Prefix<'p>>,
+	_marker: PhantomData<T>,
+}
+
+impl<'p, T: Id> ReprVisitor<'p, T> {
+	/// Create an ID from a representation which includes the prefix
+	fn parse<E: Error>(self, value: &str, repr: SerdeRepr) -> Result<ReprId<'p, T>, E> {
+		let id = value.parse::<DynamicStrongId<'p, T>>().map_err(E::custom)?;
+
+		match (self.prefix, id.prefix()) {
+			(Some(expected), Some(found)) if expected.as_str() != found => Err(E::custom(
+				crate::Error::InvalidPrefix(expected.to_string(), found.to_string()),
+			)),
+			(Some(expected), None) => {
+				Err(E::custom(crate::Error::MissingPrefix(expected.to_string())))
+			}
+			_ => Ok(ReprId::new(id, repr)),
+		}
+	}
+
+	/// Create an ID from the bytes of a representation which doesn't include the prefix
+	fn decode_bytes<E: Error>(self, bytes: T::Bytes, repr: SerdeRepr) -> Result<ReprId<'p, T>, E> {
+		let value = T::from_bytes(bytes).map_err(E::custom)?;
+		let id = match self.prefix {
+			Some(prefix) => DynamicStrongId::new(prefix, value).map_err(E::custom)?,
+			None => DynamicStrongId::new_plain(value),
+		};
+
+		Ok(ReprId::new(id, repr))
+	}
+}
+
+impl<'de, 'p, T: Id> Visitor<'de> for ReprVisitor<'p, T> {
+	type Value = ReprId<'p, T>;
+
+	fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		f.write_str("a string, a struct with `prefix` and `suffix` fields, bytes or an integer")
+	}
+
+	fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+		self.parse(value, SerdeRepr::String)
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+		let value = struct_form::join_struct_form(map)?;
+		self.parse(&value, SerdeRepr::Struct)
+	}
+
+	fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+		let mut bytes = T::Bytes::default();
+		if bytes.as_ref().len() != value.len() {
+			return Err(E::invalid_length(value.len(), &self));
+		}
+
+		bytes.as_mut().copy_from_slice(value);
+		self.decode_bytes(bytes, SerdeRepr::RawBytes)
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+		let len = T::Bytes::default().as_ref().len();
+		let value = read_byte_seq(seq, len, &self)?;
+		self.visit_bytes(&value)
+	}
+
+	fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+		self.visit_u128(value as u128)
+	}
+
+	fn visit_u128<E: Error>(self, value: u128) -> Result<Self::Value, E> {
+		let bytes = integer_bytes::<T::Bytes>(value, false, T::SIGNED).map_err(E::custom)?;
+		self.decode_bytes(bytes, SerdeRepr::Integer)
+	}
+
+	fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+		self.visit_i128(value as i128)
+	}
+
+	fn visit_i128<E: Error>(self, value: i128) -> Result<Self::Value, E> {
+		let bytes =
+			integer_bytes::<T::Bytes>(value as u128, value < 0, T::SIGNED).map_err(E::custom)?;
+		self.decode_bytes(bytes, SerdeRepr::Integer)
+	}
+}
+
+/// Serialize collections of IDs in sorted order, for deterministic output
+///
+/// Sets and sequences are serialized as sorted arrays, and maps as maps with sorted keys, so that
+/// e.g. `HashSet`s produce the same JSON on every run. Deserializing is unchanged.
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+/// use std::collections::{HashMap, HashSet};
+/// use strong_id::strong_id;
+///
+/// strong_id!(pub struct UserId(u16 => "user"));
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Team {
+///     #[serde(with = "strong_id::serde_helpers::sorted")]
+///     members: HashSet<UserId>,
+///     #[serde(with = "strong_id::serde_helpers::sorted")]
+///     roles: HashMap<UserId, String>,
+/// }
+///
+/// let team = Team {
+///     members: [3, 1, 2].into_iter().map(UserId::from).collect(),
+///     roles: [(2, "admin"), (1, "owner")]
+///         .into_iter()
+///         .map(|(id, role)| (UserId::from(id), role.to_string()))
+///         .collect(),
+/// };
+///
+/// assert_eq!(
+///     serde_json::to_string(&team).unwrap(),
+///     r#"{"members":["user_0001","user_0002","user_0003"],"roles":{"user_0001":"owner","user_0002":"admin"}}"#
+/// );
+/// ```
+pub mod sorted {
+	use serde::{Deserialize, Deserializer, Serialize, Serializer};
+	use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+	/// A collection which can be serialized in sorted order
+	pub trait SortedSerialize {
+		/// Serialize the collection in sorted order
+		fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+	}
+
+	fn serialize_seq<'a, T, I, S>(items: I, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		T: Serialize + Ord + 'a,
+		I: Iterator<Item = &'a T>,
+		S: Serializer,
+	{
+		let mut items = items.collect::<Vec<_>>();
+		items.sort_unstable();
+		serializer.collect_seq(items)
+	}
+
+	fn serialize_map<'a, K, V, I, S>(entries: I, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		K: Serialize + Ord + 'a,
+		V: Serialize + 'a,
+		I: Iterator<Item = (&'a K, &'a V)>,
+		S: Serializer,
+	{
+		let mut entries = entries.collect::<Vec<_>>();
+		entries.sort_unstable_by_key(|&(key, _)| key);
+		serializer.collect_map(entries)
+	}
+
+	impl<T: Serialize + Ord> SortedSerialize for [T] {
+		fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			serialize_seq(self.iter(), serializer)
+		}
+	}
+
+	impl<T: Serialize + Ord> SortedSerialize for Vec<T> {
+		fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			serialize_seq(self.iter(), serializer)
+		}
+	}
+
+	impl<T: Serialize + Ord, H> SortedSerialize for HashSet<T, H> {
+		fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			serialize_seq(self.iter(), serializer)
+		}
+	}
+
+	impl<T: Serialize + Ord> SortedSerialize for BTreeSet<T> {
+		fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			serializer.collect_seq(self)
+		}
+	}
+
+	impl<K: Serialize + Ord, V: Serialize, H> SortedSerialize for HashMap<K, V, H> {
+		fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			serialize_map(self.iter(), serializer)
+		}
+	}
+
+	impl<K: Serialize + Ord, V: Serialize> SortedSerialize for BTreeMap<K, V> {
+		fn serialize_sorted<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			serializer.collect_map(self)
+		}
+	}
+
+	pub fn serialize<C, S>(collection: &C, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		C: SortedSerialize + ?Sized,
+		S: Serializer,
+	{
+		collection.serialize_sorted(serializer)
+	}
+
+	pub fn deserialize<'de, C, D>(deserializer: D) -> Result<C, D::Error>
+	where
+		C: Deserialize<'de>,
+		D: Deserializer<'de>,
+	{
+		C::deserialize(deserializer)
+	}
+}
+
+/// Report where in a document deserializing failed, as a JSON Pointer
+///
+/// Wraps any deserializer and tracks the path to the value being deserialized, e.g. so that an
+/// invalid ID in a large document is reported along with where it was found.
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use strong_id::{serde_helpers::path, strong_id};
+///
+/// strong_id!(pub struct UserId(u16 => "user"));
+///
+/// #[derive(Deserialize)]
+/// struct Item {
+///     owner_id: UserId,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Order {
+///     items: Vec<Item>,
+/// }
+///
+/// let json = r#"{"items":[{"owner_id":"user_0343"},{"owner_id":"team_0343"}]}"#;
+/// let error = path::deserialize::<_, Order>(&mut serde_json::Deserializer::from_str(json))
+///     .err()
+///     .unwrap();
+///
+/// assert_eq!(error.path().to_string(), "/items/1/owner_id");
+/// assert!(error.to_string().ends_with(" at /items/1/owner_id"));
+/// ```
+#[cfg(feature = "serde-path")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde-path")))]
+pub mod path {
+	use core::{
+		cell::RefCell,
+		fmt::{self, Display},
+	};
+	use serde::de::{
+		self, Deserialize, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess,
+		VariantAccess, Visitor,
+	};
+
+	/// Deserialize a value, reporting the path to the value which failed along with the error
+	pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, PathError<D::Error>>
+	where
+		D: Deserializer<'de>,
+		T: Deserialize<'de>,
+	{
+		let track = Track::default();
+		T::deserialize(Wrap::new(deserializer, &track, false)).map_err(|error| PathError {
+			path: track.failed.into_inner().unwrap_or_default(),
+			error,
+		})
+	}
+
+	/// A segment of a [`Path`]
+	#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+	pub enum Segment {
+		/// An element of a sequence
+		Index(usize),
+		/// A field of a struct, an entry of a map or a variant of an enum
+		Key(String),
+		/// An entry of a map whose key is not a string or a number
+		Unknown,
+	}
+
+	/// The path to a value in a document, displayed as a JSON Pointer, e.g. `/items/3/owner_id`
+	///
+	/// The path to the root of a document is empty.
+	#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+	pub struct Path {
+		segments: Vec<Segment>,
+	}
+
+	impl Path {
+		/// The segments of the path, from the root of the document
+		pub fn segments(&self) -> &[Segment] {
+			&self.segments
+		}
+	}
+
+	impl Display for Path {
+		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			for segment in &self.segments {
+				match segment {
+					Segment::Index(index) => write!(f, "/{index}")?,
+					Segment::Key(key) => {
+						write!(f, "/{}", key.replace('~', "~0").replace('/', "~1"))?
+					}
+					Segment::Unknown => f.write_str("/?")?,
+				}
+			}
+			Ok(())
+		}
+	}
+
+	/// An error of the wrapped deserializer, along with the path to the value which failed
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	pub struct PathError<E> {
+		path: Path,
+		error: E,
+	}
+
+	impl<E> PathError<E> {
+		/// The path to the value which failed
+		pub fn path(&self) -> &Path {
+			&self.path
+		}
+
+		/// The error of the wrapped deserializer
+		pub fn inner(&self) -> &E {
+			&self.error
+		}
+
+		/// Consume the error and return the error of the wrapped deserializer
+		pub fn into_inner(self) -> E {
+			self.error
+		}
+	}
+
+	impl<E: Display> Display for PathError<E> {
+		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			if self.path.segments.is_empty() {
+				self.error.fmt(f)
+			} else {
+				write!(f, "{} at {}", self.error, self.path)
+			}
+		}
+	}
+
+	impl<E: std::error::Error + 'static> std::error::Error for PathError<E> {
+		fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+			Some(&self.error)
+		}
+	}
+
+	/// The path to the value being deserialized, and the path to the first value which failed
+	#[derive(Default)]
+	struct Track {
+		path: RefCell<Vec<Segment>>,
+		/// The last map key or enum variant, which becomes a segment of its value
+		key: RefCell<Option<String>>,
+		failed: RefCell<Option<Path>>,
+	}
+
+	impl Track {
+		fn take_key(&self) -> Segment {
+			self.key
+				.borrow_mut()
+				.take()
+				.map_or(Segment::Unknown, Segment::Key)
+		}
+
+		fn enter<R>(&self, segment: Segment, f: impl FnOnce() -> R) -> R {
+			self.path.borrow_mut().push(segment);
+			let result = f();
+			self.path.borrow_mut().pop();
+			result
+		}
+
+		/// Record the current path, unless a value deeper in the document already failed
+		fn fail<E>(&self, error: E) -> E {
+			let mut failed = self.failed.borrow_mut();
+			if failed.is_none() {
+				*failed = Some(Path {
+					segments: self.path.borrow().clone(),
+				});
+			}
+			error
+		}
+	}
+
+	struct Wrap<'a, D> {
+		delegate: D,
+		track: &'a Track,
+		/// Whether the value is a map key or an enum variant, which is recorded in the track
+		key: bool,
+	}
+
+	impl<'a, D> Wrap<'a, D> {
+		fn new(delegate: D, track: &'a Track, key: bool) -> Self {
+			Self {
+				delegate,
+				track,
+				key,
+			}
+		}
+	}
+
+	macro_rules! forward_deserialize {
+		($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+			$(
+				fn $method<V: Visitor<'de>>(
+					self,
+					$($arg: $ty,)*
+					visitor: V,
+				) -> Result<V::Value, D::Error> {
+					let track = self.track;
+					self.delegate
+						.$method($($arg,)* WrapVisitor::new(visitor, track, self.key))
+						.map_err(|error| track.fail(error))
+				}
+			)*
+		};
+	}
+
+	impl<'a, 'de, D: Deserializer<'de>> Deserializer<'de> for Wrap<'a, D> {
+		type Error = D::Error;
+
+		forward_deserialize! {
+			deserialize_any();
+			deserialize_bool();
+			deserialize_i8();
+			deserialize_i16();
+			deserialize_i32();
+			deserialize_i64();
+			deserialize_i128();
+			deserialize_u8();
+			deserialize_u16();
+			deserialize_u32();
+			deserialize_u64();
+			deserialize_u128();
+			deserialize_f32();
+			deserialize_f64();
+			deserialize_char();
+			deserialize_str();
+			deserialize_string();
+			deserialize_bytes();
+			deserialize_byte_buf();
+			deserialize_option();
+			deserialize_unit();
+			deserialize_unit_struct(name: &'static str);
+			deserialize_newtype_struct(name: &'static str);
+			deserialize_seq();
+			deserialize_tuple(len: usize);
+			deserialize_tuple_struct(name: &'static str, len: usize);
+			deserialize_map();
+			deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+			deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+			deserialize_identifier();
+			deserialize_ignored_any();
+		}
+
+		fn is_human_readable(&self) -> bool {
+			self.delegate.is_human_readable()
+		}
+	}
+
+	struct WrapVisitor<'a, X> {
+		delegate: X,
+		track: &'a Track,
+		key: bool,
+	}
+
+	impl<'a, X> WrapVisitor<'a, X> {
+		fn new(delegate: X, track: &'a Track, key: bool) -> Self {
+			Self {
+				delegate,
+				track,
+				key,
+			}
+		}
+
+		fn record_key(&self, key: &dyn Display) {
+			if self.key {
+				*self.track.key.borrow_mut() = Some(key.to_string());
+			}
+		}
+	}
+
+	macro_rules! forward_visit {
+		($($method:ident($ty:ty);)*) => {
+			$(
+				fn $method<E: de::Error>(self, v: $ty) -> Result<X::Value, E> {
+					self.record_key(&v);
+					self.delegate.$method(v)
+				}
+			)*
+		};
+	}
+
+	macro_rules! forward_visit_bytes {
+		($($method:ident($ty:ty);)*) => {
+			$(
+				fn $method<E: de::Error>(self, v: $ty) -> Result<X::Value, E> {
+					self.delegate.$method(v)
+				}
+			)*
+		};
+	}
+
+	impl<'a, 'de, X: Visitor<'de>> Visitor<'de> for WrapVisitor<'a, X> {
+		type Value = X::Value;
+
+		fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			self.delegate.expecting(f)
+		}
+
+		forward_visit! {
+			visit_bool(bool);
+			visit_i8(i8);
+			visit_i16(i16);
+			visit_i32(i32);
+			visit_i64(i64);
+			visit_i128(i128);
+			visit_u8(u8);
+			visit_u16(u16);
+			visit_u32(u32);
+			visit_u64(u64);
+			visit_u128(u128);
+			visit_f32(f32);
+			visit_f64(f64);
+			visit_char(char);
+			visit_str(&str);
+			visit_borrowed_str(&'de str);
+			visit_string(String);
+		}
+
+		forward_visit_bytes! {
+			visit_bytes(&[u8]);
+			visit_borrowed_bytes(&'de [u8]);
+			visit_byte_buf(Vec<u8>);
+		}
+
+		fn visit_none<E: de::Error>(self) -> Result<X::Value, E> {
+			self.delegate.visit_none()
+		}
+
+		fn visit_unit<E: de::Error>(self) -> Result<X::Value, E> {
+			self.delegate.visit_unit()
+		}
+
+		fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<X::Value, D::Error> {
+			self.delegate
+				.visit_some(Wrap::new(deserializer, self.track, false))
+		}
+
+		fn visit_newtype_struct<D: Deserializer<'de>>(
+			self,
+			deserializer: D,
+		) -> Result<X::Value, D::Error> {
+			self.delegate
+				.visit_newtype_struct(Wrap::new(deserializer, self.track, false))
+		}
+
+		fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<X::Value, A::Error> {
+			self.delegate.visit_seq(WrapSeq {
+				delegate: seq,
+				track: self.track,
+				index: 0,
+			})
+		}
+
+		fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<X::Value, A::Error> {
+			self.delegate.visit_map(WrapMap {
+				delegate: map,
+				track: self.track,
+			})
+		}
+
+		fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<X::Value, A::Error> {
+			self.delegate.visit_enum(WrapEnum {
+				delegate: data,
+				track: self.track,
+			})
+		}
+	}
+
+	/// Deserializes a value with a wrapped deserializer, recording the path if it fails
+	struct WrapSeed<'a, X> {
+		delegate: X,
+		track: &'a Track,
+		key: bool,
+	}
+
+	impl<'a, 'de, X: DeserializeSeed<'de>> DeserializeSeed<'de> for WrapSeed<'a, X> {
+		type Value = X::Value;
+
+		fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<X::Value, D::Error> {
+			let track = self.track;
+			self.delegate
+				.deserialize(Wrap::new(deserializer, track, self.key))
+				.map_err(|error| track.fail(error))
+		}
+	}
+
+	struct WrapSeq<'a, A> {
+		delegate: A,
+		track: &'a Track,
+		index: usize,
+	}
+
+	impl<'a, 'de, A: SeqAccess<'de>> SeqAccess<'de> for WrapSeq<'a, A> {
+		type Error = A::Error;
+
+		fn next_element_seed<T: DeserializeSeed<'de>>(
+			&mut self,
+			seed: T,
+		) -> Result<Option<T::Value>, A::Error> {
+			let track = self.track;
+			let element = track.enter(Segment::Index(self.index), || {
+				self.delegate
+					.next_element_seed(WrapSeed {
+						delegate: seed,
+						track,
+						key: false,
+					})
+					.map_err(|error| track.fail(error))
+			});
+			self.index += 1;
+			element
+		}
+
+		fn size_hint(&self) -> Option<usize> {
+			self.delegate.size_hint()
+		}
+	}
+
+	struct WrapMap<'a, A> {
+		delegate: A,
+		track: &'a Track,
+	}
+
+	impl<'a, 'de, A: MapAccess<'de>> MapAccess<'de> for WrapMap<'a, A> {
+		type Error = A::Error;
+
+		fn next_key_seed<K: DeserializeSeed<'de>>(
+			&mut self,
+			seed: K,
+		) -> Result<Option<K::Value>, A::Error> {
+			let track = self.track;
+			self.delegate
+				.next_key_seed(WrapSeed {
+					delegate: seed,
+					track,
+					key: true,
+				})
+				.map_err(|error| track.fail(error))
+		}
+
+		fn next_value_seed<V: DeserializeSeed<'de>>(
+			&mut self,
+			seed: V,
+		) -> Result<V::Value, A::Error> {
+			let track = self.track;
+			track.enter(track.take_key(), || {
+				self.delegate
+					.next_value_seed(WrapSeed {
+						delegate: seed,
+						track,
+						key: false,
+					})
+					.map_err(|error| track.fail(error))
+			})
+		}
+
+		fn size_hint(&self) -> Option<usize> {
+			self.delegate.size_hint()
+		}
+	}
+
+	struct WrapEnum<'a, A> {
+		delegate: A,
+		track: &'a Track,
+	}
+
+	impl<'a, 'de, A: EnumAccess<'de>> EnumAccess<'de> for WrapEnum<'a, A> {
+		type Error = A::Error;
+		type Variant = WrapVariant<'a, A::Variant>;
+
+		fn variant_seed<V: DeserializeSeed<'de>>(
+			self,
+			seed: V,
+		) -> Result<(V::Value, Self::Variant), A::Error> {
+			let track = self.track;
+			let (value, variant) = self
+				.delegate
+				.variant_seed(WrapSeed {
+					delegate: seed,
+					track,
+					key: true,
+				})
+				.map_err(|error| track.fail(error))?;
+
+			Ok((
+				value,
+				WrapVariant {
+					delegate: variant,
+					track,
+					segment: track.take_key(),
+				},
+			))
+		}
+	}
+
+	struct WrapVariant<'a, A> {
+		delegate: A,
+		track: &'a Track,
+		segment: Segment,
+	}
+
+	impl<'a, 'de, A: VariantAccess<'de>> VariantAccess<'de> for WrapVariant<'a, A> {
+		type Error = A::Error;
+
+		fn unit_variant(self) -> Result<(), A::Error> {
+			let track = self.track;
+			self.delegate
+				.unit_variant()
+				.map_err(|error| track.fail(error))
+		}
+
+		fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+			self,
+			seed: T,
+		) -> Result<T::Value, A::Error> {
+			let (delegate, track) = (self.delegate, self.track);
+			track.enter(self.segment, || {
+				delegate
+					.newtype_variant_seed(WrapSeed {
+						delegate: seed,
+						track,
+						key: false,
+					})
+					.map_err(|error| track.fail(error))
+			})
+		}
+
+		fn tuple_variant<V: Visitor<'de>>(
+			self,
+			len: usize,
+			visitor: V,
+		) -> Result<V::Value, A::Error> {
+			let (delegate, track) = (self.delegate, self.track);
+			track.enter(self.segment, || {
+				delegate
+					.tuple_variant(len, WrapVisitor::new(visitor, track, false))
+					.map_err(|error| track.fail(error))
+			})
+		}
+
+		fn struct_variant<V: Visitor<'de>>(
+			self,
+			fields: &'static [&'static str],
+			visitor: V,
+		) -> Result<V::Value, A::Error> {
+			let (delegate, track) = (self.delegate, self.track);
+			track.enter(self.segment, || {
+				delegate
+					.struct_variant(fields, WrapVisitor::new(visitor, track, false))
+					.map_err(|error| track.fail(error))
+			})
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ReprId, ReprSeed, SerdeRepr};
+	use crate::{DynamicStrongId, Error};
+	use serde::de::DeserializeSeed;
+
+	fn read(json: &str, seed: ReprSeed<'static, u32>) -> Result<ReprId<'static, u32>, String> {
+		let mut deserializer = serde_json::Deserializer::from_str(json);
+		seed.deserialize(&mut deserializer)
+			.map_err(|error| error.to_string())
+	}
+
+	#[test]
+	fn repr() {
+		let id = DynamicStrongId::new("user", 301u32).unwrap();
+
+		for (repr, json) in [
+			(SerdeRepr::String, r#""user_000009d""#),
+			(SerdeRepr::Struct, r#"{"prefix":"user","suffix":"000009d"}"#),
+			(SerdeRepr::RawBytes, "[0,0,1,45]"),
+			(SerdeRepr::Integer, "301"),
+		] {
+			let written = ReprId::new(id.clone(), repr);
+			assert_eq!(serde_json::to_string(&written).unwrap(), json);
+
+			assert_eq!(
+				read(json, ReprSeed::new().prefix("user")),
+				Ok(written.clone())
+			);
+			assert_eq!(
+				read(json, ReprSeed::new().prefix("user").repr(repr)),
+				Ok(written)
+			);
+		}
+
+		let plain = serde_json::from_str::<ReprId<u32>>("301").unwrap();
+		assert_eq!(plain.id, DynamicStrongId::new_plain(301));
+	}
+
+	#[test]
+	fn repr_errors() {
+		assert!(read(r#""team_000009d""#, ReprSeed::new().prefix("user"))
+			.unwrap_err()
+			.starts_with(&Error::InvalidPrefix("user".into(), "team".into()).to_string()));
+		assert!(read(r#""000009d""#, ReprSeed::new().prefix("user"))
+			.unwrap_err()
+			.starts_with(&Error::MissingPrefix("user".into()).to_string()));
+		assert!(read("4294967296", ReprSeed::new())
+			.unwrap_err()
+			.starts_with(&Error::OutOfRange.to_string()));
+		assert!(read("[0,1,45]", ReprSeed::new())
+			.unwrap_err()
+			.starts_with("invalid length 3"));
+		assert!(read("301", ReprSeed::new().repr(SerdeRepr::String)).is_err());
+	}
+
+	#[cfg(feature = "rmp")]
+	#[test]
+	fn rmp_human_readable() {
+		crate::strong_uuid! {
+			#[strong_id(serde = "rmp")]
+			pub struct SessionId(Uuid => "session")
+		}
+
+		let session_id = SessionId::from(uuid::Uuid::from_u128(301));
+		let json = serde_json::to_string(&session_id).unwrap();
+		assert_eq!(json, r#""session_0000000000000000000000009d""#);
+		assert_eq!(
+			serde_json::from_str::<SessionId>(&json).unwrap(),
+			session_id
+		);
+	}
+
+	#[cfg(feature = "rmp")]
+	#[test]
+	fn rmp_roundtrip() {
+		crate::strong_uuid! {
+			#[strong_id(serde = "rmp")]
+			pub struct SessionId(Uuid => "session")
+		}
+		crate::strong_uuid! {
+			#[strong_id(serde = "rmp", ext_type = 5)]
+			pub struct TraceId(Uuid)
+		}
+
+		let session_id = SessionId::from(uuid::Uuid::from_u128(301));
+		let msgpack = rmp_serde::to_vec(&session_id).unwrap();
+
+		// fixext 16, followed by the extension type and the bytes of the UUID
+		let mut expected = vec![0xd8, 37];
+		expected.extend_from_slice(&301u128.to_be_bytes());
+		assert_eq!(msgpack, expected);
+		assert_eq!(
+			rmp_serde::from_slice::<SessionId>(&msgpack).unwrap(),
+			session_id
+		);
+
+		let msgpack = rmp_serde::to_vec("session_0000000000000000000000009d").unwrap();
+		assert_eq!(
+			rmp_serde::from_slice::<SessionId>(&msgpack).unwrap(),
+			session_id
+		);
+
+		let trace_id = TraceId::from(uuid::Uuid::from_u128(301));
+		let msgpack = rmp_serde::to_vec(&trace_id).unwrap();
+		assert_eq!(msgpack[..2], [0xd8, 5]);
+		assert_eq!(
+			rmp_serde::from_slice::<TraceId>(&msgpack).unwrap(),
+			trace_id
+		);
+		assert!(rmp_serde::from_slice::<SessionId>(&msgpack).is_err());
+	}
+
+	#[cfg(feature = "arrow")]
+	#[test]
+	fn arrow_bytes() {
+		crate::strong_uuid!(pub struct DeviceId(Uuid => "device"));
+
+		#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+		struct Row {
+			#[serde(with = "super::arrow")]
+			device_id: DeviceId,
+		}
+
+		let row = Row {
+			device_id: DeviceId::from(uuid::Uuid::from_u128(301)),
+		};
+		let json = serde_json::to_string(&row).unwrap();
+		assert_eq!(json, r#"{"device_id":[0,0,0,0,0,0,0,0,0,0,0,0,0,0,1,45]}"#);
+		assert_eq!(serde_json::from_str::<Row>(&json).unwrap(), row);
+		assert!(serde_json::from_str::<Row>(r#"{"device_id":[1,45]}"#).is_err());
+
+		let field =
+			serde_json::to_string(&super::arrow::uuid_field("device_id").nullable()).unwrap();
+		assert_eq!(
+			field,
+			r#"{"name":"device_id","data_type":"FixedSizeBinary(16)","nullable":true,"metadata":{"ARROW:extension:name":"arrow.uuid"}}"#
+		);
+	}
+
+	#[test]
+	fn inner_forms() {
+		use crate::strong_id;
+
+		strong_id! {
+			#[strong_id(serde = "typeid")]
+			pub struct UserId(u16 => "user")
+		}
+		strong_id! {
+			#[strong_id(serde = "bytes")]
+			pub struct TeamId(u16 => "team")
+		}
+		strong_id! {
+			#[strong_id(serde = "integer")]
+			pub struct OrderId(u8 => "order")
+		}
+		strong_id! {
+			#[strong_id(serde = "integer")]
+			pub struct EventId(u128 => "event")
+		}
+
+		assert_eq!(
+			serde_json::to_string(&UserId::from(3203)).unwrap(),
+			r#""user_0343""#
+		);
+
+		let team_id = TeamId::from(3203);
+		assert_eq!(serde_json::to_string(&team_id).unwrap(), "[12,131]");
+		assert_eq!(serde_json::from_str::<TeamId>("[12,131]").unwrap(), team_id);
+		assert!(serde_json::from_str::<TeamId>("[12]").is_err());
+		assert!(serde_json::from_str::<TeamId>(r#""user_0343""#).is_err());
+
+		let order_id = OrderId::from(255);
+		assert_eq!(serde_json::to_string(&order_id).unwrap(), "255");
+		assert_eq!(serde_json::from_str::<OrderId>("255").unwrap(), order_id);
+		assert_eq!(
+			serde_json::from_str::<OrderId>(r#""order_7z""#).unwrap(),
+			order_id
+		);
+		assert!(serde_json::from_str::<OrderId>("256").is_err());
+		assert!(serde_json::from_str::<OrderId>("-1").is_err());
+
+		let event_id = EventId::from(u64::MAX as u128);
+		assert_eq!(
+			serde_json::to_string(&event_id).unwrap(),
+			u64::MAX.to_string()
+		);
+		assert_eq!(
+			serde_json::from_str::<EventId>(&u64::MAX.to_string()).unwrap(),
+			event_id
+		);
+	}
+
+	#[test]
+	fn signed_integer_forms() {
+		use crate::strong_id;
+
+		strong_id! {
+			#[strong_id(serde = "integer")]
+			pub struct BalanceId(i32 => "balance")
+		}
+		strong_id! {
+			#[strong_id(serde = "integer")]
+			pub struct OffsetId(i128 => "offset")
+		}
+
+		for (value, json) in [(5, "5"), (-1, "-1"), (i32::MIN, "-2147483648")] {
+			let balance_id = BalanceId::from(value);
+			assert_eq!(serde_json::to_string(&balance_id).unwrap(), json);
+			assert_eq!(serde_json::from_str::<BalanceId>(json).unwrap(), balance_id);
+		}
+		assert!(serde_json::from_str::<BalanceId>("2147483648").is_err());
+		assert!(serde_json::from_str::<BalanceId>("-2147483649").is_err());
+
+		let offset_id = OffsetId::from(i64::MIN as i128);
+		let json = i64::MIN.to_string();
+		assert_eq!(serde_json::to_string(&offset_id).unwrap(), json);
+		assert_eq!(serde_json::from_str::<OffsetId>(&json).unwrap(), offset_id);
+
+		let id = DynamicStrongId::new("balance", -301i32).unwrap();
+		let written = ReprId::new(id, SerdeRepr::Integer);
+		assert_eq!(serde_json::to_string(&written).unwrap(), "-301");
+		let mut deserializer = serde_json::Deserializer::from_str("-301");
+		assert_eq!(
+			ReprSeed::new()
+				.prefix("balance")
+				.deserialize(&mut deserializer)
+				.unwrap(),
+			written
+		);
+	}
+
+	#[test]
+	fn long_byte_sequences() {
+		let long = format!("[{}]", ["0"; 64].join(","));
+		assert!(serde_json::from_str::<ReprId<u32>>(&long).is_err());
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn uuid_form() {
+		crate::strong_uuid! {
+			#[strong_id(serde = "uuid")]
+			pub struct DeviceId(Uuid => "device")
+		}
+
+		let device_id = DeviceId::from(uuid::Uuid::from_u128(301));
+		let json = serde_json::to_string(&device_id).unwrap();
+		assert_eq!(json, r#""00000000-0000-0000-0000-00000000012d""#);
+		assert_eq!(serde_json::from_str::<DeviceId>(&json).unwrap(), device_id);
+		assert_eq!(
+			serde_json::from_str::<DeviceId>(r#""0000000000000000000000000000012d""#).unwrap(),
+			device_id
+		);
+		assert_eq!(
+			serde_json::from_str::<DeviceId>(r#""device_0000000000000000000000009d""#).unwrap(),
+			device_id
+		);
+		assert!(serde_json::from_str::<DeviceId>(r#""team_0000000000000000000000009d""#).is_err());
+	}
+
+	#[cfg(feature = "serde-path")]
+	#[test]
+	fn path() {
+		use super::path::{self, PathError, Segment};
+		use serde::Deserialize;
+		use std::collections::BTreeMap;
+
+		crate::strong_id!(pub struct UserId(u16 => "user"));
+
+		#[derive(Debug, Deserialize)]
+		#[allow(dead_code)]
+		enum Owner {
+			User(UserId),
+			Team { lead_id: UserId },
+		}
+
+		#[derive(Debug, Deserialize)]
+		#[allow(dead_code)]
+		struct Item {
+			owner_id: Option<UserId>,
+			owner: Option<Owner>,
+			#[serde(default)]
+			roles: BTreeMap<String, UserId>,
+		}
+
+		#[derive(Debug, Deserialize)]
+		#[allow(dead_code)]
+		struct Order {
+			items: Vec<Item>,
+		}
+
+		fn read(json: &str) -> PathError<serde_json::Error> {
+			path::deserialize::<_, Order>(&mut serde_json::Deserializer::from_str(json))
+				.unwrap_err()
+		}
+
+		let error = read(
+			r#"{"items":[{"owner_id":null,"owner":null},{"owner_id":"team_0343","owner":null}]}"#,
+		);
+		assert_eq!(error.path().to_string(), "/items/1/owner_id");
+		assert_eq!(
+			error.path().segments(),
+			[
+				Segment::Key("items".into()),
+				Segment::Index(1),
+				Segment::Key("owner_id".into())
+			]
+		);
+		assert_eq!(
+			error.to_string(),
+			format!("{} at /items/1/owner_id", error.inner())
+		);
+
+		let error =
+			read(r#"{"items":[{"owner_id":null,"owner":{"Team":{"lead_id":"user_034"}}}]}"#);
+		assert_eq!(error.path().to_string(), "/items/0/owner/Team/lead_id");
+
+		let error = read(r#"{"items":[{"owner_id":null,"owner":{"User":"user_03u3"}}]}"#);
+		assert_eq!(error.path().to_string(), "/items/0/owner/User");
+
+		let error = read(
+			r#"{"items":[{"owner_id":null,"owner":null,"roles":{"admins/owners~":"team_0343"}}]}"#,
+		);
+		assert_eq!(error.path().to_string(), "/items/0/roles/admins~1owners~0");
+
+		let error = read(r#"{"items":[5]}"#);
+		assert_eq!(error.path().to_string(), "/items/0");
+
+		let error = read(r#"[]"#);
+		assert!(error.path().segments().is_empty());
+		assert_eq!(error.to_string(), error.inner().to_string());
+
+		let order: Order = path::deserialize(&mut serde_json::Deserializer::from_str(
+			r#"{"items":[{"owner_id":"user_0343","owner":{"User":"user_0001"}}]}"#,
+		))
+		.unwrap();
+		assert_eq!(order.items[0].owner_id, Some(UserId::from(3203)));
+	}
+
+	#[cfg(feature = "cbor")]
+	#[test]
+	fn cbor_human_readable() {
+		crate::strong_uuid! {
+			#[strong_id(serde = "cbor")]
+			pub struct DeviceId(Uuid => "device")
+		}
+
+		let device_id = DeviceId::from(uuid::Uuid::from_u128(301));
+		let json = serde_json::to_string(&device_id).unwrap();
+		assert_eq!(json, r#""device_0000000000000000000000009d""#);
+		assert_eq!(serde_json::from_str::<DeviceId>(&json).unwrap(), device_id);
+	}
+
+	#[cfg(feature = "cbor")]
+	#[test]
+	fn cbor_roundtrip() {
+		crate::strong_uuid! {
+			#[strong_id(serde = "cbor")]
+			pub struct DeviceId(Uuid => "device")
+		}
+
+		let device_id = DeviceId::from(uuid::Uuid::from_u128(301));
+		let mut cbor = Vec::new();
+		ciborium::into_writer(&device_id, &mut cbor).unwrap();
+
+		// tag 37, followed by a 16-byte byte string
+		let mut expected = vec![0xd8, 37, 0x50];
+		expected.extend_from_slice(&301u128.to_be_bytes());
+		assert_eq!(cbor, expected);
+		assert_eq!(
+			ciborium::from_reader::<DeviceId, _>(cbor.as_slice()).unwrap(),
+			device_id
+		);
+
+		let untagged = &expected[2..];
+		assert_eq!(
+			ciborium::from_reader::<DeviceId, _>(untagged).unwrap(),
+			device_id
+		);
+
+		let mut other_tag = expected.clone();
+		other_tag[1] = 38;
+		assert!(ciborium::from_reader::<DeviceId, _>(other_tag.as_slice()).is_err());
+	}
+}
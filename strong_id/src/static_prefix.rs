@@ -0,0 +1,309 @@
+use crate::{
+	dynamic::map_prefix, global::Global, DynamicStrongIdBuf, ErasedStrongId, Error, Id, Prefix,
+	StrongId,
+};
+use core::{
+	fmt::{Display, Formatter},
+	str::FromStr,
+};
+
+/// The prefixes registered for parsing [`StaticPrefixId`]s, which are only ever added to
+static REGISTERED: Global<Vec<&'static str>> = Global::new();
+
+/// Register prefixes which [`StaticPrefixId`]s are parsed with, e.g. the prefixes of the plugins
+/// loaded at startup
+///
+/// Prefixes are validated, and are added to those already registered. The registered prefixes are
+/// global to the process, and can't be unregistered. Registering prefixes is intended to be done
+/// once at startup, as every call copies the list and leaks the previous one, which may still be
+/// read by a concurrent parse.
+pub fn register_static_prefixes<I>(prefixes: I) -> Result<(), Error>
+where
+	I: IntoIterator<Item = &'static str>,
+{
+	let mut added = Vec::new();
+	for prefix in prefixes {
+		map_prefix(prefix)?;
+		added.push(prefix);
+	}
+
+	REGISTERED.update(|current| {
+		let mut registered = current.cloned().unwrap_or_default();
+		for prefix in &added {
+			if !registered.contains(prefix) {
+				registered.push(prefix);
+			}
+		}
+		registered
+	});
+
+	Ok(())
+}
+
+/// The prefixes registered with [`register_static_prefixes`]
+pub fn registered_static_prefixes() -> &'static [&'static str] {
+	REGISTERED.get().map_or(&[], Vec::as_slice)
+}
+
+/// A StrongId whose prefix is chosen at runtime from a fixed set of `'static` prefixes
+///
+/// This is a middle ground between generated StrongIds, whose prefix is chosen at compile-time,
+/// and [`DynamicStrongId`](crate::DynamicStrongId)s, whose prefix may be any string. As the prefix
+/// is a `&'static str`, the ID has no lifetime and is `Copy` when its inner value is, e.g. for
+/// plugin systems which register a handful of prefixes at startup.
+///
+/// IDs are parsed with the prefixes registered with [`register_static_prefixes`], or with a given
+/// set of prefixes with [`parse_with_prefixes`](Self::parse_with_prefixes). Parsing fails with
+/// [`Error::UnknownPrefix`] for any other prefix.
+///
+/// ```rust
+/// use strong_id::{register_static_prefixes, Error, StaticPrefixId};
+///
+/// register_static_prefixes(["audio", "video"]).unwrap();
+///
+/// let id = "audio_0343".parse::<StaticPrefixId<u16>>().unwrap();
+/// assert_eq!(id.prefix(), "audio");
+/// assert_eq!(id, StaticPrefixId::new("audio", 3203).unwrap());
+///
+/// let copy = id;
+/// assert_eq!(copy.to_string(), "audio_0343");
+///
+/// assert_eq!(
+///     "image_0343".parse::<StaticPrefixId<u16>>(),
+///     Err(Error::UnknownPrefix("image".into()))
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct StaticPrefixId<T: Id> {
+	prefix: &'static str,
+	suffix: T,
+}
+
+impl<T: Id> StaticPrefixId<T> {
+	/// Create a new ID from a given value with a prefix
+	///
+	/// The prefix is validated, but does not need to be registered.
+	pub fn new(prefix: &'static str, value: T) -> Result<Self, Error> {
+		map_prefix(prefix)?;
+		Ok(Self {
+			prefix,
+			suffix: value,
+		})
+	}
+
+	/// Parse an ID whose prefix is one of the given prefixes
+	///
+	/// Every prefix is validated, and parsing fails if any of them is invalid.
+	///
+	/// ```rust
+	/// use strong_id::StaticPrefixId;
+	///
+	/// const PREFIXES: &[&str] = &["user", "team"];
+	///
+	/// let id = StaticPrefixId::<u16>::parse_with_prefixes(PREFIXES, "team_0343").unwrap();
+	/// assert_eq!(id.prefix(), "team");
+	/// assert!(StaticPrefixId::<u16>::parse_with_prefixes(PREFIXES, "order_0343").is_err());
+	/// ```
+	pub fn parse_with_prefixes(prefixes: &[&'static str], value: &str) -> Result<Self, Error> {
+		for prefix in prefixes {
+			map_prefix(*prefix)?;
+		}
+
+		Self::parse_with_valid_prefixes(prefixes, value)
+	}
+
+	/// Parse an ID whose prefix is one of the given prefixes, which were already validated
+	fn parse_with_valid_prefixes(prefixes: &[&'static str], value: &str) -> Result<Self, Error> {
+		let canonical = crate::_internal_canonicalize(value);
+
+		match canonical.rsplit_once('_') {
+			None => Err(Error::UnknownPrefix(String::new())),
+			Some((parsed_prefix, suffix)) => {
+				crate::_internal_check_delimiters(parsed_prefix)?;
+
+				let prefix = match prefixes.iter().find(|prefix| **prefix == parsed_prefix) {
+					Some(prefix) => *prefix,
+					None => return Err(Error::UnknownPrefix(parsed_prefix.to_string())),
+				};
+
+				Ok(Self {
+					prefix,
+					suffix: T::decode(suffix)
+						.map_err(|error| error._internal_offset(prefix.len() + 1))?,
+				})
+			}
+		}
+	}
+
+	/// The prefix of the ID
+	pub fn prefix(&self) -> &'static str {
+		self.prefix
+	}
+
+	/// The encoded suffix of the ID, without the prefix and separator
+	pub fn suffix_encoded(&self) -> String {
+		self.suffix.encode()
+	}
+
+	/// Consume the ID and return the inner value
+	pub fn into_inner(self) -> T {
+		self.suffix
+	}
+
+	/// Consume the ID and return its prefix and inner value
+	pub fn into_parts(self) -> (&'static str, T) {
+		(self.prefix, self.suffix)
+	}
+}
+
+impl<T: Id> From<StaticPrefixId<T>> for DynamicStrongIdBuf<T> {
+	fn from(value: StaticPrefixId<T>) -> Self {
+		// the prefix was validated when the ID was created
+		match DynamicStrongIdBuf::new(
+			Prefix::new_unchecked(value.prefix).allow_reserved(),
+			value.suffix,
+		) {
+			Ok(id) => id,
+			Err(_) => unreachable!("prefix was validated"),
+		}
+	}
+}
+
+impl<T: Id> Display for StaticPrefixId<T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		crate::_internal_fmt_canonical(f, |f| {
+			f.write_str(self.prefix)?;
+			f.write_str("_")?;
+
+			let mut suffix = self.suffix.encode();
+			if f.alternate() {
+				suffix.make_ascii_uppercase();
+			}
+			f.write_str(&suffix)
+		})
+	}
+}
+
+impl<T: Id> FromStr for StaticPrefixId<T> {
+	type Err = Error;
+
+	/// Parse an ID whose prefix was registered with [`register_static_prefixes`]
+	fn from_str(value: &str) -> Result<Self, Self::Err> {
+		// registered prefixes are validated when they are registered
+		Self::parse_with_valid_prefixes(registered_static_prefixes(), value)
+	}
+}
+
+impl<T: Id> TryFrom<&str> for StaticPrefixId<T> {
+	type Error = Error;
+
+	fn try_from(value: &str) -> Result<Self, Self::Error> {
+		value.parse()
+	}
+}
+
+impl<T: Id> StrongId<T> for StaticPrefixId<T> {
+	fn prefix(&self) -> Option<&str> {
+		Some(self.prefix)
+	}
+
+	fn id(&self) -> &T {
+		&self.suffix
+	}
+}
+
+impl<T: Id> ErasedStrongId for StaticPrefixId<T> {
+	fn erased_prefix(&self) -> Option<&str> {
+		Some(self.prefix)
+	}
+
+	fn encoded(&self) -> String {
+		self.suffix.encode()
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<T: Id> serde::Serialize for StaticPrefixId<T> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		serializer.serialize_str(&self.to_string())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Id> serde::Deserialize<'de> for StaticPrefixId<T> {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		String::deserialize(deserializer)?
+			.parse::<Self>()
+			.map_err(|error| serde::de::Error::custom(error.to_string()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{register_static_prefixes, registered_static_prefixes, StaticPrefixId};
+	use crate::{base32::Base32Error, DynamicStrongIdBuf, Error};
+
+	// the registered prefixes are global, so each test registers its own
+	#[test]
+	fn registered() {
+		register_static_prefixes(["staticuser", "staticteam"]).unwrap();
+		register_static_prefixes(["staticuser"]).unwrap();
+		assert_eq!(
+			registered_static_prefixes()
+				.iter()
+				.filter(|prefix| **prefix == "staticuser")
+				.count(),
+			1
+		);
+		assert_eq!(
+			register_static_prefixes(["Static"]),
+			Err(Error::IncorrectPrefixCharacter('S'))
+		);
+
+		let id = "staticteam_0343".parse::<StaticPrefixId<u16>>().unwrap();
+		assert_eq!(id.into_parts(), ("staticteam", 3203));
+		assert_eq!(format!("{id:#}"), "staticteam_0343");
+		assert_eq!(
+			"staticorder_0343".parse::<StaticPrefixId<u16>>(),
+			Err(Error::UnknownPrefix("staticorder".into()))
+		);
+		assert_eq!(
+			"staticuser_03u3".parse::<StaticPrefixId<u16>>(),
+			Err(Error::Base32Error(Base32Error::InvalidByte(13, 'u')))
+		);
+	}
+
+	#[test]
+	fn parse_with_prefixes() {
+		let prefixes = ["user", "team"];
+		assert_eq!(
+			StaticPrefixId::<u16>::parse_with_prefixes(&prefixes, "user_0343"),
+			StaticPrefixId::new("user", 3203)
+		);
+		assert_eq!(
+			StaticPrefixId::<u16>::parse_with_prefixes(&prefixes, "0343"),
+			Err(Error::UnknownPrefix("".into()))
+		);
+		assert!(StaticPrefixId::new("User", 3203u16).is_err());
+
+		// invalid prefixes are rejected, even if the ID has one of them
+		assert_eq!(
+			StaticPrefixId::<u16>::parse_with_prefixes(&["User"], "User_0343"),
+			Err(Error::IncorrectPrefixCharacter('U'))
+		);
+		assert_eq!(
+			StaticPrefixId::<u16>::parse_with_prefixes(&["user", "_user"], "user_0343"),
+			Err(Error::IncorrectPrefixCharacter('_'))
+		);
+
+		let id = StaticPrefixId::new("user", 3203u16).unwrap();
+		let dynamic = DynamicStrongIdBuf::from(id);
+		assert_eq!(dynamic.to_string(), id.to_string());
+	}
+}
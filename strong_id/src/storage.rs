@@ -0,0 +1,113 @@
+/// Recommendations for storing IDs of a type in a database, for schema linters and migration tools
+///
+/// Generated types have a `STORAGE_HINT` constant, which is derived from the inner type and the
+/// length of the encoded ID. The prefix of a generated type is the same for every ID, so the
+/// recommended column only stores the inner value. `text_len` is the width of a text column for
+/// storing complete IDs instead, e.g. in logs or in columns shared by several ID types.
+///
+/// ```rust
+/// use strong_id::{strong_id, ColumnType, IndexStrategy};
+///
+/// strong_id!(pub struct UserId(u32 => "user"));
+///
+/// assert_eq!(UserId::STORAGE_HINT.column, ColumnType::BigInt);
+/// assert_eq!(UserId::STORAGE_HINT.text_len, 12);
+/// assert_eq!(UserId::STORAGE_HINT.index, IndexStrategy::Ordered);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct StorageHint {
+	/// The recommended type of a column storing the inner value
+	pub column: ColumnType,
+	/// The length of the complete ID, including the prefix and separator, for storing it as text
+	pub text_len: usize,
+	/// The recommended index of the column
+	pub index: IndexStrategy,
+}
+
+impl StorageHint {
+	/// Create a hint
+	pub const fn new(column: ColumnType, text_len: usize, index: IndexStrategy) -> Self {
+		Self {
+			column,
+			text_len,
+			index,
+		}
+	}
+}
+
+/// The type of a column storing the inner value of IDs, named after the standard SQL types
+///
+/// Unsigned integers are stored in the next wider signed type, as SQL has no unsigned types.
+/// Values which fit no integer type, e.g. `u64` and `u128`, are stored as their big-endian bytes,
+/// which sort in the same order as the IDs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ColumnType {
+	/// A 16-bit integer, e.g. `smallint`
+	SmallInt,
+	/// A 32-bit integer, e.g. `integer`
+	Integer,
+	/// A 64-bit integer, e.g. `bigint`
+	BigInt,
+	/// A native UUID type, e.g. `uuid` in PostgreSQL, or `binary(16)` where there is none
+	Uuid,
+	/// Fixed-length binary of the given number of bytes, e.g. `binary(8)` or `bytea`
+	Binary(usize),
+}
+
+/// The index recommended for a column of IDs
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum IndexStrategy {
+	/// An ordered index, e.g. a B-tree, for IDs which are sorted or scanned in ranges
+	Ordered,
+	/// An index for equality lookups only, e.g. a hash index, for IDs whose order is meaningless.
+	/// Recommended for types declared with `#[strong_id(index = "equality")]`.
+	Equality,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ColumnType, IndexStrategy, StorageHint};
+	use crate::strong_id;
+
+	#[test]
+	fn storage_hint() {
+		strong_id!(pub struct SmallId(u8 => "small"));
+		strong_id!(pub struct OrderId(u16 => "order"));
+		strong_id!(pub struct BalanceId(i64));
+		strong_id!(pub struct CountId(core::num::NonZeroU32 => "count"));
+		strong_id!(pub struct UserId(u64 => "user"));
+		strong_id! {
+			#[strong_id(index = "equality")]
+			pub struct TokenId(u128 => "token")
+		}
+		strong_id! {
+			#[strong_id(unordered)]
+			pub struct NonceId(u128 => "nonce")
+		}
+
+		assert_eq!(SmallId::STORAGE_HINT.column, ColumnType::SmallInt);
+		assert_eq!(OrderId::STORAGE_HINT.column, ColumnType::Integer);
+		assert_eq!(BalanceId::STORAGE_HINT.column, ColumnType::BigInt);
+		assert_eq!(CountId::STORAGE_HINT.column, ColumnType::BigInt);
+		assert_eq!(
+			UserId::STORAGE_HINT,
+			StorageHint::new(ColumnType::Binary(8), 18, IndexStrategy::Ordered)
+		);
+		assert_eq!(
+			TokenId::STORAGE_HINT,
+			StorageHint::new(ColumnType::Binary(16), 32, IndexStrategy::Equality)
+		);
+		assert_eq!(NonceId::STORAGE_HINT.index, IndexStrategy::Ordered);
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn uuid_storage_hint() {
+		crate::strong_uuid!(pub struct UserId(Uuid => "user"));
+
+		assert_eq!(
+			UserId::STORAGE_HINT,
+			StorageHint::new(ColumnType::Uuid, 31, IndexStrategy::Ordered)
+		);
+	}
+}
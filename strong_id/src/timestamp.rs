@@ -0,0 +1,88 @@
+use uuid::{NoContext, Timestamp};
+
+/// A point in time which can be used to generate time-based UUIDs, e.g. with
+/// [`StrongUuid::new_v7_at`](crate::StrongUuid::new_v7_at)
+///
+/// Implemented for [`Timestamp`] and [`SystemTime`](std::time::SystemTime), and with the "chrono"
+/// and "time" features, `chrono::DateTime<Utc>` and `time::OffsetDateTime`. Times before the unix
+/// epoch can't be represented by time-based UUIDs, and are clamped to the epoch.
+pub trait IntoTimestamp {
+	/// Convert into a UUID timestamp
+	fn into_timestamp(self) -> Timestamp;
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn from_unix(seconds: i64, subsec_nanos: u32) -> Timestamp {
+	match u64::try_from(seconds) {
+		Ok(seconds) => Timestamp::from_unix(NoContext, seconds, subsec_nanos),
+		Err(_) => Timestamp::from_unix(NoContext, 0, 0),
+	}
+}
+
+impl IntoTimestamp for Timestamp {
+	fn into_timestamp(self) -> Timestamp {
+		self
+	}
+}
+
+impl IntoTimestamp for std::time::SystemTime {
+	fn into_timestamp(self) -> Timestamp {
+		match self.duration_since(std::time::UNIX_EPOCH) {
+			Ok(duration) => {
+				Timestamp::from_unix(NoContext, duration.as_secs(), duration.subsec_nanos())
+			}
+			Err(_) => Timestamp::from_unix(NoContext, 0, 0),
+		}
+	}
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl IntoTimestamp for chrono::DateTime<chrono::Utc> {
+	fn into_timestamp(self) -> Timestamp {
+		from_unix(self.timestamp(), self.timestamp_subsec_nanos())
+	}
+}
+
+#[cfg(feature = "time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+impl IntoTimestamp for time::OffsetDateTime {
+	fn into_timestamp(self) -> Timestamp {
+		from_unix(self.unix_timestamp(), self.nanosecond())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::IntoTimestamp;
+	use std::time::{Duration, UNIX_EPOCH};
+
+	#[test]
+	fn system_time() {
+		let at = UNIX_EPOCH + Duration::new(1_689_338_443, 3_000_000);
+		assert_eq!(at.into_timestamp().to_unix(), (1_689_338_443, 3_000_000));
+		assert_eq!(
+			(UNIX_EPOCH - Duration::from_secs(1))
+				.into_timestamp()
+				.to_unix(),
+			(0, 0)
+		);
+	}
+
+	#[cfg(feature = "chrono")]
+	#[test]
+	fn chrono() {
+		use chrono::TimeZone;
+
+		let at = chrono::Utc.timestamp_opt(1_689_338_443, 3_000_000).unwrap();
+		assert_eq!(at.into_timestamp().to_unix(), (1_689_338_443, 3_000_000));
+	}
+
+	#[cfg(feature = "time")]
+	#[test]
+	fn time() {
+		let at = time::OffsetDateTime::from_unix_timestamp(1_689_338_443).unwrap()
+			+ time::Duration::milliseconds(3);
+		assert_eq!(at.into_timestamp().to_unix(), (1_689_338_443, 3_000_000));
+	}
+}
@@ -0,0 +1,184 @@
+use crate::{Error, StrongId};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const RANDOM_BITS: u32 = 80;
+const RANDOM_MASK: u128 = (1 << RANDOM_BITS) - 1;
+const TIMESTAMP_MASK: u64 = (1 << 48) - 1;
+
+/// Use IDs backed by a `u128` as [ULIDs](https://github.com/ulid/spec)
+///
+/// A ULID is a 48-bit millisecond timestamp followed by 80 random bits, encoded with the same
+/// Crockford alphabet as StrongIds. The suffix of a `u128` ID is therefore the lowercase ULID, so
+/// stored ULIDs can be adopted without changing their values.
+///
+/// ```rust
+/// use strong_id::{strong_id, UlidId};
+///
+/// strong_id!(pub struct EventId(u128 => "event"));
+///
+/// let id = "event_01arz3ndektsv4rrffq69g5fav".parse::<EventId>().unwrap();
+/// assert_eq!(id.timestamp_ms(), 1_469_922_850_259);
+/// assert_eq!(EventId::from_ulid_parts(id.timestamp_ms(), id.random()), id);
+/// ```
+pub trait UlidId: StrongId<u128> + From<u128> {
+	/// Create an ID from a millisecond timestamp and random bits
+	///
+	/// Only the lower 48 bits of the timestamp and the lower 80 bits of `random` are used.
+	fn from_ulid_parts(timestamp_ms: u64, random: u128) -> Self
+	where
+		Self: Sized,
+	{
+		Self::from(ulid_from_parts(timestamp_ms, random))
+	}
+
+	/// The milliseconds since the unix epoch at which the ID was generated
+	fn timestamp_ms(&self) -> u64 {
+		(*self.id() >> RANDOM_BITS) as u64
+	}
+
+	/// The time at which the ID was generated
+	fn system_time(&self) -> SystemTime {
+		UNIX_EPOCH + Duration::from_millis(self.timestamp_ms())
+	}
+
+	/// The 80 random bits of the ID
+	fn random(&self) -> u128 {
+		*self.id() & RANDOM_MASK
+	}
+}
+
+impl<S: StrongId<u128> + From<u128>> UlidId for S {}
+
+fn ulid_from_parts(timestamp_ms: u64, random: u128) -> u128 {
+	(u128::from(timestamp_ms & TIMESTAMP_MASK) << RANDOM_BITS) | (random & RANDOM_MASK)
+}
+
+/// Generates ULID-compatible IDs which increase monotonically, see [`UlidId`]
+///
+/// IDs generated within the same millisecond, or after the clock moved backwards, increment the
+/// previous ID instead of using new random bits, so that they sort in the order they were
+/// generated. If the random bits overflow, the increment carries into the timestamp. Once the
+/// largest ULID has been generated, generating fails with [`Error::OutOfRange`].
+///
+/// ```rust
+/// use strong_id::{strong_id, UlidGenerator, UlidId};
+///
+/// strong_id!(pub struct EventId(u128 => "event"));
+///
+/// let mut generator = UlidGenerator::new();
+/// let first: EventId = generator.generate_from(1_469_922_850_259, 41).unwrap();
+/// let second: EventId = generator.generate_from(1_469_922_850_259, 7).unwrap();
+///
+/// assert!(second > first);
+/// assert_eq!(second.random(), 42);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct UlidGenerator {
+	last: u128,
+}
+
+impl UlidGenerator {
+	/// Create a generator, whose first ID is not constrained by a previous ID
+	pub const fn new() -> Self {
+		Self { last: 0 }
+	}
+
+	/// Generate an ID from a millisecond timestamp and random bits, incrementing the previous ID if
+	/// it would not sort after it
+	pub fn generate_from<T: UlidId>(
+		&mut self,
+		timestamp_ms: u64,
+		random: u128,
+	) -> Result<T, Error> {
+		let ulid = ulid_from_parts(timestamp_ms, random);
+		self.last = if ulid > self.last {
+			ulid
+		} else {
+			self.last.checked_add(1).ok_or(Error::OutOfRange)?
+		};
+		Ok(T::from(self.last))
+	}
+
+	/// Generate an ID for the current time
+	#[cfg(all(feature = "uuid", feature = "uuid-v4"))]
+	#[cfg_attr(docsrs, doc(cfg(feature = "uuid-v4")))]
+	pub fn generate<T: UlidId>(&mut self) -> Result<T, Error> {
+		let timestamp_ms = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_or(0, |duration| duration.as_millis() as u64);
+
+		// v4 UUIDs have 122 random bits, around the version and variant bits
+		let uuid = uuid::Uuid::new_v4().as_u128();
+		let random =
+			(uuid & ((1 << 62) - 1)) | (((uuid >> 64) & 0xFFF) << 62) | ((uuid >> 122) << 74);
+
+		self.generate_from(timestamp_ms, random)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{UlidGenerator, UlidId};
+	use crate::{strong_id, Error};
+	use std::time::{Duration, UNIX_EPOCH};
+
+	strong_id!(pub struct EventId(u128 => "event"));
+
+	#[test]
+	fn ulid_parts() {
+		let id = EventId::from(0x0156_3e3a_b5d3_d676_4c61_efb9_9302_bd5b);
+		assert_eq!(id.suffix_encoded(), "01arz3ndektsv4rrffq69g5fav");
+		assert_eq!(id.timestamp_ms(), 1_469_922_850_259);
+		assert_eq!(id.random(), 0xd676_4c61_efb9_9302_bd5b);
+		assert_eq!(
+			id.system_time(),
+			UNIX_EPOCH + Duration::from_millis(1_469_922_850_259)
+		);
+
+		let max = EventId::from_ulid_parts(u64::MAX, u128::MAX);
+		assert_eq!(max, EventId::from(u128::MAX));
+	}
+
+	#[test]
+	fn monotonic() {
+		let mut generator = UlidGenerator::new();
+
+		let first: EventId = generator.generate_from(1000, 500).unwrap();
+		assert_eq!(first, EventId::from_ulid_parts(1000, 500));
+
+		// same millisecond, and a clock which moved backwards
+		let second: EventId = generator.generate_from(1000, 3).unwrap();
+		let third: EventId = generator.generate_from(999, 3).unwrap();
+		assert_eq!(second, EventId::from_ulid_parts(1000, 501));
+		assert_eq!(third, EventId::from_ulid_parts(1000, 502));
+
+		let later: EventId = generator.generate_from(1001, 3).unwrap();
+		assert_eq!(later, EventId::from_ulid_parts(1001, 3));
+
+		// overflowing random bits carry into the timestamp
+		let mut generator = UlidGenerator::new();
+		let _: EventId = generator.generate_from(1000, u128::MAX).unwrap();
+		let carried: EventId = generator.generate_from(1000, 0).unwrap();
+		assert_eq!(carried, EventId::from_ulid_parts(1001, 0));
+
+		// there is no ULID after the largest one
+		let mut generator = UlidGenerator::new();
+		let last: EventId = generator.generate_from(u64::MAX, u128::MAX).unwrap();
+		assert_eq!(last, EventId::from(u128::MAX));
+		assert_eq!(
+			generator.generate_from::<EventId>(u64::MAX, u128::MAX),
+			Err(Error::OutOfRange)
+		);
+	}
+
+	#[cfg(all(feature = "uuid", feature = "uuid-v4"))]
+	#[test]
+	fn generate() {
+		let mut generator = UlidGenerator::new();
+		let ids = (0..100)
+			.map(|_| generator.generate::<EventId>().unwrap())
+			.collect::<Vec<_>>();
+		assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+		assert!(ids[0].timestamp_ms() > 1_600_000_000_000);
+	}
+}
@@ -0,0 +1,151 @@
+use core::{fmt::Display, future::Future, str::FromStr};
+
+/// An ID whose format is valid, but which hasn't been confirmed to exist yet
+///
+/// Parsing an `Unverified<UserId>` validates the ID like parsing a `UserId`, but the ID can only be
+/// used where a `UserId` is required after [`verify`](Self::verify) promotes it with a check, e.g.
+/// a database lookup. This keeps IDs from request inputs from reaching code which assumes they
+/// exist. The ID can still be displayed or debugged, e.g. for logging, but not borrowed.
+///
+/// ```rust
+/// use strong_id::{strong_id, Unverified};
+///
+/// strong_id!(pub struct UserId(u16 => "user"));
+///
+/// async fn exists(id: UserId) -> bool {
+///     id == UserId::from(3203)
+/// }
+///
+/// async fn handle(input: &str) -> Option<UserId> {
+///     let id = input.parse::<Unverified<UserId>>().ok()?;
+///     id.verify(exists).await
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Unverified<T>(T);
+
+impl<T> Unverified<T> {
+	/// Mark an ID as unverified
+	pub const fn new(id: T) -> Self {
+		Self(id)
+	}
+
+	/// Promote the ID if `check` confirms that it exists
+	pub async fn verify<F, Fut>(self, check: F) -> Option<T>
+	where
+		T: Clone,
+		F: FnOnce(T) -> Fut,
+		Fut: Future<Output = bool>,
+	{
+		if check(self.0.clone()).await {
+			Some(self.0)
+		} else {
+			None
+		}
+	}
+
+	/// Promote the ID if `check` confirms that it exists, for checks which may fail, e.g. when the
+	/// database is unavailable
+	pub async fn try_verify<F, Fut, E>(self, check: F) -> Result<Option<T>, E>
+	where
+		T: Clone,
+		F: FnOnce(T) -> Fut,
+		Fut: Future<Output = Result<bool, E>>,
+	{
+		Ok(if check(self.0.clone()).await? {
+			Some(self.0)
+		} else {
+			None
+		})
+	}
+}
+
+impl<T: FromStr> FromStr for Unverified<T> {
+	type Err = T::Err;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		s.parse().map(Self)
+	}
+}
+
+impl<T: Display> Display for Unverified<T> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Unverified<T> {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		T::deserialize(deserializer).map(Self)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Unverified;
+	use crate::{strong_id, Error};
+	use core::{
+		future::Future,
+		pin::Pin,
+		task::{Context, Poll},
+	};
+	use std::{sync::Arc, task::Wake};
+
+	strong_id!(pub struct UserId(u16 => "user"));
+
+	struct NoopWaker;
+
+	impl Wake for NoopWaker {
+		fn wake(self: Arc<Self>) {}
+	}
+
+	/// Poll a future which never waits to completion
+	fn ready<F: Future>(future: F) -> F::Output {
+		let waker = Arc::new(NoopWaker).into();
+		let mut context = Context::from_waker(&waker);
+		let mut future = Box::pin(future);
+		match Pin::as_mut(&mut future).poll(&mut context) {
+			Poll::Ready(output) => output,
+			Poll::Pending => panic!("the future is not ready"),
+		}
+	}
+
+	#[test]
+	fn verify() {
+		let exists = |id: UserId| async move { id == UserId::from(3203) };
+
+		let id = "user_0343".parse::<Unverified<UserId>>().unwrap();
+		assert_eq!(id, Unverified::new(UserId::from(3203)));
+		assert_eq!(id.to_string(), "user_0343");
+		assert_eq!(ready(id.verify(exists)), Some(UserId::from(3203)));
+
+		let id = "user_0344".parse::<Unverified<UserId>>().unwrap();
+		assert_eq!(ready(id.verify(exists)), None);
+
+		assert_eq!(
+			"user_034".parse::<Unverified<UserId>>(),
+			Err(Error::InvalidLength(4, 3))
+		);
+	}
+
+	#[test]
+	fn try_verify() {
+		let id = Unverified::new(UserId::from(3203));
+		assert_eq!(
+			ready(id.try_verify(|_| async { Ok::<_, ()>(true) })),
+			Ok(Some(UserId::from(3203)))
+		);
+		assert_eq!(
+			ready(id.try_verify(|_| async { Err::<bool, _>("unavailable") })),
+			Err("unavailable")
+		);
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn deserialize() {
+		let id = serde_json::from_str::<Unverified<UserId>>(r#""user_0343""#).unwrap();
+		assert_eq!(id, Unverified::new(UserId::from(3203)));
+	}
+}
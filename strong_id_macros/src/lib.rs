@@ -1,60 +1,527 @@
 extern crate proc_macro;
 
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Fields, LitStr, Type};
+use quote::ToTokens;
+use syn::{
+	parse_macro_input, parse_quote, punctuated::Punctuated, Data, DataEnum, DeriveInput, Fields,
+	GenericArgument, Generics, Ident, LitInt, LitStr, Member, Path, PathArguments, Token, Type,
+};
 
-fn assert_prefix_valid(prefix: &str) {
-	assert!(!prefix.is_empty(), "prefix must be non-empty");
-	assert!(prefix.len() < 64, "prefix is longer than 63 characters");
+fn validate_prefix(prefix: &str) -> Result<(), &'static str> {
+	if prefix.is_empty() {
+		return Err("prefix must be non-empty");
+	}
+	if prefix.len() >= 64 {
+		return Err("prefix is longer than 63 characters");
+	}
 
 	let underscore = b'_';
 	let bytes = prefix.as_bytes();
 
-	assert_ne!(*bytes.first().unwrap(), underscore, "prefix cannot start with an underscore");
-	assert_ne!(*bytes.last().unwrap(), underscore, "prefix cannot end with an underscore");
+	if bytes.first() == Some(&underscore) {
+		return Err("prefix cannot start with an underscore");
+	}
+	if bytes.last() == Some(&underscore) {
+		return Err("prefix cannot end with an underscore");
+	}
 
-	for (index, b) in prefix.as_bytes().iter().enumerate() {
+	for (index, b) in bytes.iter().enumerate() {
 		if cfg!(feature = "delimited") && *b == underscore && index > 0 {
 			continue;
 		}
 
-		assert!(
-			b.is_ascii_lowercase(),
-			"prefix contains non ascii lowercase characters"
-		);
+		if !b.is_ascii_lowercase() {
+			return Err("prefix contains non ascii lowercase characters");
+		}
+	}
+
+	Ok(())
+}
+
+/// Decode the base32 encoded suffix of an ID of `byte_len` bytes, at most 16, e.g. the suffix of a
+/// TypeID
+fn decode_suffix(suffix: &str, byte_len: usize) -> Result<u128, String> {
+	const ALPHABET: &[u8; 32] = b"0123456789abcdefghjkmnpqrstvwxyz";
+
+	let bits = byte_len * 8;
+	let len = bits / 5 + 1;
+	if suffix.len() != len {
+		return Err(format!(
+			"expected a suffix of {len} characters, found {}",
+			suffix.len()
+		));
+	}
+
+	// the first character holds the padding bits, which must be zero
+	let max_first = 0x1F >> (len * 5 - bits);
+
+	let mut value: u128 = 0;
+	for (index, c) in suffix.chars().enumerate() {
+		let symbol = ALPHABET
+			.iter()
+			.position(|symbol| *symbol as char == c)
+			.ok_or_else(|| format!("invalid suffix character `{c}` at position {index}"))?;
+
+		if index == 0 && symbol > max_first {
+			return Err(format!(
+				"the suffix overflows {bits} bits, the first character may be at most `{}`, found `{c}`",
+				ALPHABET[max_first] as char
+			));
+		}
+
+		value = (value << 5) | symbol as u128;
+	}
+
+	Ok(value)
+}
+
+/// How a constant of an inner type is created from the decoded suffix
+enum ConstantType {
+	Unsigned(usize),
+	Signed(usize),
+	NonZero(usize, Ident),
+	Uuid,
+}
+
+/// The inner types which constants can be declared for. Pointer-sized integers are excluded, as
+/// their encoding depends on the target and the "portable-usize" feature.
+fn constant_type(ty: &Type) -> Option<ConstantType> {
+	match ty {
+		Type::Path(path) => {
+			let segment = path.path.segments.last()?;
+			let ident = segment.ident.to_string();
+			let width = |name: &str| match name {
+				"8" => Some(1),
+				"16" => Some(2),
+				"32" => Some(4),
+				"64" => Some(8),
+				"128" => Some(16),
+				_ => None,
+			};
+
+			if ident == "Uuid" {
+				Some(ConstantType::Uuid)
+			} else if let Some(bits) = ident.strip_prefix("NonZeroU") {
+				let unsigned = Ident::new(&format!("u{bits}"), segment.ident.span());
+				width(bits).map(|len| ConstantType::NonZero(len, unsigned))
+			} else if let Some(bits) = ident.strip_prefix('u') {
+				width(bits).map(ConstantType::Unsigned)
+			} else if let Some(bits) = ident.strip_prefix('i') {
+				width(bits).map(ConstantType::Signed)
+			} else {
+				None
+			}
+		}
+		Type::Group(group) => constant_type(&group.elem),
+		Type::Paren(paren) => constant_type(&paren.elem),
+		_ => None,
+	}
+}
+
+enum SerdeRepr {
+	String,
+	Struct,
+	Uuid,
+	Bytes,
+	Integer,
+	Cbor,
+	Rmp,
+}
+
+/// The field holding the inner value of an ID
+struct Field {
+	member: Member,
+	/// The other fields, which are initialized with their `Default` when an ID is created, e.g.
+	/// `PhantomData` markers
+	defaulted: Vec<Member>,
+}
+
+impl Field {
+	/// Create the ID from an expression of the inner value
+	fn construct(&self, value: impl ToTokens) -> impl ToTokens {
+		let member = &self.member;
+		let defaulted = &self.defaulted;
+		quote! {
+			Self {
+				#member: #value,
+				#(#defaulted: ::core::default::Default::default(),)*
+			}
+		}
 	}
 }
 
 struct Attributes {
+	krate: Path,
 	prefix: Option<String>,
 	suffix_type: Type,
+	field: Field,
+	serde: SerdeRepr,
+	ext_type: Option<LitInt>,
+	deny_sequential: bool,
+	deref: bool,
+	encoding: Option<Type>,
+	ord: bool,
+	unordered: bool,
+	index_strategy: Option<Ident>,
+	case_insensitive: bool,
+	into_inner: bool,
+	no_into_inner: bool,
+	skip_serde: bool,
+	accessors: Vec<Ident>,
+	default_version: Option<Ident>,
+	default_nil: bool,
+	standalone: bool,
+}
+
+/// Whether the type is a primitive integer narrow enough that IDs are likely to be sequential, and
+/// therefore enumerable when exposed. Integers are matched by the last segment of their path, e.g.
+/// `core::num::NonZeroU32`, and through `Wrapping<...>`.
+fn is_sequential_type(ty: &Type) -> bool {
+	match ty {
+		Type::Path(path) => match path.path.segments.last() {
+			Some(segment) if segment.ident == "Wrapping" => match &segment.arguments {
+				PathArguments::AngleBracketed(arguments) => {
+					arguments.args.iter().any(|argument| match argument {
+						GenericArgument::Type(ty) => is_sequential_type(ty),
+						_ => false,
+					})
+				}
+				_ => false,
+			},
+			Some(segment) => [
+				"u8",
+				"u16",
+				"u32",
+				"u64",
+				"usize",
+				"i8",
+				"i16",
+				"i32",
+				"i64",
+				"isize",
+				"NonZeroU8",
+				"NonZeroU16",
+				"NonZeroU32",
+				"NonZeroU64",
+				"NonZeroUsize",
+			]
+			.iter()
+			.any(|ident| segment.ident == ident),
+			None => false,
+		},
+		// types passed through `macro_rules!` as a `ty` fragment arrive wrapped in a group
+		Type::Group(group) => is_sequential_type(&group.elem),
+		Type::Paren(paren) => is_sequential_type(&paren.elem),
+		_ => false,
+	}
+}
+
+/// Whether the type is one of the type parameters of the struct
+fn is_type_param(ty: &Type, generics: &Generics) -> bool {
+	match ty {
+		Type::Path(path) if path.qself.is_none() => match path.path.get_ident() {
+			Some(ident) => generics.type_params().any(|param| param.ident == *ident),
+			None => false,
+		},
+		Type::Group(group) => is_type_param(&group.elem, generics),
+		Type::Paren(paren) => is_type_param(&paren.elem, generics),
+		_ => false,
+	}
+}
+
+/// The `ColumnType` of integer and UUID inner types. Other types are stored as binary.
+fn storage_column(ty: &Type) -> Option<&'static str> {
+	match constant_type(ty)? {
+		ConstantType::Unsigned(len) | ConstantType::NonZero(len, _) => match len {
+			1 => Some("SmallInt"),
+			2 => Some("Integer"),
+			4 => Some("BigInt"),
+			_ => None,
+		},
+		ConstantType::Signed(len) => match len {
+			1 | 2 => Some("SmallInt"),
+			4 => Some("Integer"),
+			8 => Some("BigInt"),
+			_ => None,
+		},
+		ConstantType::Uuid => Some("Uuid"),
+	}
+}
+
+/// Parse the value of the `crate` option
+fn parse_crate(meta: &syn::meta::ParseNestedMeta) -> Result<Path, syn::Error> {
+	let value = meta.value()?;
+
+	// accept a string, like serde, or a bare path so that `$crate` can be passed through from
+	// `macro_rules!` macros
+	if value.peek(LitStr) {
+		value.parse::<LitStr>()?.parse::<Path>()
+	} else {
+		value.call(Path::parse_mod_style)
+	}
+}
+
+/// Convert the name of a variant into the snake case of its accessors, e.g. `OrderLine` into
+/// `order_line`
+fn snake_case(ident: &Ident) -> String {
+	let mut snake = String::new();
+	for (index, c) in ident.to_string().chars().enumerate() {
+		if c.is_uppercase() {
+			if index > 0 {
+				snake.push('_');
+			}
+			snake.extend(c.to_lowercase());
+		} else {
+			snake.push(c);
+		}
+	}
+	snake
+}
+
+/// Derive `StrongId` for an enum whose variants each wrap a StrongId type, like `strong_id_enum!`
+fn derive_strong_id_enum(input: &DeriveInput, data: &DataEnum) -> proc_macro::TokenStream {
+	let mut krate = parse_quote!(::strong_id);
+	for attr in &input.attrs {
+		if attr.path().is_ident("strong_id") {
+			let parsed = attr.parse_nested_meta(|meta| {
+				if meta.path.is_ident("crate") {
+					krate = parse_crate(&meta)?;
+					Ok(())
+				} else {
+					Err(meta.error("only `crate` is supported when deriving `StrongId` for enums"))
+				}
+			});
+			if let Err(error) = parsed {
+				return error.to_compile_error().into();
+			}
+		}
+	}
+
+	let ident = &input.ident;
+	if input.generics.lt_token.is_some() || data.variants.is_empty() {
+		return syn::Error::new_spanned(
+			ident,
+			"`StrongId` is only supported for non-generic enums with at least one variant",
+		)
+		.to_compile_error()
+		.into();
+	}
+
+	let mut variants = Vec::new();
+	let mut types = Vec::new();
+	for variant in &data.variants {
+		match &variant.fields {
+			Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+				variants.push(&variant.ident);
+				types.push(&fields.unnamed[0].ty);
+			}
+			_ => {
+				return syn::Error::new_spanned(
+					variant,
+					"each variant must wrap a single StrongId type, e.g. `User(UserId)`",
+				)
+				.to_compile_error()
+				.into()
+			}
+		}
+	}
+
+	let accessors = variants.iter().zip(&types).map(|(variant, ty)| {
+		let snake = snake_case(variant);
+		let is = Ident::new(&format!("is_{snake}"), variant.span());
+		let as_ = Ident::new(&format!("as_{snake}"), variant.span());
+		let is_doc = format!("Whether the ID is a [`{variant}`](Self::{variant})");
+		let as_doc = format!("The ID, if it is a [`{variant}`](Self::{variant})");
+
+		quote! {
+			#[doc = #is_doc]
+			pub fn #is(&self) -> bool {
+				matches!(self, Self::#variant(_))
+			}
+
+			#[doc = #as_doc]
+			pub fn #as_(&self) -> ::core::option::Option<&#ty> {
+				match self {
+					Self::#variant(id) => ::core::option::Option::Some(id),
+					#[allow(unreachable_patterns)]
+					_ => ::core::option::Option::None,
+				}
+			}
+		}
+	});
+
+	let expanded = quote! {
+		#krate::_internal_impl_strong_id_enum!(#ident { #(#variants(#types)),* });
+
+		impl #ident {
+			#(#accessors)*
+		}
+	};
+
+	expanded.into()
 }
 
 fn from_input(input: &DeriveInput) -> Result<Attributes, syn::Error> {
+	let mut krate = parse_quote!(::strong_id);
 	let mut prefix = None;
-	let mut suffix = None;
+	let mut field_name = None;
+	let mut serde = SerdeRepr::String;
+	let mut ext_type = None;
+	let mut deny_sequential = false;
+	let mut deref = false;
+	let mut encoding = None;
+	let mut ord = false;
+	let mut unordered = false;
+	let mut index_strategy = None;
+	let mut case_insensitive = false;
+	let mut into_inner = false;
+	let mut no_into_inner = false;
+	let mut skip_serde = false;
+	let mut accessors = Vec::new();
+	let mut default_version = None;
+	let mut default_nil = false;
+	let mut standalone = false;
 
 	for attr in &input.attrs {
 		if attr.path().is_ident("strong_id") {
 			attr.parse_nested_meta(|meta| {
+				if meta.path.is_ident("crate") {
+					krate = parse_crate(&meta)?;
+				}
+
 				if meta.path.is_ident("prefix") {
-					let value = meta.value()?.parse::<LitStr>()?.value();
+					let value = meta.value()?.parse::<LitStr>()?;
 
-					if value.is_empty() {
+					if value.value().is_empty() {
 						prefix = None
 					} else {
-						prefix = Some(value)
+						validate_prefix(&value.value())
+							.map_err(|error| syn::Error::new(value.span(), error))?;
+						prefix = Some(value.value())
 					}
 				}
 
+				if meta.path.is_ident("derive") {
+					return Err(meta.error(
+						"derives can't be given to `strong_id`, add `#[derive(...)]` to the struct instead",
+					));
+				}
+
+				if meta.path.is_ident("field") {
+					field_name = Some(meta.value()?.parse::<LitStr>()?);
+				}
+
 				if meta.path.is_ident("suffix") {
-					let value = meta.value()?.parse::<LitStr>()?.value();
+					return Err(meta.error(
+						"`suffix` is no longer supported, the field is detected or given with `field = \"...\"`",
+					));
+				}
+
+				if meta.path.is_ident("serde") {
+					let value = meta.value()?.parse::<LitStr>()?;
+
+					serde = match value.value().as_str() {
+						"string" | "typeid" => SerdeRepr::String,
+						"struct" => SerdeRepr::Struct,
+						"uuid" => SerdeRepr::Uuid,
+						"bytes" => SerdeRepr::Bytes,
+						"integer" => SerdeRepr::Integer,
+						"cbor" => SerdeRepr::Cbor,
+						"rmp" => SerdeRepr::Rmp,
+						_ => {
+							return Err(syn::Error::new(
+								value.span(),
+								"expected one of `string`, `typeid`, `struct`, `uuid`, `bytes`, `integer`, `cbor` or `rmp`",
+							))
+						}
+					};
+				}
+
+				if meta.path.is_ident("ext_type") {
+					ext_type = Some(meta.value()?.parse::<LitInt>()?);
+				}
+
+				if meta.path.is_ident("deny_sequential") {
+					deny_sequential = true;
+				}
+
+				if meta.path.is_ident("deref") {
+					deref = true;
+				}
+
+				if meta.path.is_ident("ord") {
+					ord = true;
+				}
+
+				if meta.path.is_ident("unordered") {
+					unordered = true;
+				}
+
+				if meta.path.is_ident("index") {
+					let value = meta.value()?.parse::<LitStr>()?;
+
+					index_strategy = match value.value().as_str() {
+						"ordered" => Some(Ident::new("Ordered", value.span())),
+						"equality" => Some(Ident::new("Equality", value.span())),
+						_ => {
+							return Err(syn::Error::new(
+								value.span(),
+								"expected one of `ordered` or `equality`",
+							))
+						}
+					};
+				}
+
+				if meta.path.is_ident("case_insensitive") {
+					case_insensitive = true;
+				}
+
+				if meta.path.is_ident("into_inner") {
+					into_inner = true;
+				}
+
+				if meta.path.is_ident("no_into_inner") {
+					no_into_inner = true;
+				}
+
+				if meta.path.is_ident("skip_serde") {
+					skip_serde = true;
+				}
+
+				if meta.path.is_ident("standalone") {
+					standalone = true;
+				}
 
-					if value.is_empty() {
-						suffix = None
+				if meta.path.is_ident("default_nil") {
+					default_nil = true;
+				}
+
+				if meta.path.is_ident("accessor") {
+					accessors.push(meta.value()?.parse::<LitStr>()?.parse::<Ident>()?);
+				}
+
+				if meta.path.is_ident("default") {
+					let value = meta.value()?;
+					let version = if value.peek(LitStr) {
+						value.parse::<LitStr>()?.parse::<Ident>()?
 					} else {
-						suffix = Some(value)
+						value.parse::<Ident>()?
+					};
+
+					if version != "v4" && version != "v7" {
+						return Err(syn::Error::new(version.span(), "expected `v4` or `v7`"));
 					}
+					default_version = Some(version);
+				}
+
+				if meta.path.is_ident("encoding") {
+					let value = meta.value()?;
+
+					encoding = Some(if value.peek(LitStr) {
+						value.parse::<LitStr>()?.parse::<Type>()?
+					} else {
+						value.parse::<Type>()?
+					});
 				}
 
 				Ok(())
@@ -62,30 +529,157 @@ fn from_input(input: &DeriveInput) -> Result<Attributes, syn::Error> {
 		}
 	}
 
-	let field = match &input.data {
-		Data::Struct(data_struct) => match &data_struct.fields {
-			Fields::Unnamed(fields) => match fields.unnamed.first() {
-				Some(field) => field,
-				None => panic!("tuple should have a single field"),
-			},
-			_ => panic!("must be a tuple struct with a single field"),
-		},
-		_ => panic!("type must be a struct"),
+	let fields = match &input.data {
+		Data::Struct(data_struct) => &data_struct.fields,
+		_ => {
+			return Err(syn::Error::new_spanned(
+				&input.ident,
+				"StrongId can only be derived for structs",
+			))
+		}
+	};
+
+	let members = fields.members().collect::<Vec<_>>();
+	let types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+	let index = match (&field_name, fields) {
+		(Some(name), Fields::Named(_)) => members
+			.iter()
+			.position(|member| matches!(member, Member::Named(ident) if *ident == name.value()))
+			.ok_or_else(|| syn::Error::new(name.span(), "no field with this name"))?,
+		(Some(name), _) => {
+			return Err(syn::Error::new(
+				name.span(),
+				"`field` can only be given for structs with named fields",
+			))
+		}
+		(None, _) if members.len() == 1 => 0,
+		(None, _) if members.is_empty() => {
+			return Err(syn::Error::new_spanned(
+				&input.ident,
+				"expected a struct with a single field",
+			))
+		}
+		(None, _) => {
+			return Err(syn::Error::new_spanned(
+				fields,
+				"expected a single field, or `#[strong_id(field = \"...\")]`",
+			))
+		}
+	};
+
+	let ty = types[index].clone();
+	let field = Field {
+		member: members[index].clone(),
+		defaulted: members
+			.into_iter()
+			.enumerate()
+			.filter(|(i, _)| *i != index)
+			.map(|(_, member)| member)
+			.collect(),
 	};
 
 	let attributes = Attributes {
+		krate,
 		prefix,
-		suffix_type: field.ty.clone(),
+		suffix_type: ty,
+		field,
+		serde,
+		ext_type,
+		deny_sequential,
+		deref,
+		encoding,
+		ord,
+		unordered,
+		index_strategy,
+		case_insensitive,
+		into_inner,
+		no_into_inner,
+		skip_serde,
+		accessors,
+		default_version,
+		default_nil,
+		standalone,
 	};
 
 	Ok(attributes)
 }
 
+/// Derive `StrongId` for a struct with a single field, either a tuple struct or a struct with a
+/// named field, or for an enum whose variants each wrap a StrongId type
+///
+/// Generic structs are supported, with their bounds and where clause forwarded to the generated
+/// impls. When the inner value is itself a type parameter, e.g. `struct Scoped<T: Id>(T)`, the
+/// orphan rule forbids `From<Scoped<T>> for T` and comparing `T` with the ID, so these impls are
+/// omitted.
+///
+/// Configured with the `#[strong_id(...)]` attribute:
+///
+/// - `crate = "..."` - the path to the `strong_id` crate, when it is re-exported from another crate.
+///   Defaults to `::strong_id`.
+/// - `prefix = "..."` - the prefix of the ID
+/// - `field = "..."` - the field holding the inner value, for structs with more than one named
+///   field. The other fields are initialized with their `Default` when an ID is created, so they
+///   are usually markers such as `PhantomData`.
+/// - `serde = "string" | "typeid" | "struct" | "uuid" | "bytes" | "integer" | "cbor" | "rmp"` - the
+///   serde representation when the "serde" feature is enabled. `string` and `typeid` are the
+///   prefixed string. `uuid`, `bytes` and `integer` serialize the inner value as a bare UUID, raw
+///   bytes or a number, without the prefix. `uuid` requires a UUID-backed ID, and `cbor` and `rmp`
+///   require the feature of the same name and a UUID-backed ID.
+///   See `strong_id::serde_helpers`.
+/// - `ext_type = ...` - the MessagePack extension type of `serde = "rmp"`. Defaults to
+///   `strong_id::serde_helpers::rmp::EXT_TYPE`.
+/// - `deny_sequential` - fail to compile when the ID is backed by an integer narrower than 128 bits.
+///   Narrow integer IDs are usually sequential, and exposing them allows them to be enumerated.
+/// - `deref` - implement `Deref<Target = Inner>` and `AsRef<Inner>`, so that e.g. a UUID-backed ID
+///   can be passed to functions taking `&Uuid`. This is a trade-off: it makes call sites terser, but
+///   the inner value's methods become callable on the ID, which weakens the distinction between the
+///   two types.
+/// - `encoding = ...` - the `strong_id::Encoding` of the suffix, e.g. `Base32<MyAlphabet>`.
+///   Defaults to `strong_id::Base32`, the TypeID alphabet.
+/// - `ord` - implement `Ord` and `PartialOrd` by the inner value, along with comparisons with the
+///   inner value. Types declared with `strong_id!` set this.
+/// - `unordered` - don't implement `Ord` and `PartialOrd`, even though `ord` is set, e.g. by
+///   `strong_id!`. The order of random IDs, e.g. v4 UUIDs, is meaningless, so omitting it forces
+///   call sites to sort by something else explicitly.
+/// - `index = "ordered" | "equality"` - the index recommended by `STORAGE_HINT`. Defaults to
+///   `ordered`, as ordered indices also serve equality lookups, and the database order of an ID
+///   doesn't depend on whether the type implements `Ord`.
+/// - `case_insensitive` - parse IDs with `from_str_relaxed`, which also accepts uppercase prefixes
+///   and suffixes, e.g. for IDs typed by people. IDs are still displayed in their canonical case.
+/// - `into_inner` - implement `From<Id> for Inner`. Types declared with `strong_id!` set this.
+/// - `no_into_inner` - don't implement `From<Id> for Inner`, even though `into_inner` is set, e.g.
+///   by `strong_id!`, so that integer-backed IDs can't silently convert into integers, e.g. via
+///   `Into<u64>`, and end up in arithmetic. The inner value is then only available through the
+///   explicitly named `into_inner` and `id`.
+/// - `accessor = "..."` - generate an inherent method with the given name, e.g. `get` or `value`,
+///   which borrows the inner value like `StrongId::id` without importing the trait. May be given
+///   more than once.
+/// - `default_nil` - implement `Default` with the default of the inner value, i.e. the nil UUID or
+///   zero, e.g. for configs and test fixtures which require `Default`. Not supported for `NonZero`
+///   integers.
+/// - `standalone` - also implement `Display`, `FromStr`, `From<Inner>` and the other impls which
+///   `strong_id!` provides, for deriving on an existing newtype instead of declaring it with the
+///   macro. Only supported for non-generic tuple structs with a single field. Ordering is only
+///   implemented with `ord`, and `From<Id> for Inner` with `into_inner`, so that the struct can keep
+///   its own, e.g. a derived `Ord`.
+/// - `skip_serde` - don't implement `Serialize` and `Deserialize` when the "serde" feature is
+///   enabled, so that the type can implement its own representation.
+///
+/// Deriving for an enum generates the same impls as `strong_id_enum!`, i.e. `FromStr` dispatched on
+/// the prefix, `Display`, `ErasedStrongId`, `From` for each variant and serde, along with `is_*`
+/// and `as_*` accessors named after each variant in snake case, e.g. `as_order_line` for
+/// `OrderLine(OrderLineId)`. Only the `crate` option is supported for enums.
 #[proc_macro_derive(StrongId, attributes(strong_id))]
 pub fn derive_strong_id(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	let input = parse_macro_input!(input as DeriveInput);
 
-	let name = &input.ident;
+	if let Data::Enum(data) = &input.data {
+		return derive_strong_id_enum(&input, data);
+	}
+
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	let name = quote!(#ident #ty_generics);
 
 	let attributes = match from_input(&input) {
 		Err(error) => {
@@ -95,38 +689,168 @@ pub fn derive_strong_id(input: proc_macro::TokenStream) -> proc_macro::TokenStre
 		Ok(attributes) => attributes,
 	};
 
-	let prefix_expr = match attributes.prefix {
-		Some(prefix) => {
-			assert_prefix_valid(&prefix);
-			quote!(Some(#prefix))
+	let krate = &attributes.krate;
+	let member = &attributes.field.member;
+	let from_value = attributes.field.construct(quote!(value));
+	let from_other = attributes.field.construct(quote!(other.into()));
+
+	if attributes.deny_sequential && is_sequential_type(&attributes.suffix_type) {
+		let error = syn::Error::new_spanned(
+			&attributes.suffix_type,
+			"`deny_sequential` is set, but the ID is backed by a sequential integer type which may be enumerated",
+		)
+		.to_compile_error();
+		return quote!(#error).into();
+	}
+
+	if attributes.default_nil
+		&& matches!(
+			constant_type(&attributes.suffix_type),
+			Some(ConstantType::NonZero(_, _))
+		) {
+		let error = syn::Error::new_spanned(
+			&attributes.suffix_type,
+			"`default_nil` is set, but the ID is backed by a `NonZero` integer which has no zero value",
+		)
+		.to_compile_error();
+		return quote!(#error).into();
+	}
+
+	if attributes.standalone
+		&& (input.generics.lt_token.is_some()
+			|| attributes.field.member != Member::Unnamed(0.into())
+			|| !attributes.field.defaulted.is_empty())
+	{
+		let error = syn::Error::new_spanned(
+			ident,
+			"`standalone` is only supported for non-generic tuple structs with a single field",
+		)
+		.to_compile_error();
+		return quote!(#error).into();
+	}
+
+	let prefix_expr = match &attributes.prefix {
+		Some(prefix) => quote!(Some(#prefix)),
+		None => quote!(None),
+	};
+
+	// the prefix and its separator
+	let prefix_len = attributes
+		.prefix
+		.as_ref()
+		.map_or(0, |prefix| prefix.len() + 1);
+
+	// foreign traits can't be implemented for a bare type parameter, e.g. `From<Scoped<T>> for T`
+	let generic_inner = is_type_param(&attributes.suffix_type, &input.generics);
+
+	let storage_column = match storage_column(&attributes.suffix_type) {
+		Some(column) => {
+			let column = Ident::new(column, proc_macro::Span::call_site().into());
+			quote!(#krate::ColumnType::#column)
 		}
 		None => {
-			quote!(None)
+			let suffix_type = &attributes.suffix_type;
+			quote! {
+				#krate::ColumnType::Binary(::core::mem::size_of::<<#suffix_type as #krate::Id>::Bytes>())
+			}
 		}
 	};
+	let storage_index = match &attributes.index_strategy {
+		Some(strategy) => quote!(#krate::IndexStrategy::#strategy),
+		None => quote!(#krate::IndexStrategy::Ordered),
+	};
 
 	let suffix_type = attributes.suffix_type;
 	let suffix_type = quote!(#suffix_type);
 
-	let serde = if cfg!(feature = "serde") {
+	let case_insensitive = attributes.case_insensitive;
+	let default_encoding = attributes.encoding.is_none();
+
+	let encoding = match attributes.encoding {
+		Some(encoding) => quote!(#encoding),
+		None => quote!(#krate::Base32),
+	};
+
+	// `Encoding::encoded_len` can't be called in a const, so the length is computed here
+	let suffix_len = quote! {
+		#krate::_internal_encoded_len(
+			::core::mem::size_of::<<#suffix_type as #krate::Id>::Bytes>(),
+			<#encoding as #krate::Encoding>::MIN_PAD_BITS,
+			<#encoding as #krate::Encoding>::SYMBOL_BITS,
+		) + <#encoding as #krate::Encoding>::CHECK_LEN
+	};
+
+	let serde = if cfg!(feature = "serde") && !attributes.skip_serde {
+		let (serialize, deserialize) = match attributes.serde {
+			SerdeRepr::String => (
+				quote!(serializer.serialize_str(&self.to_string())),
+				quote! {
+					::std::string::String::deserialize(deserializer)?
+						.parse::<Self>()
+						.map_err(|error| #krate::serde::de::Error::custom(error.to_string()))
+				},
+			),
+			SerdeRepr::Struct => (
+				quote!(#krate::serde_helpers::struct_form::serialize(
+					self, serializer
+				)),
+				quote!(#krate::serde_helpers::struct_form::deserialize(
+					deserializer
+				)),
+			),
+			SerdeRepr::Uuid => (
+				quote!(#krate::serde_helpers::uuid_form::serialize(self, serializer)),
+				quote!(#krate::serde_helpers::uuid_form::deserialize(deserializer)),
+			),
+			SerdeRepr::Bytes => (
+				quote!(#krate::serde_helpers::bytes_form::serialize(self, serializer)),
+				quote!(#krate::serde_helpers::bytes_form::deserialize(deserializer)),
+			),
+			SerdeRepr::Integer => (
+				quote!(#krate::serde_helpers::integer_form::serialize(self, serializer)),
+				quote!(#krate::serde_helpers::integer_form::deserialize(deserializer)),
+			),
+			SerdeRepr::Cbor => (
+				quote!(#krate::serde_helpers::cbor::serialize(self, serializer)),
+				quote!(#krate::serde_helpers::cbor::deserialize(deserializer)),
+			),
+			SerdeRepr::Rmp => {
+				let ext_type = match &attributes.ext_type {
+					Some(ext_type) => quote!(#ext_type),
+					None => quote!(#krate::serde_helpers::rmp::EXT_TYPE),
+				};
+				(
+					quote!(#krate::serde_helpers::rmp::serialize_with_ext_type(
+						self, #ext_type, serializer
+					)),
+					quote!(#krate::serde_helpers::rmp::deserialize_with_ext_type(
+						#ext_type,
+						deserializer
+					)),
+				)
+			}
+		};
+
+		let mut de_generics = input.generics.clone();
+		de_generics.params.insert(0, parse_quote!('de));
+		let (de_impl_generics, _, _) = de_generics.split_for_impl();
+
 		quote! {
-			impl ::strong_id::serde::Serialize for #name {
+			impl #impl_generics #krate::serde::Serialize for #name #where_clause {
 				 fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
 				 where
-					  S: ::strong_id::serde::Serializer,
+					  S: #krate::serde::Serializer,
 				 {
-					  serializer.serialize_str(&self.to_string())
+					  #serialize
 				 }
 			}
 
-			impl<'de> ::strong_id::serde::Deserialize<'de> for #name {
+			impl #de_impl_generics #krate::serde::Deserialize<'de> for #name #where_clause {
 				 fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
 				 where
-					  D: ::strong_id::serde::Deserializer<'de>,
+					  D: #krate::serde::Deserializer<'de>,
 				 {
-					  ::std::string::String::deserialize(deserializer)?
-						   .parse::<Self>()
-						   .map_err(|error| ::strong_id::serde::de::Error::custom(error.to_string()))
+					  #deserialize
 				 }
 			}
 		}
@@ -134,37 +858,360 @@ pub fn derive_strong_id(input: proc_macro::TokenStream) -> proc_macro::TokenStre
 		quote!()
 	};
 
+	let deref = if attributes.deref {
+		quote! {
+			impl #impl_generics ::core::ops::Deref for #name #where_clause {
+				type Target = #suffix_type;
+
+				fn deref(&self) -> &Self::Target {
+					&self.#member
+				}
+			}
+
+			impl #impl_generics ::core::convert::AsRef<#suffix_type> for #name #where_clause {
+				fn as_ref(&self) -> &#suffix_type {
+					&self.#member
+				}
+			}
+		}
+	} else {
+		quote!()
+	};
+
+	let default_nil = if attributes.default_nil {
+		let mut default_generics = input.generics.clone();
+		default_generics
+			.make_where_clause()
+			.predicates
+			.push(parse_quote!(#suffix_type: ::core::default::Default));
+		let default_where_clause = &default_generics.where_clause;
+		let from_default = attributes
+			.field
+			.construct(quote!(::core::default::Default::default()));
+
+		quote! {
+			impl #impl_generics ::core::default::Default for #name #default_where_clause {
+				fn default() -> Self {
+					#from_default
+				}
+			}
+		}
+	} else {
+		quote!()
+	};
+
+	// the impls which `strong_id!` adds to the struct it declares
+	let standalone = if attributes.standalone {
+		let prefix = attributes.prefix.as_ref().map(|prefix| quote!(#prefix));
+		quote! {
+			#krate::_internal_impl_common!(@@internal #ident(#suffix_type));
+
+			#krate::_internal_impl_from_str!(@@internal #ident(#suffix_type => #prefix));
+		}
+	} else {
+		quote!()
+	};
+
+	let into_inner = if !attributes.into_inner || attributes.no_into_inner || generic_inner {
+		quote!()
+	} else {
+		quote! {
+			impl #impl_generics ::core::convert::From<#name> for #suffix_type #where_clause {
+				fn from(value: #name) -> Self {
+					value.#member
+				}
+			}
+		}
+	};
+
+	let ordering = if !attributes.ord || attributes.unordered {
+		quote!()
+	} else {
+		let mut ord_generics = input.generics.clone();
+		ord_generics
+			.make_where_clause()
+			.predicates
+			.push(parse_quote!(#suffix_type: ::core::cmp::Ord));
+		let ord_where_clause = &ord_generics.where_clause;
+
+		let inner_ordering = if generic_inner {
+			quote!()
+		} else {
+			quote! {
+				impl #impl_generics ::core::cmp::PartialOrd<#suffix_type> for #name #ord_where_clause {
+					fn partial_cmp(&self, other: &#suffix_type) -> Option<::core::cmp::Ordering> {
+						::core::cmp::PartialOrd::partial_cmp(&self.#member, other)
+					}
+				}
+
+				impl #impl_generics ::core::cmp::PartialOrd<#name> for #suffix_type #ord_where_clause {
+					fn partial_cmp(&self, other: &#name) -> Option<::core::cmp::Ordering> {
+						::core::cmp::PartialOrd::partial_cmp(self, &other.#member)
+					}
+				}
+			}
+		};
+
+		quote! {
+			impl #impl_generics ::core::cmp::PartialOrd for #name #ord_where_clause {
+				fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+					Some(::core::cmp::Ord::cmp(self, other))
+				}
+			}
+
+			impl #impl_generics ::core::cmp::Ord for #name #ord_where_clause {
+				fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+					::core::cmp::Ord::cmp(&self.#member, &other.#member)
+				}
+			}
+
+			#inner_ordering
+		}
+	};
+
+	let accessors = attributes.accessors.iter().map(|accessor| {
+		quote! {
+			/// The inner value of the ID
+			pub fn #accessor(&self) -> &#suffix_type {
+				&self.#member
+			}
+		}
+	});
+
 	let expanded = quote! {
-		impl ::strong_id::StrongId<#suffix_type> for #name {
+		impl #impl_generics #krate::StrongId<#suffix_type> for #name #where_clause {
 			fn prefix(&self) -> Option<&str> {
 				#prefix_expr
 			}
 
 			fn id(&self) -> &#suffix_type {
-				&self.0
+				&self.#member
+			}
+
+			fn encode_into(&self, dst: &mut [u8]) -> ::core::result::Result<usize, #krate::Error> {
+				#krate::_internal_encode_into::<#suffix_type, #encoding>(#prefix_expr, &self.#member, dst)
+			}
+		}
+
+		impl #impl_generics #name #where_clause {
+			/// The prefix of this ID type, if any
+			pub const PREFIX: Option<&'static str> = #prefix_expr;
+
+			/// The length of an encoded ID of this type, including the prefix and separator
+			pub const ENCODED_LEN: usize = #prefix_len + #suffix_len;
+
+			/// A description of the format of IDs of this type, for showing to users
+			pub const EXPECTED_FORMAT: #krate::ExpectedFormat<'static> = #krate::ExpectedFormat::new(
+				#prefix_expr,
+				#suffix_len,
+			)
+			.with_symbols(<#encoding as #krate::Encoding>::SYMBOLS);
+
+			/// Recommendations for storing IDs of this type in a database, see `StorageHint`
+			pub const STORAGE_HINT: #krate::StorageHint = #krate::StorageHint::new(
+				#storage_column,
+				Self::ENCODED_LEN,
+				#storage_index,
+			);
+
+			/// The encoded suffix of the ID, without the prefix and separator
+			pub fn suffix_encoded(&self) -> ::std::string::String {
+				#krate::Id::encode_with::<#encoding>(&self.#member)
+			}
+
+			/// Encode the complete ID into the start of `dst` without allocating, returning the
+			/// number of bytes written. Fails with `Error::BufferTooSmall` if `dst` is shorter than
+			/// `ENCODED_LEN`.
+			pub fn encode_into(&self, dst: &mut [u8]) -> ::core::result::Result<usize, #krate::Error> {
+				#krate::_internal_encode_into::<#suffix_type, #encoding>(#prefix_expr, &self.#member, dst)
+			}
+
+			/// Whether `FromStr` parses IDs with `from_str_relaxed`
+			#[doc(hidden)]
+			pub const _INTERNAL_CASE_INSENSITIVE: bool = #case_insensitive;
+
+			/// Whether the suffix uses the default encoding, which constants are decoded with
+			#[doc(hidden)]
+			pub const _INTERNAL_DEFAULT_ENCODING: bool = #default_encoding;
+
+			/// Normalize the case of an encoded suffix, see `from_str_relaxed`
+			#[doc(hidden)]
+			pub fn _internal_normalize_suffix(suffix: &str) -> ::std::borrow::Cow<'_, str> {
+				#krate::_internal_normalize_case::<#encoding>(suffix)
+			}
+
+			/// The encoded suffix, uppercased unless the encoding is case-sensitive
+			#[doc(hidden)]
+			pub fn _internal_suffix_uppercase(&self) -> ::std::string::String {
+				let mut suffix = self.suffix_encoded();
+				if <#encoding as #krate::Encoding>::UPPERCASE {
+					suffix.make_ascii_uppercase();
+				}
+				suffix
+			}
+
+			/// Decode an ID from its encoded suffix, without the prefix and separator
+			pub fn from_suffix_encoded(suffix: &str) -> ::core::result::Result<Self, #krate::Error> {
+				<#suffix_type as #krate::Id>::decode_with::<#encoding, _>(suffix).map(|value| #from_value)
+			}
+
+			/// Split an encoded ID into its prefix and suffix
+			#[doc(hidden)]
+			pub fn _internal_split_suffix(value: &str) -> Option<(&str, &str)> {
+				#krate::_internal_split_suffix::<#encoding>(value, #suffix_len)
+			}
+
+			/// Consume the ID and return the inner value
+			pub fn into_inner(self) -> #suffix_type {
+				self.#member
+			}
+
+			#(#accessors)*
+
+			/// Consume the ID and return its prefix and inner value
+			pub fn into_parts(self) -> (Option<#krate::Prefix<'static>>, #suffix_type) {
+				(Self::PREFIX.map(#krate::Prefix::from), self.#member)
+			}
+
+			/// Create this ID from another ID type with the same inner value, e.g. when an entity
+			/// changes type
+			pub fn from_id<S>(other: S) -> Self
+			where
+				S: #krate::StrongId<#suffix_type> + Into<#suffix_type>,
+			{
+				#from_other
+			}
+
+			/// Convert this ID into another ID type with the same inner value
+			pub fn cast<S>(self) -> S
+			where
+				S: #krate::StrongId<#suffix_type> + From<#suffix_type>,
+			{
+				S::from(self.#member)
+			}
+
+			/// Convert this ID into another ID type with a wider inner value, e.g. when migrating
+			/// from `u32` to `u64` keys
+			pub fn widen<S, U>(self) -> S
+			where
+				U: #krate::Id + From<#suffix_type>,
+				S: #krate::StrongId<U> + From<U>,
+			{
+				S::from(U::from(self.#member))
+			}
+
+			/// Convert this ID into another ID type with a narrower inner value, failing with
+			/// `Error::OutOfRange` if the value does not fit
+			pub fn narrow<S, U>(self) -> ::core::result::Result<S, #krate::Error>
+			where
+				U: #krate::Id + ::core::convert::TryFrom<#suffix_type>,
+				S: #krate::StrongId<U> + From<U>,
+			{
+				<U as ::core::convert::TryFrom<#suffix_type>>::try_from(self.#member)
+					.map(S::from)
+					.map_err(|_| #krate::Error::OutOfRange)
+			}
+		}
+
+		impl #impl_generics #krate::StaticPrefix for #name #where_clause {
+			type Inner = #suffix_type;
+
+			const PREFIX: Option<&'static str> = #prefix_expr;
+		}
+
+		impl #impl_generics #krate::ErasedStrongId for #name #where_clause {
+			fn erased_prefix(&self) -> Option<&str> {
+				#prefix_expr
+			}
+
+			fn encoded(&self) -> ::std::string::String {
+				self.suffix_encoded()
 			}
 		}
 
+		#deref
+
+		#default_nil
+
+		#standalone
+
+		#into_inner
+
+		#ordering
+
 		#serde
 	};
 
 	proc_macro::TokenStream::from(expanded)
 }
 
-#[proc_macro_derive(StrongUuid)]
+/// Derive `StrongUuid` for a struct which also derives `StrongId` with a `Uuid` inner value
+///
+/// Configured with the `#[strong_id(...)]` attribute, in addition to the options of `StrongId`:
+///
+/// - `default = v4 | v7` - generate an inherent `new()`, which generates an ID with the given UUID
+///   version, so that call sites don't choose a version for each type. Requires the "uuid-v4" or
+///   "uuid-v7" feature.
+#[proc_macro_derive(StrongUuid, attributes(strong_id))]
 pub fn derive_strong_id_uuid(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	let input = parse_macro_input!(input as DeriveInput);
 
-	let name = &input.ident;
+	let ident = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	let name = quote!(#ident #ty_generics);
+
+	let attributes = match from_input(&input) {
+		Err(error) => {
+			let error = error.to_compile_error();
+			return quote!(#error).into();
+		}
+		Ok(attributes) => attributes,
+	};
+
+	let krate = &attributes.krate;
+	let member = &attributes.field.member;
+	let from_value = attributes.field.construct(quote!(value));
+
+	let default_new = match &attributes.default_version {
+		Some(version) => {
+			let (feature, enabled, constructor) = if version == "v4" {
+				("uuid-v4", cfg!(feature = "uuid-v4"), quote!(new_v4))
+			} else {
+				("uuid-v7", cfg!(feature = "uuid-v7"), quote!(now_v7))
+			};
+
+			if !enabled {
+				let error = syn::Error::new(
+					version.span(),
+					format!("`default = {version}` requires the \"{feature}\" feature"),
+				)
+				.to_compile_error();
+				return quote!(#error).into();
+			}
+
+			let doc = format!("Generate an ID from a new {version} UUID");
+			quote! {
+				impl #impl_generics #name #where_clause {
+					#[doc = #doc]
+					pub fn new() -> Self {
+						<Self as #krate::StrongUuid>::#constructor()
+					}
+				}
+			}
+		}
+		None => quote!(),
+	};
 
 	let uuid_v1_impl = if cfg!(feature = "uuid-v1") {
 		quote! {
-			fn new_v1(ts: ::strong_id::uuid::Timestamp, node_id: &[u8; 6]) -> Self {
-				Self(::strong_id::uuid::Uuid::new_v1(ts, node_id))
+			fn new_v1(ts: #krate::uuid::Timestamp, node_id: &[u8; 6]) -> Self {
+				let value = #krate::uuid::Uuid::new_v1(ts, node_id);
+				#from_value
 			}
 
 			fn now_v1(node_id: &[u8; 6]) -> Self {
-				Self(::strong_id::uuid::Uuid::now_v1(node_id))
+				let value = #krate::uuid::Uuid::now_v1(node_id);
+				#from_value
 			}
 		}
 	} else {
@@ -173,8 +1220,9 @@ pub fn derive_strong_id_uuid(input: proc_macro::TokenStream) -> proc_macro::Toke
 
 	let uuid_v3_impl = if cfg!(feature = "uuid-v3") {
 		quote! {
-			fn new_v3(namespace: &::strong_id::uuid::Uuid, name: &[u8]) -> Self {
-				Self (::strong_id::uuid::Uuid::new_v3(namespace, name))
+			fn new_v3(namespace: &#krate::uuid::Uuid, name: &[u8]) -> Self {
+				let value = #krate::uuid::Uuid::new_v3(namespace, name);
+				#from_value
 			}
 		}
 	} else {
@@ -184,7 +1232,8 @@ pub fn derive_strong_id_uuid(input: proc_macro::TokenStream) -> proc_macro::Toke
 	let uuid_v4_impl = if cfg!(feature = "uuid-v4") {
 		quote! {
 			fn new_v4() -> Self {
-				Self(::strong_id::uuid::Uuid::new_v4())
+				let value = #krate::uuid::Uuid::new_v4();
+				#from_value
 			}
 		}
 	} else {
@@ -193,8 +1242,9 @@ pub fn derive_strong_id_uuid(input: proc_macro::TokenStream) -> proc_macro::Toke
 
 	let uuid_v5_impl = if cfg!(feature = "uuid-v5") {
 		quote! {
-			fn new_v5(namespace: &::strong_id::uuid::Uuid, name: &[u8]) -> Self {
-				Self(::strong_id::uuid::Uuid::new_v5(namespace, name))
+			fn new_v5(namespace: &#krate::uuid::Uuid, name: &[u8]) -> Self {
+				let value = #krate::uuid::Uuid::new_v5(namespace, name);
+				#from_value
 			}
 		}
 	} else {
@@ -203,12 +1253,14 @@ pub fn derive_strong_id_uuid(input: proc_macro::TokenStream) -> proc_macro::Toke
 
 	let uuid_v6_impl = if cfg!(feature = "uuid-v6") {
 		quote! {
-			fn new_v6(ts: ::strong_id::uuid::Timestamp, node_id: &[u8; 6]) -> Self {
-				Self(::strong_id::uuid::Uuid::new_v6(ts, node_id))
+			fn new_v6(ts: #krate::uuid::Timestamp, node_id: &[u8; 6]) -> Self {
+				let value = #krate::uuid::Uuid::new_v6(ts, node_id);
+				#from_value
 			}
 
 			fn now_v6(node_id: &[u8; 6]) -> Self {
-				Self(::strong_id::uuid::Uuid::now_v6(node_id))
+				let value = #krate::uuid::Uuid::now_v6(node_id);
+				#from_value
 			}
 		}
 	} else {
@@ -217,12 +1269,14 @@ pub fn derive_strong_id_uuid(input: proc_macro::TokenStream) -> proc_macro::Toke
 
 	let uuid_v7_impl = if cfg!(feature = "uuid-v7") {
 		quote! {
-			fn new_v7(ts: ::strong_id::uuid::Timestamp) -> Self {
-				Self(::strong_id::uuid::Uuid::new_v7(ts))
+			fn new_v7(ts: #krate::uuid::Timestamp) -> Self {
+				let value = #krate::uuid::Uuid::new_v7(ts);
+				#from_value
 			}
 
 			fn now_v7() -> Self {
-				Self(::strong_id::uuid::Uuid::now_v7())
+				let value = #krate::uuid::Uuid::now_v7();
+				#from_value
 			}
 		}
 	} else {
@@ -232,7 +1286,8 @@ pub fn derive_strong_id_uuid(input: proc_macro::TokenStream) -> proc_macro::Toke
 	let uuid_v8_impl = if cfg!(feature = "uuid-v8") {
 		quote! {
 			fn new_v8(buf: [u8; 16]) -> Self {
-				Self(::strong_id::uuid::Uuid::new_v8(buf))
+				let value = #krate::uuid::Uuid::new_v8(buf);
+				#from_value
 			}
 		}
 	} else {
@@ -240,9 +1295,18 @@ pub fn derive_strong_id_uuid(input: proc_macro::TokenStream) -> proc_macro::Toke
 	};
 
 	let expanded = quote! {
-		impl ::strong_id::StrongUuid for #name {
+		impl #impl_generics #krate::StrongUuid for #name #where_clause {
 			fn from_u128(v: u128) -> Self {
-				Self(::strong_id::uuid::Uuid::from_u128(v))
+				let value = #krate::uuid::Uuid::from_u128(v);
+				#from_value
+			}
+
+			fn as_bytes(&self) -> &[u8; 16] {
+				self.#member.as_bytes()
+			}
+
+			fn to_bytes(self) -> [u8; 16] {
+				self.#member.into_bytes()
 			}
 
 			#uuid_v1_impl
@@ -253,33 +1317,374 @@ pub fn derive_strong_id_uuid(input: proc_macro::TokenStream) -> proc_macro::Toke
 			#uuid_v7_impl
 			#uuid_v8_impl
 		}
+
+		impl #impl_generics From<#name> for [u8; 16] #where_clause {
+			fn from(value: #name) -> Self {
+				value.#member.into_bytes()
+			}
+		}
+
+		impl #impl_generics From<#krate::uuid::fmt::Hyphenated> for #name #where_clause {
+			fn from(value: #krate::uuid::fmt::Hyphenated) -> Self {
+				let value = #krate::uuid::Uuid::from(value);
+				#from_value
+			}
+		}
+
+		impl #impl_generics From<#krate::uuid::fmt::Simple> for #name #where_clause {
+			fn from(value: #krate::uuid::fmt::Simple) -> Self {
+				let value = #krate::uuid::Uuid::from(value);
+				#from_value
+			}
+		}
+
+		impl #impl_generics From<#krate::uuid::fmt::Urn> for #name #where_clause {
+			fn from(value: #krate::uuid::fmt::Urn) -> Self {
+				let value = #krate::uuid::Uuid::from(value);
+				#from_value
+			}
+		}
+
+		impl #impl_generics From<#krate::uuid::fmt::Braced> for #name #where_clause {
+			fn from(value: #krate::uuid::fmt::Braced) -> Self {
+				let value = #krate::uuid::Uuid::from(value);
+				#from_value
+			}
+		}
+
+		#default_new
 	};
 
 	proc_macro::TokenStream::from(expanded)
 }
 
-/// Validate `&'static str` prefixes at compile-time
+/// Validate a TypeID literal at compile-time, and create a `DynamicStrongId<'static, Uuid>` from it
 ///
-/// ```
-/// # fn main() {
-/// # use strong_id_macros::prefix;
-/// prefix!("user");
-/// prefix!("user_account");
-/// # }
+/// The prefix and the base32 encoded UUID suffix are both validated, so that an invalid literal,
+/// e.g. a hardcoded test fixture, fails to compile instead of failing at runtime. The ID can be
+/// used to initialize a `const`. Requires the "uuid" feature of `strong_id`.
 ///
+/// ```ignore
+/// use strong_id::{typeid, DynamicStrongId};
+/// use uuid::Uuid;
 ///
-/// // prefix!("USER");
+/// const SYSTEM_USER: DynamicStrongId<'static, Uuid> = typeid!("user_01h455vb4pex5vsknk084sn02q");
 /// ```
 #[proc_macro]
-pub fn prefix(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+pub fn typeid(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	let input = parse_macro_input!(input as LitStr);
 	let value = input.value();
 
-	let expanded = if value.is_empty() {
-		quote!(None)
+	let (prefix, suffix) = match value.rsplit_once('_') {
+		Some((prefix, suffix)) => (Some(prefix), suffix),
+		None => (None, value.as_str()),
+	};
+
+	let prefix = match prefix.map(|prefix| validate_prefix(prefix).map(|_| prefix)) {
+		Some(Err(error)) => {
+			return syn::Error::new(input.span(), error)
+				.to_compile_error()
+				.into()
+		}
+		Some(Ok(prefix)) => quote!(Some(#prefix)),
+		None => quote!(None),
+	};
+
+	let suffix = match decode_suffix(suffix, 16) {
+		Ok(suffix) => suffix,
+		Err(error) => {
+			return syn::Error::new(input.span(), error)
+				.to_compile_error()
+				.into()
+		}
+	};
+
+	let expanded = quote! {
+		::strong_id::DynamicStrongId::<::strong_id::uuid::Uuid>::_internal_from_static(
+			#prefix,
+			#suffix,
+		)
+	};
+
+	proc_macro::TokenStream::from(expanded)
+}
+
+/// Create a `Prefix<'static>` from a `&'static str`, validated at compile-time
+///
+/// The prefix can be used to initialize a `const`. Empty prefixes are rejected, see
+/// [`optional_prefix!`](macro@optional_prefix) for IDs which may not have a prefix.
+///
+/// ```
+/// use strong_id::{prefix, DynamicStrongId, Prefix};
+///
+/// const USER: Prefix<'static> = prefix!("user");
+///
+/// let user_id = DynamicStrongId::<u16>::new(USER, 3203).unwrap();
+/// assert_eq!(user_id.to_string(), "user_0343");
+/// ```
+///
+/// The prefix refers to `::strong_id`. When `strong_id` is re-exported from another crate, the path
+/// to it is given with `crate = "..."`, like the `crate` option of the derives:
+///
+/// ```
+/// mod prelude {
+///     pub use strong_id as reexported;
+/// }
+///
+/// use prelude::reexported::{prefix, Prefix};
+///
+/// const USER: Prefix<'static> = prefix!("user", crate = "prelude::reexported");
+/// assert_eq!(USER.as_str(), "user");
+/// ```
+///
+/// Invalid prefixes fail to compile, with an error pointing at the literal:
+///
+/// ```compile_fail
+/// # use strong_id_macros::prefix;
+/// prefix!("USER");
+/// ```
+#[proc_macro]
+pub fn prefix(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let PrefixInput {
+		literal: input,
+		krate,
+	} = parse_macro_input!(input as PrefixInput);
+
+	let expanded = match validate_prefix_literal(&input) {
+		Ok(()) => quote!(#krate::Prefix::from_static(#input)),
+		Err(error) => error.to_compile_error(),
+	};
+
+	proc_macro::TokenStream::from(expanded)
+}
+
+/// Create an `Option<Prefix<'static>>` from a `&'static str`, validated at compile-time
+///
+/// An empty literal is `None`, like the empty prefixes of generated StrongIds. The path to
+/// `strong_id` is given with `crate = "..."`, as with [`prefix!`](macro@prefix).
+///
+/// ```
+/// use strong_id::{optional_prefix, Prefix};
+///
+/// const USER: Option<Prefix<'static>> = optional_prefix!("user");
+/// const PLAIN: Option<Prefix<'static>> = optional_prefix!("");
+///
+/// assert_eq!(USER.as_ref().map(Prefix::as_str), Some("user"));
+/// assert_eq!(PLAIN, None);
+/// ```
+#[proc_macro]
+pub fn optional_prefix(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let PrefixInput {
+		literal: input,
+		krate,
+	} = parse_macro_input!(input as PrefixInput);
+
+	let expanded = if input.value().is_empty() {
+		quote!(::core::option::Option::None::<#krate::Prefix<'static>>)
 	} else {
-		assert_prefix_valid(&value);
-		quote!(#value)
+		match validate_prefix_literal(&input) {
+			Ok(()) => {
+				quote!(::core::option::Option::Some(#krate::Prefix::from_static(#input)))
+			}
+			Err(error) => error.to_compile_error(),
+		}
+	};
+
+	proc_macro::TokenStream::from(expanded)
+}
+
+/// The input of [`prefix!`](macro@prefix) and [`optional_prefix!`](macro@optional_prefix), a prefix
+/// literal and an optional `crate = "..."` path to `strong_id`
+struct PrefixInput {
+	literal: LitStr,
+	krate: Path,
+}
+
+impl syn::parse::Parse for PrefixInput {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let literal = input.parse()?;
+		let mut krate = parse_quote!(::strong_id);
+
+		if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+			input.parse::<Token![crate]>()?;
+			input.parse::<Token![=]>()?;
+
+			// accept a string or a bare path, like the `crate` option of the derives
+			krate = if input.peek(LitStr) {
+				input.parse::<LitStr>()?.parse::<Path>()?
+			} else {
+				input.call(Path::parse_mod_style)?
+			};
+			input.parse::<Option<Token![,]>>()?;
+		}
+
+		Ok(Self { literal, krate })
+	}
+}
+
+/// Validate a prefix literal, with an error pointing at the literal
+fn validate_prefix_literal(input: &LitStr) -> Result<(), syn::Error> {
+	validate_prefix(&input.value()).map_err(|error| syn::Error::new(input.span(), error))
+}
+
+/// Join `&'static str` prefixes with underscores into a delimited prefix, validated at compile-time
+///
+/// Requires the "delimited" feature, as the joined prefix contains underscores.
+///
+#[cfg_attr(feature = "delimited", doc = "```")]
+#[cfg_attr(not(feature = "delimited"), doc = "```ignore")]
+/// # fn main() {
+/// # use strong_id_macros::concat_prefix;
+/// assert_eq!(concat_prefix!("billing", "invoice"), "billing_invoice");
+/// # }
+/// ```
+#[proc_macro]
+pub fn concat_prefix(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let parts = parse_macro_input!(input with Punctuated::<LitStr, Token![,]>::parse_terminated);
+
+	if parts.is_empty() {
+		return syn::Error::new(
+			proc_macro::Span::call_site().into(),
+			"expected at least one prefix",
+		)
+		.to_compile_error()
+		.into();
+	}
+
+	if let Some(part) = parts.iter().find(|part| part.value().is_empty()) {
+		return syn::Error::new(part.span(), "prefixes must be non-empty")
+			.to_compile_error()
+			.into();
+	}
+
+	let value = parts
+		.iter()
+		.map(LitStr::value)
+		.collect::<Vec<_>>()
+		.join("_");
+
+	if parts.len() > 1 && !cfg!(feature = "delimited") {
+		return syn::Error::new(
+			parts[1].span(),
+			"joining prefixes requires the \"delimited\" feature",
+		)
+		.to_compile_error()
+		.into();
+	}
+
+	if let Err(error) = validate_prefix(&value) {
+		return syn::Error::new_spanned(parts, format!("`{value}`: {error}"))
+			.to_compile_error()
+			.into();
+	}
+
+	proc_macro::TokenStream::from(quote!(#value))
+}
+
+/// The input of `_internal_constant!`, `inner, (prefix), "value"`
+struct ConstantInput {
+	inner: Type,
+	prefix: Option<LitStr>,
+	value: LitStr,
+}
+
+impl syn::parse::Parse for ConstantInput {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let inner = input.parse()?;
+		input.parse::<Token![,]>()?;
+		let content;
+		syn::parenthesized!(content in input);
+		let prefix = content.parse()?;
+		input.parse::<Token![,]>()?;
+		let value = input.parse()?;
+
+		Ok(Self {
+			inner,
+			prefix,
+			value,
+		})
+	}
+}
+
+/// Validate a constant ID of a `strong_id!` declaration at compile-time, and create its inner value
+#[doc(hidden)]
+#[proc_macro]
+pub fn _internal_constant(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = parse_macro_input!(input as ConstantInput);
+	let inner = &input.inner;
+	let value = input.value.value();
+
+	let constant =
+		match constant_type(inner) {
+			Some(constant) => constant,
+			None => return syn::Error::new_spanned(
+				inner,
+				"constants can only be declared for IDs backed by fixed-width integers or `Uuid`",
+			)
+			.to_compile_error()
+			.into(),
+		};
+
+	let suffix = match &input.prefix {
+		Some(prefix) => value
+			.strip_prefix(&prefix.value())
+			.and_then(|suffix| suffix.strip_prefix('_')),
+		None => Some(value.as_str()),
+	};
+	let suffix = match suffix {
+		Some(suffix) => suffix,
+		None => {
+			let prefix = input
+				.prefix
+				.map(|prefix| prefix.value())
+				.unwrap_or_default();
+			return syn::Error::new(
+				input.value.span(),
+				format!("expected an ID starting with `{prefix}_`"),
+			)
+			.to_compile_error()
+			.into();
+		}
+	};
+
+	let byte_len = match &constant {
+		ConstantType::Unsigned(len) | ConstantType::Signed(len) | ConstantType::NonZero(len, _) => {
+			*len
+		}
+		ConstantType::Uuid => 16,
+	};
+
+	let decoded = match decode_suffix(suffix, byte_len) {
+		Ok(decoded) => decoded,
+		Err(error) => {
+			return syn::Error::new(input.value.span(), error)
+				.to_compile_error()
+				.into()
+		}
+	};
+
+	let expanded = match constant {
+		ConstantType::Unsigned(_) => quote!(#decoded as #inner),
+		// signed integers are encoded in offset-binary, see `impl_strong_int!`
+		ConstantType::Signed(len) => {
+			let decoded = decoded ^ (1 << (len * 8 - 1));
+			quote!(#decoded as #inner)
+		}
+		ConstantType::NonZero(_, unsigned) => {
+			if decoded == 0 {
+				return syn::Error::new(input.value.span(), "the ID may not be zero")
+					.to_compile_error()
+					.into();
+			}
+
+			quote! {
+				match <#inner>::new(#decoded as #unsigned) {
+					::core::option::Option::Some(value) => value,
+					::core::option::Option::None => ::core::unreachable!(),
+				}
+			}
+		}
+		ConstantType::Uuid => quote!(<#inner>::from_u128(#decoded)),
 	};
 
 	proc_macro::TokenStream::from(expanded)
@@ -0,0 +1,3 @@
+//! Re-exports `strong_id`, for crates which only depend on it through another crate
+
+pub use strong_id;
@@ -0,0 +1,33 @@
+use reexport_facade::strong_id::{strong_id, strong_uuid};
+
+strong_id!(pub struct Unprefixed(u32));
+strong_id!(pub struct UserId(u32 => "user"));
+strong_id!(pub struct OrgUserId(u32, u64 => "orguser"));
+strong_uuid!(pub struct SessionId(pub Uuid => "session"));
+
+#[cfg(test)]
+mod tests {
+	use super::{OrgUserId, Unprefixed, UserId};
+	use reexport_facade::strong_id::{optional_prefix, prefix, DynamicStrongId, Prefix, StrongId};
+
+	#[test]
+	fn generated() {
+		assert_eq!(Unprefixed::from(3203).prefix(), None);
+		assert_eq!(UserId::from(3203).to_string(), "user_0000343");
+		assert_eq!("user_0000343".parse::<UserId>(), Ok(UserId::from(3203)));
+		assert_eq!(OrgUserId::PREFIX, Some("orguser"));
+		let id = OrgUserId::from((1, 2));
+		assert_eq!(id.to_string().parse::<OrgUserId>(), Ok(id));
+	}
+
+	#[test]
+	fn prefix_literal() {
+		const USER: Prefix<'static> = prefix!("user", crate = "reexport_facade::strong_id");
+		const PLAIN: Option<Prefix<'static>> =
+			optional_prefix!("", crate = reexport_facade::strong_id);
+
+		let id = DynamicStrongId::<u16>::new(USER, 3203).unwrap();
+		assert_eq!(id.to_string(), "user_0343");
+		assert_eq!(PLAIN, None);
+	}
+}
@@ -0,0 +1,24 @@
+use strong_id::{strong_id, StrongId};
+
+strong_id!(pub struct UserId(u64 => "user"));
+
+/// Display and parse an ID so that both the encode and decode paths end up in the bundle
+#[no_mangle]
+pub extern "C" fn roundtrip(value: u64) -> u64 {
+	UserId::from(value)
+		.to_string()
+		.parse::<UserId>()
+		.map(|id| *id.id())
+		.unwrap_or_default()
+}
+
+/// Render a parse error so that error formatting ends up in the bundle
+#[no_mangle]
+pub extern "C" fn error_len(value: u64) -> usize {
+	let mut id = UserId::from(value).to_string();
+	id.truncate(8);
+	match id.parse::<UserId>() {
+		Ok(_) => 0,
+		Err(error) => error.to_string().len(),
+	}
+}